@@ -44,4 +44,71 @@ pub enum SundialError {
     InvalidTokenProgram,
     InvalidPortLendingProgram,
     InvalidSundialCollateralConfig,
+    #[msg("Not enough Port LP to recombine that many tokens")]
+    InvalidRecombineAmount,
+
+    //325
+    InvalidSundialAmmConfig,
+    #[msg("Not enough liquidity in the amm")]
+    InsufficientAmmLiquidity,
+    #[msg("Slippage tolerance exceeded")]
+    AmmSlippageExceeded,
+    #[msg("Only the share of Port LP redeemed so far can be claimed")]
+    ExceedRedeemedLiquidity,
+    #[msg("Can only roll over into a later Sundial on the same reserve and oracle")]
+    InvalidRolloverSundial,
+
+    //330
+    #[msg("Only reducing positions is allowed at the moment")]
+    ReduceOnly,
+    #[msg("Paused")]
+    Paused,
+    #[msg("Only the proposed owner can accept the Sundial Market")]
+    InvalidPendingOwner,
+    #[msg("Config change delay can only be increased, up to the maximum delay")]
+    InvalidConfigChangeDelay,
+    #[msg("The queued config change can't be executed yet")]
+    ConfigChangeNotReady,
+
+    //335
+    #[msg("Stale tolerance is out of bounds")]
+    InvalidStaleTolerance,
+    #[msg("Oracle price is not trading")]
+    OraclePriceNotTrading,
+    #[msg("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Primary and fallback oracle prices deviate too much")]
+    OracleDeviationTooLarge,
+
+    //340
+    #[msg("Account has an outdated version, migrate it first")]
+    AccountNotMigrated,
+    #[msg("This account can't be migrated")]
+    InvalidAccountMigration,
+    #[msg("Sundial Profile has no free slot left, resize it first")]
+    ProfileCapacityExceeded,
+    #[msg("Sundial Profile capacity can only grow")]
+    InvalidProfileCapacity,
+    #[msg("Collateral can only move to another profile of the same user and market")]
+    InvalidCollateralTransfer,
+
+    //345
+    #[msg("Sundial Profile still has collaterals or loans")]
+    ProfileNotEmpty,
+    #[msg("Sundial still has principal or yield tokens outstanding")]
+    SundialNotEmpty,
+    #[msg("Invalid Profile Delegate")]
+    InvalidProfileDelegate,
+    #[msg("Profile Delegate is not allowed to do this")]
+    ProfileDelegateNotPermitted,
+    #[msg("Profile Delegate has expired")]
+    ProfileDelegateExpired,
+
+    //350
+    #[msg("Sundial Profile still has delegates")]
+    ProfileHasDelegates,
+    #[msg("Already registered in the Sundial Market Registry")]
+    AlreadyRegistered,
+    #[msg("Yield Position doesn't hold that many yield tokens")]
+    InvalidYieldPositionAmount,
 }
@@ -1,15 +1,18 @@
 use crate::error::SundialError;
+use crate::state::{ProfileSlot, ProfileSlots, SundialOracleConfig};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{MintTo, Transfer};
-use pyth_client::PriceType;
 use pyth_client::{cast, Price};
-use solana_maths::{Decimal, TryDiv, TryMul};
+use pyth_client::{PriceStatus, PriceType};
+use solana_maths::{Decimal, TryDiv, TryMul, U192};
 
 use vipers::unwrap_int;
 use vipers::VipersError;
 
-pub const SUNDIAL_COLLATERAL_STALE_TOL: u64 = 10; //The collateral (port lp) token price would be invalid after 10 slots
-pub const SUNDIAL_PROFILE_STALE_TOL: u64 = 10; //The asset infos in sundial profile would be invalid after 10 slots
+pub const DEFAULT_STALE_TOL: u64 = 10; //Prices and sundial profiles are invalid after 10 slots unless configured otherwise
+pub const MAX_STALE_TOL: u64 = 150; //Configured tolerances can't be longer than 150 slots
+pub const COLLATERAL_EMA_HALF_LIFE: i64 = 20 * 60; //Collateral ema prices close half the gap to the spot price every 20 minutes
+pub const MAX_CONFIG_CHANGE_DELAY: i64 = 30 * 24 * 60 * 60; //Queued config changes can't be delayed more than 30 days
 macro_rules! seeds {
     ($ctx:ident, $account: ident, $bump_name: ident) => {
         paste! {  &[&[
@@ -21,9 +24,16 @@ macro_rules! seeds {
     };
 }
 
-pub fn price_per_lamport(price: Decimal, decimals: u8) -> Result<Decimal, ProgramError> {
+/// Value of `amount` lamports of a token with `decimals` priced at `price` per whole token.
+pub fn token_value(price: Decimal, amount: u64, decimals: u8) -> Result<Decimal, ProgramError> {
     let divisor = unwrap_int!(10u64.checked_pow(decimals as u32));
-    price.try_div(divisor)
+    price.try_mul(amount)?.try_div(divisor)
+}
+
+/// Lamports of a token with `decimals` priced at `price` per whole token worth `value`.
+pub fn token_amount(price: Decimal, value: Decimal, decimals: u8) -> Result<Decimal, ProgramError> {
+    let multiplier = unwrap_int!(10u64.checked_pow(decimals as u32));
+    value.try_mul(multiplier)?.try_div(price)
 }
 
 #[inline(always)]
@@ -88,9 +98,12 @@ macro_rules! log_then_prop_err {
     };
 }
 
-pub fn get_pyth_oracle_price(oracle: &AccountInfo, clock: &Clock) -> Result<Decimal, ProgramError> {
-    const STALE_AFTER_SLOTS_ELAPSED: u64 = 10;
-
+pub fn get_pyth_oracle_price(
+    oracle: &AccountInfo,
+    clock: &Clock,
+    stale_tol: u64,
+    oracle_config: &SundialOracleConfig,
+) -> Result<Decimal, ProgramError> {
     let pyth_data = oracle.try_borrow_data()?;
     let pyth_price = cast::<Price>(&pyth_data);
     vipers::invariant!(
@@ -102,7 +115,7 @@ pub fn get_pyth_oracle_price(oracle: &AccountInfo, clock: &Clock) -> Result<Deci
     let slots_elapsed = unwrap_int!(clock.slot.checked_sub(pyth_price.valid_slot));
 
     vipers::invariant!(
-        slots_elapsed <= STALE_AFTER_SLOTS_ELAPSED,
+        slots_elapsed <= stale_tol,
         SundialError::InvalidOracleConfig,
         &format!(
             "Oracle price is stale, current {:?}, last_update {:?}:",
@@ -110,10 +123,37 @@ pub fn get_pyth_oracle_price(oracle: &AccountInfo, clock: &Clock) -> Result<Deci
         )
     );
 
+    vipers::invariant!(
+        matches!(pyth_price.agg.status, PriceStatus::Trading),
+        SundialError::OraclePriceNotTrading,
+        &format!("Oracle price status is {:?}", pyth_price.agg.status)
+    );
+
     let price: u64 = pyth_price.agg.price.try_into().map_err(|_| {
         msg!("Oracle price cannot be negative {:?}", pyth_price.agg.price);
         SundialError::InvalidOracleConfig
     })?;
+    let conf = pyth_price.agg.conf;
+
+    if oracle_config.max_confidence_bips != 0 {
+        let max_conf = unwrap_int!((price as u128)
+            .checked_mul(oracle_config.max_confidence_bips as u128)
+            .and_then(|c| c.checked_div(10000)));
+        vipers::invariant!(
+            conf as u128 <= max_conf,
+            SundialError::OracleConfidenceTooWide,
+            &format!(
+                "Oracle confidence {:?} of price {:?} is too wide",
+                conf, price
+            )
+        );
+    }
+
+    let price = if oracle_config.use_upper_bound_price {
+        unwrap_int!(price.checked_add(conf))
+    } else {
+        price
+    };
 
     let market_price = if pyth_price.expo >= 0 {
         let exponent: u32 = log_then_prop_err!(pyth_price
@@ -133,13 +173,117 @@ pub fn get_pyth_oracle_price(oracle: &AccountInfo, clock: &Clock) -> Result<Deci
     Ok(market_price)
 }
 
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+const LN_2_WAD: u128 = 693_147_180_559_945_309;
+
+/// Binary logarithm of a positive [Decimal], scaled by WAD.
+/// The integer part comes from the bit length and the fraction is found by repeated squaring.
+pub fn log2_wad(x: Decimal) -> Result<i128, ProgramError> {
+    let wad = U192::from(WAD);
+    let two_wad = U192::from(2 * WAD);
+    let mut raw = x.0;
+    vipers::invariant!(!raw.is_zero(), VipersError::IntegerOverflow, "log2 of zero");
+
+    let mut integer_part = raw.bits() as i128 - wad.bits() as i128;
+    raw = if integer_part >= 0 {
+        raw >> integer_part as usize
+    } else {
+        raw << unwrap_int!(integer_part.checked_neg()) as usize
+    };
+    while raw >= two_wad {
+        raw = raw >> 1;
+        integer_part += 1;
+    }
+    while raw < wad {
+        raw = raw << 1;
+        integer_part -= 1;
+    }
+
+    // raw is in [1, 2) now, every squaring that reaches 2 sets the next fraction bit.
+    let mut y = raw.as_u128();
+    let mut fraction = 0u128;
+    let mut delta = WAD;
+    while delta > 0 {
+        delta >>= 1;
+        y = unwrap_int!(y.checked_mul(y)) / WAD;
+        if y >= 2 * WAD {
+            y >>= 1;
+            fraction += delta;
+        }
+    }
+
+    Ok(unwrap_int!(integer_part
+        .checked_mul(WAD as i128)
+        .and_then(|i| i.checked_add(fraction as i128))))
+}
+
+/// 2 to the power of a WAD scaled exponent. The fractional part is evaluated as e^(f * ln2) with
+/// its Taylor series, results below the [Decimal] precision are rounded to zero.
+pub fn exp2_wad(exponent: i128) -> Result<Decimal, ProgramError> {
+    let wad = WAD as i128;
+    let mut integer_part = exponent / wad;
+    let mut fraction = exponent % wad;
+    if fraction < 0 {
+        fraction += wad;
+        integer_part -= 1;
+    }
+
+    let x = fraction as u128 * LN_2_WAD / WAD;
+    let mut term = WAD;
+    let mut sum = WAD;
+    let mut k = 1u128;
+    while term > 0 {
+        term = term * x / WAD / k;
+        sum += term;
+        k += 1;
+    }
+
+    let sum = U192::from(sum);
+    if integer_part >= 0 {
+        vipers::invariant!(
+            (integer_part as usize) + sum.bits() < 192,
+            VipersError::IntegerOverflow,
+            "exp2 overflow"
+        );
+        Ok(Decimal(sum << integer_part as usize))
+    } else if integer_part <= -192 {
+        Ok(Decimal::zero())
+    } else {
+        Ok(Decimal(
+            sum >> unwrap_int!(integer_part.checked_neg()) as usize,
+        ))
+    }
+}
+
+/// `base^exponent` for a non negative [Decimal] exponent, computed as 2^(exponent * log2(base)).
+pub fn decimal_pow(base: Decimal, exponent: Decimal) -> Result<Decimal, ProgramError> {
+    if exponent == Decimal::zero() {
+        return Ok(Decimal::one());
+    }
+    if base == Decimal::zero() {
+        return Ok(Decimal::zero());
+    }
+    let log = log2_wad(base)?;
+    let product = unwrap_int!(U192::from(log.unsigned_abs())
+        .checked_mul(exponent.0)
+        .map(|p| p / U192::from(WAD)));
+    vipers::invariant!(
+        product.bits() < 127,
+        VipersError::IntegerOverflow,
+        "pow overflow"
+    );
+    let product = product.as_u128() as i128;
+    exp2_wad(if log < 0 { -product } else { product })
+}
+
 pub fn update_or_insert<T, F, M, D>(
-    elems: &mut Vec<T>,
+    elems: &mut ProfileSlots<T>,
     check: F,
     mutate: M,
     default: D,
 ) -> ProgramResult
 where
+    T: ProfileSlot,
     F: Fn(&&mut T) -> bool,
     M: Fn(&mut T) -> ProgramResult,
     D: Fn() -> Result<T, ProgramError>,
@@ -148,8 +292,7 @@ where
     if let Some(elem) = iter.find(check) {
         mutate(elem)
     } else {
-        elems.push(default()?);
-        Ok(())
+        elems.push(default()?)
     }
 }
 
@@ -176,6 +319,14 @@ pub trait CheckSundialAlreadyEnd {
     fn check_sundial_already_end(&self) -> ProgramResult;
 }
 
+pub trait CheckSundialActive {
+    fn check_sundial_active(&self) -> ProgramResult;
+}
+
+pub trait CheckSundialNotPaused {
+    fn check_sundial_not_paused(&self) -> ProgramResult;
+}
+
 macro_rules! get_raw_from_uint {
     ($x: expr) => {
         $x.0 .0
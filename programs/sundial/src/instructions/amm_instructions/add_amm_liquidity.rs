@@ -0,0 +1,186 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{Sundial, SundialAmm, SundialMarket};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, transfer, Mint, Token, TokenAccount, Transfer};
+use paste::paste;
+use solana_maths::{Decimal, TryDiv, TryMul};
+use sundial_derives::{validates, CheckSundialNotEnd, CheckSundialNotPaused};
+
+/// Deposit liquidity and principal tokens into the [SundialAmm] in proportion to its reserves.
+/// The first deposit sets the initial rate, so it needs at least as many principal tokens as liquidity.
+#[validates(check_sundial_not_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialNotPaused)]
+#[instruction(liquidity_amount: u64, max_principle_amount: u64)]
+pub struct AddAmmLiquidity<'info> {
+    #[account(mut, has_one = sundial)]
+    pub sundial_amm: Box<Account<'info, SundialAmm>>,
+
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds=[
+            sundial_amm.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial_amm.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [SundialAmm].
+    pub sundial_amm_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"lp_mint"
+        ],
+        bump = sundial_amm.bumps.lp_mint_bump
+    )]
+    pub amm_lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial_amm.bumps.liquidity_bump
+    )]
+    pub amm_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"principle"
+        ],
+        bump = sundial_amm.bumps.principle_bump
+    )]
+    pub amm_principle_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_amm_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user_authority: Signer<'info>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_add_amm_liquidity(
+    ctx: Context<AddAmmLiquidity>,
+    liquidity_amount: u64,
+    max_principle_amount: u64,
+) -> ProgramResult {
+    let lp_supply = ctx.accounts.amm_lp_mint.supply;
+    let liquidity_reserve = ctx.accounts.amm_liquidity_wallet.amount;
+    let principle_reserve = ctx.accounts.amm_principle_wallet.amount;
+
+    let (principle_amount, lp_amount) = if lp_supply == 0 {
+        vipers::invariant!(
+            max_principle_amount >= liquidity_amount,
+            SundialError::InvalidSundialAmmConfig,
+            "Initial principal amount should not be less than the liquidity amount"
+        );
+        (max_principle_amount, liquidity_amount)
+    } else {
+        vipers::invariant!(
+            liquidity_reserve != 0,
+            SundialError::InsufficientAmmLiquidity
+        );
+        let principle_amount = log_then_prop_err!(Decimal::from(liquidity_amount)
+            .try_mul(principle_reserve)
+            .and_then(|d| d.try_div(liquidity_reserve))
+            .and_then(|d| d.try_ceil_u64()));
+        let lp_amount = log_then_prop_err!(Decimal::from(liquidity_amount)
+            .try_mul(lp_supply)
+            .and_then(|d| d.try_div(liquidity_reserve))
+            .and_then(|d| d.try_floor_u64()));
+        vipers::invariant!(
+            principle_amount <= max_principle_amount,
+            SundialError::AmmSlippageExceeded,
+            &format!(
+                "Need {:?} principal tokens, max {:?}",
+                principle_amount, max_principle_amount
+            )
+        );
+        (principle_amount, lp_amount)
+    };
+    vipers::invariant!(lp_amount != 0, SundialError::InvalidMintAmount);
+
+    log_then_prop_err!(transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_liquidity_wallet.to_account_info(),
+                to: ctx.accounts.amm_liquidity_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        liquidity_amount,
+    ));
+
+    log_then_prop_err!(transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_principle_token_wallet.to_account_info(),
+                to: ctx.accounts.amm_principle_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        principle_amount,
+    ));
+
+    log_then_prop_err!(mint_to(
+        create_mint_to_cpi(
+            ctx.accounts.amm_lp_mint.to_account_info(),
+            ctx.accounts.user_amm_lp_wallet.to_account_info(),
+            ctx.accounts.sundial_amm_authority.to_account_info(),
+            seeds!(ctx, sundial_amm, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        lp_amount,
+    ));
+
+    log_then_prop_err!(ctx.accounts.amm_liquidity_wallet.reload());
+    log_then_prop_err!(ctx.accounts.amm_principle_wallet.reload());
+    log_then_prop_err!(ctx.accounts.sundial_amm.update_implied_apy(
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+    ));
+
+    emit!(AddAmmLiquidityEvent {
+        sundial_amm: ctx.accounts.sundial_amm.key(),
+        liquidity_amount,
+        principle_amount,
+        lp_minted: lp_amount,
+        implied_apy: ctx.accounts.sundial_amm.implied_apy,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::add_amm_liquidity].
+pub struct AddAmmLiquidityEvent {
+    /// The [SundialAmm].
+    #[index]
+    pub sundial_amm: Pubkey,
+    /// Liquidity deposited.
+    pub liquidity_amount: u64,
+    /// Principal tokens deposited.
+    pub principle_amount: u64,
+    /// Amm LP tokens minted to the user.
+    pub lp_minted: u64,
+    /// The implied apy after the deposit.
+    pub implied_apy: [u64; 3],
+}
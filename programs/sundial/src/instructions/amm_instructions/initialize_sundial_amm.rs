@@ -0,0 +1,164 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{Fee, Sundial, SundialAmm, SundialAmmBumps, SundialAmmConfig, SundialMarket};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use sundial_derives::{validates, CheckSundialActive, CheckSundialNotEnd, CheckSundialOwner};
+use vipers::unwrap_int;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SundialAmmConfigParams {
+    pub swap_fee: u8,
+    pub time_scale_in_seconds: u64,
+}
+
+impl From<SundialAmmConfigParams> for SundialAmmConfig {
+    fn from(config: SundialAmmConfigParams) -> Self {
+        SundialAmmConfig {
+            swap_fee: Fee {
+                bips: config.swap_fee,
+            },
+            time_scale_in_seconds: config.time_scale_in_seconds,
+        }
+    }
+}
+
+/// Create the [SundialAmm] that trades the principal tokens of a [Sundial] against its liquidity.
+#[validates(check_sundial_owner, check_sundial_not_end, check_sundial_active)]
+#[derive(Accounts, CheckSundialOwner, CheckSundialNotEnd, CheckSundialActive)]
+#[sundial_role(listing_admin)]
+#[instruction(bumps: SundialAmmBumps, config: SundialAmmConfigParams, pda_bump: u8)]
+pub struct InitializeSundialAmm<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial.key().as_ref(),
+            b"amm"
+        ],
+        bump = pda_bump
+    )]
+    pub sundial_amm: Box<Account<'info, SundialAmm>>,
+
+    #[account(
+        seeds=[
+            sundial_amm.key().as_ref(),
+            b"authority"
+        ],
+        bump = bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [SundialAmm].
+    pub sundial_amm_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"lp_mint"
+        ],
+        bump = bumps.lp_mint_bump,
+        mint::authority = sundial_amm_authority,
+        mint::decimals = liquidity_mint.decimals
+    )]
+    pub amm_lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = bumps.liquidity_bump,
+        token::authority = sundial_amm_authority,
+        token::mint = liquidity_mint
+    )]
+    pub amm_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"principle"
+        ],
+        bump = bumps.principle_bump,
+        token::authority = sundial_amm_authority,
+        token::mint = principle_token_mint
+    )]
+    pub amm_principle_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = sundial_port_liquidity_wallet.mint @ SundialError::InvalidPortLiquidityMint)]
+    pub liquidity_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_initialize_sundial_amm(
+    ctx: Context<InitializeSundialAmm>,
+    bumps: SundialAmmBumps,
+    config: SundialAmmConfigParams,
+    _pda_bump: u8,
+) -> ProgramResult {
+    let config: SundialAmmConfig = config.into();
+    let seconds_to_end = unwrap_int!(ctx
+        .accounts
+        .sundial
+        .end_unix_time_stamp
+        .checked_sub(ctx.accounts.clock.unix_timestamp));
+    log_then_prop_err!(config.sanity_check(seconds_to_end));
+
+    let sundial_amm = &mut ctx.accounts.sundial_amm;
+    sundial_amm.bumps = bumps;
+    sundial_amm.sundial = ctx.accounts.sundial.key();
+    sundial_amm.sundial_market = ctx.accounts.sundial_market.key();
+    sundial_amm.config = config;
+
+    emit!(InitializeSundialAmmEvent {
+        sundial_amm: sundial_amm.key(),
+        sundial: sundial_amm.sundial,
+        config: sundial_amm.config.clone(),
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::initialize_sundial_amm].
+pub struct InitializeSundialAmmEvent {
+    /// The [SundialAmm].
+    #[index]
+    pub sundial_amm: Pubkey,
+    /// The [Sundial] it trades.
+    pub sundial: Pubkey,
+    /// The [SundialAmmConfig].
+    pub config: SundialAmmConfig,
+}
@@ -0,0 +1,9 @@
+mod add_amm_liquidity;
+mod initialize_sundial_amm;
+mod remove_amm_liquidity;
+mod swap_amm;
+
+pub use add_amm_liquidity::*;
+pub use initialize_sundial_amm::*;
+pub use remove_amm_liquidity::*;
+pub use swap_amm::*;
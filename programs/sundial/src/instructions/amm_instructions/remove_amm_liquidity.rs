@@ -0,0 +1,160 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{Sundial, SundialAmm, SundialMarket};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount};
+use paste::paste;
+use solana_maths::{Decimal, TryDiv, TryMul};
+use sundial_derives::{validates, CheckSundialNotPaused};
+
+/// Burn amm LP tokens for the pro-rata share of both reserves. Works after the [Sundial] ends,
+/// the principal tokens withdrawn can then be redeemed at par.
+#[validates(check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotPaused)]
+#[instruction(lp_amount: u64)]
+pub struct RemoveAmmLiquidity<'info> {
+    #[account(mut, has_one = sundial)]
+    pub sundial_amm: Box<Account<'info, SundialAmm>>,
+
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds=[
+            sundial_amm.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial_amm.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [SundialAmm].
+    pub sundial_amm_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"lp_mint"
+        ],
+        bump = sundial_amm.bumps.lp_mint_bump
+    )]
+    pub amm_lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial_amm.bumps.liquidity_bump
+    )]
+    pub amm_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"principle"
+        ],
+        bump = sundial_amm.bumps.principle_bump
+    )]
+    pub amm_principle_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_amm_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user_authority: Signer<'info>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn process_remove_amm_liquidity(
+    ctx: Context<RemoveAmmLiquidity>,
+    lp_amount: u64,
+) -> ProgramResult {
+    let lp_supply = ctx.accounts.amm_lp_mint.supply;
+    vipers::invariant!(
+        lp_amount != 0 && lp_amount <= lp_supply,
+        SundialError::InsufficientAmmLiquidity
+    );
+    let liquidity_amount = log_then_prop_err!(Decimal::from(lp_amount)
+        .try_mul(ctx.accounts.amm_liquidity_wallet.amount)
+        .and_then(|d| d.try_div(lp_supply))
+        .and_then(|d| d.try_floor_u64()));
+    let principle_amount = log_then_prop_err!(Decimal::from(lp_amount)
+        .try_mul(ctx.accounts.amm_principle_wallet.amount)
+        .and_then(|d| d.try_div(lp_supply))
+        .and_then(|d| d.try_floor_u64()));
+
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.amm_lp_mint.to_account_info(),
+                to: ctx.accounts.user_amm_lp_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        lp_amount,
+    ));
+
+    log_then_prop_err!(transfer(
+        create_transfer_cpi(
+            ctx.accounts.amm_liquidity_wallet.to_account_info(),
+            ctx.accounts.user_liquidity_wallet.to_account_info(),
+            ctx.accounts.sundial_amm_authority.to_account_info(),
+            seeds!(ctx, sundial_amm, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        liquidity_amount,
+    ));
+
+    log_then_prop_err!(transfer(
+        create_transfer_cpi(
+            ctx.accounts.amm_principle_wallet.to_account_info(),
+            ctx.accounts.user_principle_token_wallet.to_account_info(),
+            ctx.accounts.sundial_amm_authority.to_account_info(),
+            seeds!(ctx, sundial_amm, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        principle_amount,
+    ));
+
+    log_then_prop_err!(ctx.accounts.amm_liquidity_wallet.reload());
+    log_then_prop_err!(ctx.accounts.amm_principle_wallet.reload());
+    log_then_prop_err!(ctx.accounts.sundial_amm.update_implied_apy(
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+    ));
+
+    emit!(RemoveAmmLiquidityEvent {
+        sundial_amm: ctx.accounts.sundial_amm.key(),
+        liquidity_amount,
+        principle_amount,
+        lp_burned: lp_amount,
+        implied_apy: ctx.accounts.sundial_amm.implied_apy,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::remove_amm_liquidity].
+pub struct RemoveAmmLiquidityEvent {
+    /// The [SundialAmm].
+    #[index]
+    pub sundial_amm: Pubkey,
+    /// Liquidity withdrawn.
+    pub liquidity_amount: u64,
+    /// Principal tokens withdrawn.
+    pub principle_amount: u64,
+    /// Amm LP tokens burned.
+    pub lp_burned: u64,
+    /// The implied apy after the withdrawal.
+    pub implied_apy: [u64; 3],
+}
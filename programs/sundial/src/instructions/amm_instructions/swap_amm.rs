@@ -0,0 +1,153 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{Sundial, SundialAmm, SundialMarket, SwapDirection};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+use paste::paste;
+use sundial_derives::{validates, CheckSundialNotEnd, CheckSundialNotPaused};
+
+/// Swap principal tokens for liquidity or the other way around along the yield space curve of the [SundialAmm].
+#[validates(check_sundial_not_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialNotPaused)]
+#[instruction(amount_in: u64, min_amount_out: u64, direction: SwapDirection)]
+pub struct SwapAmm<'info> {
+    #[account(mut, has_one = sundial)]
+    pub sundial_amm: Box<Account<'info, SundialAmm>>,
+
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds=[
+            sundial_amm.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial_amm.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [SundialAmm].
+    pub sundial_amm_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial_amm.bumps.liquidity_bump
+    )]
+    pub amm_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"principle"
+        ],
+        bump = sundial_amm.bumps.principle_bump
+    )]
+    pub amm_principle_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user_authority: Signer<'info>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_swap_amm(
+    ctx: Context<SwapAmm>,
+    amount_in: u64,
+    min_amount_out: u64,
+    direction: SwapDirection,
+) -> ProgramResult {
+    let amount_out = log_then_prop_err!(ctx.accounts.sundial_amm.get_swap_amount_out(
+        &ctx.accounts.sundial,
+        &ctx.accounts.clock,
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+        amount_in,
+        direction,
+    ));
+    vipers::invariant!(
+        amount_out >= min_amount_out,
+        SundialError::AmmSlippageExceeded,
+        &format!("Amount out {:?}, min {:?}", amount_out, min_amount_out)
+    );
+
+    let (user_in_wallet, amm_in_wallet, amm_out_wallet, user_out_wallet) = match direction {
+        SwapDirection::PrincipleToLiquidity => (
+            &ctx.accounts.user_principle_token_wallet,
+            &ctx.accounts.amm_principle_wallet,
+            &ctx.accounts.amm_liquidity_wallet,
+            &ctx.accounts.user_liquidity_wallet,
+        ),
+        SwapDirection::LiquidityToPrinciple => (
+            &ctx.accounts.user_liquidity_wallet,
+            &ctx.accounts.amm_liquidity_wallet,
+            &ctx.accounts.amm_principle_wallet,
+            &ctx.accounts.user_principle_token_wallet,
+        ),
+    };
+
+    log_then_prop_err!(transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: user_in_wallet.to_account_info(),
+                to: amm_in_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        amount_in,
+    ));
+
+    log_then_prop_err!(transfer(
+        create_transfer_cpi(
+            amm_out_wallet.to_account_info(),
+            user_out_wallet.to_account_info(),
+            ctx.accounts.sundial_amm_authority.to_account_info(),
+            seeds!(ctx, sundial_amm, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        amount_out,
+    ));
+
+    log_then_prop_err!(ctx.accounts.amm_liquidity_wallet.reload());
+    log_then_prop_err!(ctx.accounts.amm_principle_wallet.reload());
+    log_then_prop_err!(ctx.accounts.sundial_amm.update_implied_apy(
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+    ));
+
+    emit!(SwapAmmEvent {
+        sundial_amm: ctx.accounts.sundial_amm.key(),
+        direction,
+        amount_in,
+        amount_out,
+        implied_apy: ctx.accounts.sundial_amm.implied_apy,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::swap_amm].
+pub struct SwapAmmEvent {
+    /// The [SundialAmm].
+    #[index]
+    pub sundial_amm: Pubkey,
+    /// Which token was sold.
+    pub direction: SwapDirection,
+    /// Tokens paid by the user, including the fee.
+    pub amount_in: u64,
+    /// Tokens received by the user.
+    pub amount_out: u64,
+    /// The implied apy after the swap.
+    pub implied_apy: [u64; 3],
+}
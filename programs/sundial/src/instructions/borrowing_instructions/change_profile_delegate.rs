@@ -0,0 +1,49 @@
+use crate::error::SundialError;
+use crate::instructions::ProfileDelegateEvent;
+use crate::state::{ProfileDelegate, ProfileDelegatePermissions, SundialProfile};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+use vipers::invariant;
+
+/// Change the permissions and expiry of a [ProfileDelegate], e.g., to renew it.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction(permissions: ProfileDelegatePermissions, expiry_unix_timestamp: i64)]
+pub struct ChangeProfileDelegate<'info> {
+    #[account(
+        has_one = user @ SundialError::InvalidProfileUser
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    #[account(
+        mut,
+        has_one = sundial_profile @ SundialError::InvalidProfileDelegate
+    )]
+    pub profile_delegate: Account<'info, ProfileDelegate>,
+
+    pub user: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_change_profile_delegate(
+    ctx: Context<ChangeProfileDelegate>,
+    permissions: ProfileDelegatePermissions,
+    expiry_unix_timestamp: i64,
+) -> ProgramResult {
+    invariant!(
+        expiry_unix_timestamp > ctx.accounts.clock.unix_timestamp,
+        SundialError::ProfileDelegateExpired
+    );
+
+    let profile_delegate = &mut ctx.accounts.profile_delegate;
+    profile_delegate.permissions = permissions;
+    profile_delegate.expiry_unix_timestamp = expiry_unix_timestamp;
+
+    emit!(ProfileDelegateEvent {
+        profile: ctx.accounts.sundial_profile.key(),
+        delegate: profile_delegate.delegate,
+        permissions,
+        expiry_unix_timestamp,
+    });
+    Ok(())
+}
@@ -1,44 +1,90 @@
 use crate::helpers::*;
-use crate::instructions::SundialCollateralConfigParams;
+use crate::instructions::{QueueConfigChangeEvent, SundialCollateralConfigParams};
 
-use crate::state::{LiquidationConfig, LiquidityCap, SundialCollateral, SundialMarket, LTV};
+use crate::state::{
+    LiquidationConfig, LiquidityCap, PendingConfigChange, RegistryEntryKind, SundialCollateral,
+    SundialCollateralConfig, SundialMarket, LTV,
+};
 use anchor_lang::prelude::*;
 
 use crate::error::SundialError;
 
 use sundial_derives::{validates, CheckSundialOwner};
+use vipers::unwrap_int;
 
+/// Queue a new [SundialCollateralConfig], applied by [sundial::execute_config_change] once the
+/// `config_change_delay` of the [SundialMarket] has passed. The LTV and liquidation threshold
+/// then ramp to the new values over `ramp_duration_in_seconds`.
 #[validates(check_sundial_owner)]
 #[derive(Accounts, Clone, CheckSundialOwner)]
-#[instruction(config: SundialCollateralConfigParams)]
+#[sundial_role(risk_admin)]
+#[instruction(config: SundialCollateralConfigParams, bump: u8)]
 pub struct ChangeSundialCollateralConfig<'info> {
-    #[account[mut]]
     pub sundial_collateral: Account<'info, SundialCollateral>,
     #[account(mut)]
     pub owner: Signer<'info>,
     pub sundial_market: Box<Account<'info, SundialMarket>>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_collateral.key().as_ref(),
+            b"pending_config"
+        ],
+        bump = bump
+    )]
+    pub pending_config_change: Box<Account<'info, PendingConfigChange>>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn process_change_sundial_collateral_config(
     ctx: Context<ChangeSundialCollateralConfig>,
     config: SundialCollateralConfigParams,
+    _bump: u8,
+    ramp_duration_in_seconds: i64,
 ) -> ProgramResult {
-    ctx.accounts
-        .sundial_collateral
-        .sundial_collateral_config
-        .ltv = LTV { ltv: config.ltv };
-    ctx.accounts
-        .sundial_collateral
-        .sundial_collateral_config
-        .liquidation_config = LiquidationConfig {
-        liquidation_threshold: config.liquidation_threshold,
-        liquidation_penalty: config.liquidation_penalty,
-    };
-    ctx.accounts
-        .sundial_collateral
-        .sundial_collateral_config
-        .liquidity_cap = LiquidityCap {
-        lamports: config.liquidity_cap,
+    vipers::invariant!(
+        ramp_duration_in_seconds >= 0,
+        SundialError::InvalidSundialCollateralConfig,
+        "Ramp duration can't be negative"
+    );
+    let new_config = SundialCollateralConfig {
+        ltv: LTV { ltv: config.ltv },
+        liquidation_config: LiquidationConfig {
+            liquidation_threshold: config.liquidation_threshold,
+            liquidation_penalty: config.liquidation_penalty,
+        },
+        liquidity_cap: LiquidityCap {
+            lamports: config.liquidity_cap,
+        },
+        ..ctx
+            .accounts
+            .sundial_collateral
+            .sundial_collateral_config
+            .clone()
     };
+    log_then_prop_err!(new_config.sanity_check());
+
+    let pending = &mut ctx.accounts.pending_config_change;
+    pending.sundial_market = ctx.accounts.sundial_market.key();
+    pending.target = ctx.accounts.sundial_collateral.key();
+    pending.payer = ctx.accounts.owner.key();
+    pending.kind = RegistryEntryKind::SundialCollateral;
+    pending.sundial_collateral_config = new_config;
+    pending.ramp_duration_in_seconds = ramp_duration_in_seconds;
+    pending.queued_unix_timestamp = ctx.accounts.clock.unix_timestamp;
+    pending.execute_after_unix_timestamp = unwrap_int!(ctx
+        .accounts
+        .clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.sundial_market.config_change_delay));
+
+    emit!(QueueConfigChangeEvent {
+        pending_config_change: pending.key(),
+        target: pending.target,
+        kind: pending.kind,
+        execute_after_unix_timestamp: pending.execute_after_unix_timestamp,
+    });
     Ok(())
 }
@@ -0,0 +1,67 @@
+use crate::helpers::*;
+use crate::oracle::OracleSource;
+use crate::state::{SundialCollateral, SundialCollateralPriceCheck, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SundialCollateralPriceCheckParams {
+    pub oracle: Pubkey,
+    pub source: OracleSource,
+    pub max_deviation_bips: u16,
+}
+
+impl From<SundialCollateralPriceCheckParams> for SundialCollateralPriceCheck {
+    fn from(config: SundialCollateralPriceCheckParams) -> Self {
+        SundialCollateralPriceCheck {
+            oracle: config.oracle,
+            source: config.source,
+            max_deviation_bips: config.max_deviation_bips,
+            ..SundialCollateralPriceCheck::default()
+        }
+    }
+}
+
+/// Change the independent oracle the Port reserve price of a [SundialCollateral] is checked
+/// against, the default key turns the check off. It takes effect immediately like the oracle
+/// config of a [Sundial].
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(risk_admin)]
+#[instruction(config: SundialCollateralPriceCheckParams)]
+pub struct ChangeSundialCollateralPriceCheck<'info> {
+    #[account(mut)]
+    pub sundial_collateral: Account<'info, SundialCollateral>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_collateral_price_check(
+    ctx: Context<ChangeSundialCollateralPriceCheck>,
+    config: SundialCollateralPriceCheckParams,
+) -> ProgramResult {
+    let price_check = SundialCollateralPriceCheck::from(config);
+    log_then_prop_err!(price_check.sanity_check());
+    emit!(ChangeSundialCollateralPriceCheckEvent {
+        sundial_collateral: ctx.accounts.sundial_collateral.key(),
+        oracle: price_check.oracle,
+        max_deviation_bips: price_check.max_deviation_bips,
+    });
+    ctx.accounts.sundial_collateral.price_check = price_check;
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_collateral_price_check].
+pub struct ChangeSundialCollateralPriceCheckEvent {
+    /// The [SundialCollateral].
+    #[index]
+    pub sundial_collateral: Pubkey,
+    /// New independent oracle.
+    pub oracle: Pubkey,
+    /// New max deviation in bips.
+    pub max_deviation_bips: u16,
+}
@@ -0,0 +1,43 @@
+use crate::helpers::*;
+use crate::state::{check_stale_tol, SundialCollateral, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+/// Override the collateral staleness tolerance of the [SundialMarket] for a [SundialCollateral],
+/// zero unsets it. Applies immediately, within [MAX_STALE_TOL].
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(risk_admin)]
+#[instruction(collateral_stale_tol: u64)]
+pub struct ChangeSundialCollateralStaleTol<'info> {
+    #[account(mut)]
+    pub sundial_collateral: Account<'info, SundialCollateral>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_collateral_stale_tol(
+    ctx: Context<ChangeSundialCollateralStaleTol>,
+    collateral_stale_tol: u64,
+) -> ProgramResult {
+    log_then_prop_err!(check_stale_tol(collateral_stale_tol));
+    ctx.accounts.sundial_collateral.collateral_stale_tol = collateral_stale_tol;
+    emit!(ChangeSundialCollateralStaleTolEvent {
+        sundial_collateral: ctx.accounts.sundial_collateral.key(),
+        collateral_stale_tol,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_collateral_stale_tol].
+pub struct ChangeSundialCollateralStaleTolEvent {
+    /// The [SundialCollateral].
+    #[index]
+    pub sundial_collateral: Pubkey,
+    /// New tolerance in slots.
+    pub collateral_stale_tol: u64,
+}
@@ -0,0 +1,47 @@
+use crate::helpers::*;
+use crate::state::{MarketStatus, SundialCollateral, SundialMarket, SundialMarketRegistryEntry};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+/// Change the status of a [SundialCollateral] and its [SundialMarketRegistryEntry].
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(pause_guardian)]
+#[instruction(status: MarketStatus)]
+pub struct ChangeSundialCollateralStatus<'info> {
+    #[account(mut)]
+    pub sundial_collateral: Account<'info, SundialCollateral>,
+    #[account(
+        mut,
+        constraint = registry_entry.account == sundial_collateral.key()
+    )]
+    pub registry_entry: Account<'info, SundialMarketRegistryEntry>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_collateral_status(
+    ctx: Context<ChangeSundialCollateralStatus>,
+    status: MarketStatus,
+) -> ProgramResult {
+    ctx.accounts.sundial_collateral.status = status;
+    ctx.accounts.registry_entry.status = status;
+    emit!(ChangeSundialCollateralStatusEvent {
+        sundial_collateral: ctx.accounts.sundial_collateral.key(),
+        status,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_collateral_status].
+pub struct ChangeSundialCollateralStatusEvent {
+    /// The [SundialCollateral].
+    #[index]
+    pub sundial_collateral: Pubkey,
+    /// New [MarketStatus].
+    pub status: MarketStatus,
+}
@@ -0,0 +1,43 @@
+use crate::error::SundialError;
+use crate::state::{ProfileDelegate, SundialProfile, SundialProfileMut};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+use vipers::unwrap_int;
+
+/// Revoke a [ProfileDelegate], its rent goes back to the user.
+#[validates()]
+#[derive(Accounts, Clone)]
+pub struct CloseProfileDelegate<'info> {
+    #[account(
+        mut,
+        has_one = sundial_profile @ SundialError::InvalidProfileDelegate,
+        has_one = user @ SundialError::InvalidProfileUser,
+        close = user
+    )]
+    pub profile_delegate: Account<'info, ProfileDelegate>,
+
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+pub fn process_close_profile_delegate(ctx: Context<CloseProfileDelegate>) -> ProgramResult {
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    profile.header.delegate_count = unwrap_int!(profile.header.delegate_count.checked_sub(1));
+
+    emit!(CloseProfileDelegateEvent {
+        profile: ctx.accounts.profile_delegate.sundial_profile,
+        delegate: ctx.accounts.profile_delegate.delegate,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct CloseProfileDelegateEvent {
+    #[index]
+    pub profile: Pubkey,
+    pub delegate: Pubkey,
+}
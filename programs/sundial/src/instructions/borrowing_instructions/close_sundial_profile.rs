@@ -0,0 +1,55 @@
+use crate::error::SundialError;
+use crate::state::{SundialProfile, SundialProfileMut};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+use vipers::invariant;
+
+/// Close a [SundialProfile] without any collateral, loan or delegate left, its rent goes to
+/// `receiver`. An empty profile has nothing at stake, so it can be closed whatever the status of
+/// its [SundialMarket](crate::state::SundialMarket).
+#[validates()]
+#[derive(Accounts, Clone)]
+pub struct CloseSundialProfile<'info> {
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser,
+        close = receiver
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    pub user: Signer<'info>,
+
+    /// CHECK: Receives the rent of the [SundialProfile].
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+pub fn process_close_sundial_profile(ctx: Context<CloseSundialProfile>) -> ProgramResult {
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let profile = SundialProfileMut::load(&profile_info)?;
+    invariant!(
+        profile.collaterals.is_empty() && profile.loans.is_empty(),
+        SundialError::ProfileNotEmpty,
+        &format!(
+            "Profile has {:?} collaterals and {:?} loans",
+            profile.collaterals.len(),
+            profile.loans.len()
+        )
+    );
+    profile.header.check_no_delegates()?;
+
+    emit!(CloseSundialProfileEvent {
+        profile: ctx.accounts.sundial_profile.key(),
+        user: ctx.accounts.user.key(),
+        receiver: ctx.accounts.receiver.key(),
+    });
+    Ok(())
+}
+
+#[event]
+pub struct CloseSundialProfileEvent {
+    #[index]
+    pub profile: Pubkey,
+    pub user: Pubkey,
+    pub receiver: Pubkey,
+}
@@ -1,9 +1,9 @@
 use crate::helpers::*;
-use crate::state::{SundialCollateral, SundialProfile};
+use crate::state::{SundialCollateral, SundialMarket, SundialProfile, SundialProfileMut};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialProfileMarket};
+use sundial_derives::{validates, CheckSundialActive, CheckSundialProfileMarket};
 
 use crate::helpers::{create_transfer_cpi, update_or_insert};
 use anchor_spl::token::transfer;
@@ -13,15 +13,12 @@ use crate::state::SundialProfileCollateral;
 
 /// Deposit Port LP tokens as collateral into sundial profile to gain borrowing power.
 /// [SundialProfile] should be created and initialized.
-#[validates(check_sundial_profile_market)]
-#[derive(Accounts, Clone, CheckSundialProfileMarket)]
+#[validates(check_sundial_profile_market, check_sundial_active)]
+#[derive(Accounts, Clone, CheckSundialProfileMarket, CheckSundialActive)]
 #[instruction(amount:u64)]
 pub struct DepositSundialCollateral<'info> {
-    #[account(
-        mut,
-        has_one = user @ SundialError::InvalidProfileUser
-    )]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(
         has_one = token_program @ SundialError::InvalidTokenProgram
@@ -40,15 +37,26 @@ pub struct DepositSundialCollateral<'info> {
 
     #[account(mut)]
     pub user_port_lp_wallet: Account<'info, TokenAccount>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
+    /// The profile user, or a [state::ProfileDelegate] passing its record as the last remaining
+    /// account.
     pub user: Signer<'info>,
     pub transfer_authority: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn process_deposit_sundial_collateral<'info>(
     ctx: Context<'_, '_, '_, 'info, DepositSundialCollateral<'info>>,
     amount: u64,
 ) -> ProgramResult {
+    ctx.accounts.sundial_profile.load()?.check_signer(
+        ctx.accounts.sundial_profile.key(),
+        ctx.accounts.user.key(),
+        ctx.remaining_accounts.last(),
+        |p| p.deposit,
+    )?;
+
     log_then_prop_err!(transfer(
         create_transfer_cpi(
             ctx.accounts.user_port_lp_wallet.to_account_info(),
@@ -62,7 +70,8 @@ pub fn process_deposit_sundial_collateral<'info>(
         amount
     ));
 
-    let sundial_profile = &mut ctx.accounts.sundial_profile;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut sundial_profile = SundialProfileMut::load(&profile_info)?;
 
     let liquidity_cap = ctx
         .accounts
@@ -77,7 +86,11 @@ pub fn process_deposit_sundial_collateral<'info>(
         &mut sundial_profile.collaterals,
         |c| c.sundial_collateral == ctx.accounts.sundial_collateral.key(),
         |c| c.asset.add_amount(amount),
-        || SundialProfileCollateral::init_collateral(amount, &ctx.accounts.sundial_collateral)
+        || SundialProfileCollateral::init_collateral(
+            amount,
+            &ctx.accounts.sundial_collateral,
+            &ctx.accounts.clock
+        )
     ));
 
     emit!(DepositSundialCollateralEvent {
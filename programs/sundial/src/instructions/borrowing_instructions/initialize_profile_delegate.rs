@@ -0,0 +1,85 @@
+use crate::error::SundialError;
+use crate::state::{
+    ProfileDelegate, ProfileDelegatePermissions, SundialProfile, SundialProfileMut,
+};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+use vipers::{invariant, unwrap_int};
+
+/// Let `delegate` sign the [SundialProfile] instructions picked by `permissions` instead of the
+/// user until `expiry_unix_timestamp`.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction(
+    delegate: Pubkey,
+    permissions: ProfileDelegatePermissions,
+    expiry_unix_timestamp: i64,
+    bump: u8
+)]
+pub struct InitializeProfileDelegate<'info> {
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [
+            sundial_profile.key().as_ref(),
+            b"delegate",
+            delegate.as_ref()
+        ],
+        bump = bump
+    )]
+    pub profile_delegate: Account<'info, ProfileDelegate>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_initialize_profile_delegate(
+    ctx: Context<InitializeProfileDelegate>,
+    delegate: Pubkey,
+    permissions: ProfileDelegatePermissions,
+    expiry_unix_timestamp: i64,
+    _bump: u8,
+) -> ProgramResult {
+    invariant!(
+        expiry_unix_timestamp > ctx.accounts.clock.unix_timestamp,
+        SundialError::ProfileDelegateExpired
+    );
+
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    profile.header.delegate_count = unwrap_int!(profile.header.delegate_count.checked_add(1));
+
+    let profile_delegate = &mut ctx.accounts.profile_delegate;
+    profile_delegate.sundial_profile = ctx.accounts.sundial_profile.key();
+    profile_delegate.user = ctx.accounts.user.key();
+    profile_delegate.delegate = delegate;
+    profile_delegate.permissions = permissions;
+    profile_delegate.expiry_unix_timestamp = expiry_unix_timestamp;
+
+    emit!(ProfileDelegateEvent {
+        profile: ctx.accounts.sundial_profile.key(),
+        delegate,
+        permissions,
+        expiry_unix_timestamp,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::initialize_profile_delegate] and [sundial::change_profile_delegate].
+pub struct ProfileDelegateEvent {
+    #[index]
+    pub profile: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: ProfileDelegatePermissions,
+    pub expiry_unix_timestamp: i64,
+}
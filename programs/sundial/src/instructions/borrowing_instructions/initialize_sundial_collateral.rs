@@ -1,8 +1,9 @@
 use crate::helpers::*;
 
 use crate::state::{
-    LiquidationConfig, LiquidityCap, SundialCollateral, SundialCollateralBumps,
-    SundialCollateralConfig, SundialMarket, LTV,
+    LiquidationConfig, LiquidityCap, RegistryEntryKind, SundialCollateral, SundialCollateralBumps,
+    SundialCollateralConfig, SundialMarket, SundialMarketRegistry, SundialMarketRegistryEntry, LTV,
+    SUNDIAL_COLLATERAL_VERSION,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -13,11 +14,13 @@ use crate::error::SundialError;
 
 #[validates(check_sundial_market_owner)]
 #[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[sundial_role(listing_admin)]
 #[instruction(
     bumps: SundialCollateralBumps,
     config: SundialCollateralConfigParams,
     name: String,
-    pda_bump: u8
+    pda_bump: u8,
+    registry_entry_bump: u8
 )]
 pub struct InitializeSundialCollateral<'info> {
     #[account(
@@ -71,6 +74,24 @@ pub struct InitializeSundialCollateral<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
     pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(
+        mut,
+        has_one = sundial_market
+    )]
+    pub sundial_market_registry: Box<Account<'info, SundialMarketRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_market_registry.key().as_ref(),
+            b"entry",
+            sundial_market_registry.entry_count.to_le_bytes().as_ref()
+        ],
+        bump = registry_entry_bump
+    )]
+    pub registry_entry: Box<Account<'info, SundialMarketRegistryEntry>>,
 }
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
 pub struct SundialCollateralConfigParams {
@@ -106,6 +127,7 @@ pub fn process_initialize_sundial_collateral(
     config: SundialCollateralConfigParams,
     _name: String,
     _pda_bump: u8,
+    _registry_entry_bump: u8,
 ) -> ProgramResult {
     let sundial_collateral = &mut ctx.accounts.sundial_collateral;
     sundial_collateral.bumps = bumps;
@@ -114,9 +136,18 @@ pub fn process_initialize_sundial_collateral(
     sundial_collateral.sundial_market = ctx.accounts.sundial_market.key();
     sundial_collateral.token_program = ctx.accounts.token_program.key();
     sundial_collateral.collateral_mint = ctx.accounts.port_lp_mint.key();
+    sundial_collateral.version = SUNDIAL_COLLATERAL_VERSION;
+    sundial_collateral.registered = true;
     sundial_collateral
         .sundial_collateral_config
         .collateral_decimals = ctx.accounts.port_lp_mint.decimals;
     log_then_prop_err!(sundial_collateral.sundial_collateral_config.sanity_check());
+
+    let registry_entry = &mut ctx.accounts.registry_entry;
+    registry_entry.registry = ctx.accounts.sundial_market_registry.key();
+    registry_entry.index = log_then_prop_err!(ctx.accounts.sundial_market_registry.next_index());
+    registry_entry.kind = RegistryEntryKind::SundialCollateral;
+    registry_entry.account = sundial_collateral.key();
+    registry_entry.reserve = sundial_collateral.port_collateral_reserve;
     Ok(())
 }
@@ -1,23 +1,33 @@
-use crate::state::SundialProfile;
+use crate::state::{
+    SundialProfile, DEFAULT_PROFILE_COLLATERAL_CAPACITY, DEFAULT_PROFILE_LOAN_CAPACITY,
+    SUNDIAL_PROFILE_VERSION,
+};
 use anchor_lang::prelude::*;
 use sundial_derives::*;
 
-/// Initialize [state::SundialProfile]
+/// Initialize [state::SundialProfile] with the default capacity, see
+/// [sundial::resize_sundial_profile](crate::sundial::resize_sundial_profile) to hold more assets.
+/// A user can have many isolated profiles in a market, told apart by `sub_account_id`.
 #[validates()]
 #[derive(Accounts, Clone)]
-#[instruction(sundial_market: Pubkey, bump: u8)]
+#[instruction(sundial_market: Pubkey, bump: u8, sub_account_id: u16)]
 pub struct InitializeSundialProfile<'info> {
     #[account(
         init,
         payer = user,
+        space = SundialProfile::space(
+            DEFAULT_PROFILE_COLLATERAL_CAPACITY,
+            DEFAULT_PROFILE_LOAN_CAPACITY
+        ),
         seeds=[
             sundial_market.as_ref(),
             user.key().as_ref(),
-            b"profile"
+            b"profile",
+            sub_account_id.to_le_bytes().as_ref()
         ],
         bump = bump
     )]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -29,9 +39,14 @@ pub fn process_initialize_sundial_profile(
     ctx: Context<InitializeSundialProfile>,
     sundial_market: Pubkey,
     _bump: u8,
+    sub_account_id: u16,
 ) -> ProgramResult {
-    let profile = &mut ctx.accounts.sundial_profile;
+    let mut profile = ctx.accounts.sundial_profile.load_init()?;
     profile.user = ctx.accounts.user.key();
     profile.sundial_market = sundial_market;
+    profile.version = SUNDIAL_PROFILE_VERSION;
+    profile.collateral_capacity = DEFAULT_PROFILE_COLLATERAL_CAPACITY;
+    profile.loan_capacity = DEFAULT_PROFILE_LOAN_CAPACITY;
+    profile.sub_account_id = sub_account_id;
     Ok(())
 }
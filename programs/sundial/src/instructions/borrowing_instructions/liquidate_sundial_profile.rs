@@ -1,9 +1,12 @@
 use crate::helpers::*;
-use crate::state::{calculate_risk_factor, Sundial, SundialCollateral, SundialProfile};
+use crate::state::{
+    calculate_risk_factor, Sundial, SundialCollateral, SundialMarket, SundialProfile,
+    SundialProfileMut,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialProfileStale};
+use sundial_derives::{validates, CheckSundialNotPaused, CheckSundialProfileStale};
 
 use itertools::Itertools;
 use paste::paste;
@@ -14,7 +17,7 @@ use anchor_spl::token::transfer;
 
 use crate::error::SundialError;
 
-use solana_maths::{Decimal, Rate, TryMul, TrySub, U192};
+use solana_maths::{Rate, TryMul, TrySub};
 
 /// Percentage of a [Profile] that can be repaid during
 /// each liquidation call due to price change
@@ -28,12 +31,12 @@ pub const LIQUIDATION_CLOSE_FACTOR: u8 = 50;
 /// You can only repay half of the total loan value, except for repaying overtime loan, you can repay all of the loan.
 /// It would try to repay as much token as possible.
 /// If there exists an overtime loan, you must liquidate the overtime loan first.
-#[validates(check_sundial_profile_stale)]
-#[derive(Accounts, Clone, CheckSundialProfileStale)]
+#[validates(check_sundial_profile_stale, check_sundial_not_paused)]
+#[derive(Accounts, Clone, CheckSundialProfileStale, CheckSundialNotPaused)]
 #[instruction()]
 pub struct LiquidateSundialProfile<'info> {
     #[account(mut)]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(mut)]
     pub user_repay_liquidity_wallet: Account<'info, TokenAccount>,
@@ -82,13 +85,15 @@ pub struct LiquidateSundialProfile<'info> {
     pub sundial_collateral_wallet: Box<Account<'info, TokenAccount>>,
 
     pub transfer_authority: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn process_liquidate_sundial_profile(ctx: Context<LiquidateSundialProfile>) -> ProgramResult {
     let user_wallet = &ctx.accounts.user_repay_liquidity_wallet;
-    let sundial_profile = &mut ctx.accounts.sundial_profile;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut sundial_profile = SundialProfileMut::load(&profile_info)?;
     let current_ts = ctx.accounts.clock.unix_timestamp;
     let no_overtime_loans = !sundial_profile
         .loans
@@ -114,7 +119,9 @@ pub fn process_liquidate_sundial_profile(ctx: Context<LiquidateSundialProfile>)
 
     let before_liquidation_margin = log_then_prop_err!(sundial_profile.get_liquidation_margin());
     let before_borrowed_value = log_then_prop_err!(sundial_profile.get_borrowed_value());
-    let (collaterals, loans) = sundial_profile.get_mut_collaterals_and_loans();
+    let SundialProfileMut {
+        collaterals, loans, ..
+    } = &mut sundial_profile;
     let (loan_pos, loan_to_repay) = vipers::unwrap_opt!(
         loans.iter_mut().find_position(|l| l.sundial == sundial_key),
         "This profile doesn't have this loan asset"
@@ -123,7 +130,7 @@ pub fn process_liquidate_sundial_profile(ctx: Context<LiquidateSundialProfile>)
 
     let allowed_repay_value = if is_loan_overtime {
         max(
-            Decimal(U192(loan_to_repay.asset.total_value)),
+            log_then_prop_err!(loan_to_repay.asset.total_value()),
             allowed_repay_value_when_no_overtime,
         )
     } else {
@@ -150,7 +157,7 @@ pub fn process_liquidate_sundial_profile(ctx: Context<LiquidateSundialProfile>)
         "Only overtime or unhealthy profile can be liquidated"
     );
 
-    let available_withdraw_value = Decimal(U192(collateral_to_withdraw.asset.total_value));
+    let available_withdraw_value = log_then_prop_err!(collateral_to_withdraw.asset.total_value());
     let available_repay_value = log_then_prop_err!(collateral_to_withdraw
         .config
         .liquidation_config
@@ -1,6 +1,6 @@
 use crate::helpers::*;
 
-use crate::state::{Sundial, SundialProfile};
+use crate::state::{Sundial, SundialMarket, SundialProfile, SundialProfileMut};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
@@ -13,27 +13,31 @@ use crate::error::SundialError;
 use crate::state::SundialProfileLoan;
 
 use sundial_derives::{
-    validates, CheckSundialNotEnd, CheckSundialProfileMarket, CheckSundialProfileStale,
+    validates, CheckSundialActive, CheckSundialNotEnd, CheckSundialProfileMarket,
+    CheckSundialProfileStale,
 };
 
-use vipers::{unwrap_int, unwrap_opt};
+use vipers::{invariant, unwrap_int, unwrap_opt};
 
 #[validates(
     check_sundial_profile_stale,
     check_sundial_profile_market,
-    check_sundial_not_end
+    check_sundial_not_end,
+    check_sundial_active
 )]
 #[derive(
-    Accounts, Clone, CheckSundialProfileStale, CheckSundialProfileMarket, CheckSundialNotEnd,
+    Accounts,
+    Clone,
+    CheckSundialProfileStale,
+    CheckSundialProfileMarket,
+    CheckSundialNotEnd,
+    CheckSundialActive,
 )]
 #[instruction(amount:u64)]
 pub struct MintSundialLiquidityWithCollateral<'info> {
     /// Refreshed [SundialProfile].
-    #[account(
-        mut,
-        has_one = user @ SundialError::InvalidProfileUser
-    )]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(
         has_one = token_program @ SundialError::InvalidTokenProgram
@@ -72,8 +76,11 @@ pub struct MintSundialLiquidityWithCollateral<'info> {
 
     #[account(mut)]
     pub user_principle_wallet: Account<'info, TokenAccount>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
+    /// The profile user, or a [state::ProfileDelegate] passing its record as the last remaining
+    /// account.
     pub user: Signer<'info>,
 }
 
@@ -81,6 +88,21 @@ pub fn process_mint_sundial_liquidity_with_collateral<'info>(
     ctx: Context<'_, '_, '_, 'info, MintSundialLiquidityWithCollateral<'info>>,
     amount: u64,
 ) -> ProgramResult {
+    {
+        let profile = ctx.accounts.sundial_profile.load()?;
+        let is_delegate = profile.check_signer(
+            ctx.accounts.sundial_profile.key(),
+            ctx.accounts.user.key(),
+            ctx.remaining_accounts.last(),
+            |p| p.mint,
+        )?;
+        invariant!(
+            !is_delegate || ctx.accounts.user_principle_wallet.owner == profile.user,
+            SundialError::ProfileDelegateNotPermitted,
+            "Delegates can only mint to the profile user"
+        );
+    }
+
     let fee_rate = ctx.accounts.sundial.config.borrow_fee;
     let fee_amount = log_then_prop_err!(fee_rate.mint_fee(
         amount,
@@ -104,7 +126,8 @@ pub fn process_mint_sundial_liquidity_with_collateral<'info>(
         unwrap_int!(amount.checked_sub(fee_amount))
     ));
 
-    let profile = &mut ctx.accounts.sundial_profile;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
     let sundial_key = ctx.accounts.sundial.key();
     log_then_prop_err!(update_or_insert(
         &mut profile.loans,
@@ -129,10 +152,18 @@ pub fn process_mint_sundial_liquidity_with_collateral<'info>(
             SundialProfileLoan::init_loan(
                 amount,
                 oracle_info,
+                ctx.accounts
+                    .sundial
+                    .fallback_oracle(ctx.remaining_accounts.get(1))?,
                 ctx.accounts.sundial.key(),
                 &ctx.accounts.clock,
                 ctx.accounts.sundial.end_unix_time_stamp,
                 ctx.accounts.sundial.config.liquidity_decimals,
+                ctx.accounts
+                    .sundial_market
+                    .stale_tolerances
+                    .oracle(ctx.accounts.sundial.oracle_stale_tol),
+                &ctx.accounts.sundial.oracle_config,
             )
         }
     ));
@@ -1,21 +1,47 @@
+mod change_profile_delegate;
 mod change_sundial_collateral_config;
+mod change_sundial_collateral_price_check;
+mod change_sundial_collateral_stale_tol;
+mod change_sundial_collateral_status;
+mod close_profile_delegate;
+mod close_sundial_profile;
 mod deposit_sundial_collateral;
+mod initialize_profile_delegate;
 mod initialize_sundial_collateral;
 mod initialize_sundial_profile;
 mod liquidate_sundial_profile;
 mod mint_sundial_liquidity_with_collateral;
 mod refresh_sundial_collateral;
 mod refresh_sundial_profile;
+mod register_existing_sundial_collateral;
 mod repay_sundial_liquidity;
+mod repay_sundial_principle;
+mod resize_sundial_profile;
+mod rollover_loan;
+mod transfer_sundial_collateral;
+mod transfer_sundial_profile;
 mod withdraw_sundial_collateral;
 
+pub use change_profile_delegate::*;
 pub use change_sundial_collateral_config::*;
+pub use change_sundial_collateral_price_check::*;
+pub use change_sundial_collateral_stale_tol::*;
+pub use change_sundial_collateral_status::*;
+pub use close_profile_delegate::*;
+pub use close_sundial_profile::*;
 pub use deposit_sundial_collateral::*;
+pub use initialize_profile_delegate::*;
 pub use initialize_sundial_collateral::*;
 pub use initialize_sundial_profile::*;
 pub use liquidate_sundial_profile::*;
 pub use mint_sundial_liquidity_with_collateral::*;
 pub use refresh_sundial_collateral::*;
 pub use refresh_sundial_profile::*;
+pub use register_existing_sundial_collateral::*;
 pub use repay_sundial_liquidity::*;
+pub use repay_sundial_principle::*;
+pub use resize_sundial_profile::*;
+pub use rollover_loan::*;
+pub use transfer_sundial_collateral::*;
+pub use transfer_sundial_profile::*;
 pub use withdraw_sundial_collateral::*;
@@ -1,6 +1,6 @@
 use crate::error::SundialError;
-use crate::helpers::price_per_lamport;
-use crate::state::SundialCollateral;
+use crate::oracle::{deviation_bips, get_source_price};
+use crate::state::{SundialCollateral, SundialMarket, SundialOracleConfig};
 use anchor_lang::prelude::*;
 use port_anchor_adaptor::PortReserve;
 use solana_maths::Decimal;
@@ -14,35 +14,85 @@ use sundial_derives::*;
 pub struct RefreshSundialCollateral<'info> {
     #[account(
         mut,
-        has_one = port_collateral_reserve @ SundialError::InvalidPortReserve
+        has_one = port_collateral_reserve @ SundialError::InvalidPortReserve,
+        has_one = sundial_market @ SundialError::SundialMarketNotMatch
     )]
     pub sundial_collateral: Account<'info, SundialCollateral>,
 
     #[account(constraint = !port_collateral_reserve.last_update.stale @ SundialError::ReserveIsNotRefreshed)]
     pub port_collateral_reserve: Account<'info, PortReserve>,
 
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
     pub clock: Sysvar<'info, Clock>,
+    // optional independent oracle of the underlying liquidity, required if the price check is on
 }
 
-pub fn process_refresh_sundial_collateral(ctx: Context<RefreshSundialCollateral>) -> ProgramResult {
+pub fn process_refresh_sundial_collateral<'info>(
+    ctx: Context<'_, '_, '_, 'info, RefreshSundialCollateral<'info>>,
+) -> ProgramResult {
+    let sundial_collateral_key = ctx.accounts.sundial_collateral.key();
     let sundial_collateral = &mut ctx.accounts.sundial_collateral;
     let reserve = &ctx.accounts.port_collateral_reserve;
     let liquidity_price = reserve.liquidity.market_price;
+    log_then_prop_err!(sundial_collateral.check_version());
+
+    let price_check = &mut sundial_collateral.price_check;
+    if price_check.oracle != Pubkey::default() {
+        let oracle = ctx
+            .remaining_accounts
+            .get(0)
+            .ok_or(SundialError::OracleNeeded)?;
+        vipers::assert_keys_eq!(oracle.key, price_check.oracle, "Invalid price check oracle");
+        let oracle_price = log_then_prop_err!(get_source_price(
+            price_check.source,
+            oracle,
+            &ctx.accounts.clock,
+            ctx.accounts.sundial_market.stale_tolerances.oracle(0),
+            &SundialOracleConfig::default(),
+        ));
+        let reserve_price = Decimal(U192(get_raw_from_uint!(liquidity_price)));
+        let deviation = log_then_prop_err!(deviation_bips(oracle_price, reserve_price));
+        price_check.last_deviation_bips = deviation;
+        price_check.last_checked_slot = ctx.accounts.clock.slot;
+        if deviation > price_check.max_deviation_bips as u64 {
+            msg!(
+                "Reserve price {:?} and oracle price {:?} deviate by {:?} bips, price not refreshed",
+                reserve_price,
+                oracle_price,
+                deviation
+            );
+            emit!(SundialCollateralPriceCheckFailedEvent {
+                sundial_collateral: sundial_collateral_key,
+                deviation_bips: deviation,
+                max_deviation_bips: price_check.max_deviation_bips,
+            });
+            return Ok(());
+        }
+    }
+
     let exchange_rate = log_then_prop_err!(reserve.collateral_exchange_rate());
 
     let collateral_price =
         log_then_prop_err!(exchange_rate.decimal_collateral_to_liquidity(liquidity_price));
 
-    // TODO: why we do the division here? I think it's better to divide later so more precision is preserved?
-    sundial_collateral.collateral_price =
-        get_raw_from_uint!(log_then_prop_err!(price_per_lamport(
-            // Fixed type mismatch here.
-            Decimal(U192(get_raw_from_uint!(collateral_price))),
-            sundial_collateral
-                .sundial_collateral_config
-                .collateral_decimals
-        )));
+    // Stored per whole token, the decimals are applied when valuing an amount.
+    sundial_collateral.collateral_price = get_raw_from_uint!(collateral_price);
+
+    log_then_prop_err!(sundial_collateral.update_ema_price(ctx.accounts.clock.unix_timestamp));
 
     sundial_collateral.last_updated_slot = ctx.accounts.clock.slot.into();
     Ok(())
 }
+
+#[event]
+/// Event called in [sundial::refresh_sundial_collateral] when the price check fails.
+pub struct SundialCollateralPriceCheckFailedEvent {
+    /// The [SundialCollateral].
+    #[index]
+    pub sundial_collateral: Pubkey,
+    /// Deviation between the reserve price and the independent oracle in bips.
+    pub deviation_bips: u64,
+    /// Max deviation accepted in bips.
+    pub max_deviation_bips: u16,
+}
@@ -1,45 +1,96 @@
-use crate::state::SundialProfile;
+use crate::error::SundialError;
+use crate::state::{Sundial, SundialMarket, SundialProfile, SundialProfileMut};
 use anchor_lang::prelude::*;
 
 use sundial_derives::validates;
 
-/// Refresh [SundialProfile]'s borrowing power.
+/// Refresh [SundialProfile]'s borrowing power. It only updates prices, so it works whatever the
+/// status of the market, its [Sundial]s and collaterals: reduce only markets still need fresh
+/// profiles to repay, withdraw and liquidate.
+///
+/// Remaining accounts, in order:
+/// - the [SundialCollateral](crate::state::SundialCollateral) of each collateral of the profile,
+/// - the oracle of each loan,
+/// - the [Sundial] of each loan,
+/// - the fallback oracles of the loans' [Sundial]s that have one, in any order.
 #[validates()]
 #[derive(Accounts, Clone)]
 #[instruction()]
 pub struct RefreshSundialProfile<'info> {
-    #[account(mut)]
-    pub profile: Box<Account<'info, SundialProfile>>,
+    #[account(
+        mut,
+        has_one = sundial_market @ SundialError::SundialMarketNotMatch
+    )]
+    pub profile: AccountLoader<'info, SundialProfile>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub clock: Sysvar<'info, Clock>,
-    // optional [SundialCollateral] and oracles
 }
 
 pub fn process_refresh_sundial_profile<'info>(
     ctx: Context<'_, '_, '_, 'info, RefreshSundialProfile<'info>>,
 ) -> ProgramResult {
-    let profile = &mut ctx.accounts.profile;
-    profile.last_update = ctx.accounts.clock.slot.into();
+    let profile_info = ctx.accounts.profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    profile.header.last_update_slot = ctx.accounts.clock.slot;
+    let stale_tolerances = &ctx.accounts.sundial_market.stale_tolerances;
 
     let collaterals_and_oracles = ctx.remaining_accounts;
 
     let collateral_cnt = profile.collaterals.len();
     let loan_cnt = profile.loans.len();
 
+    vipers::invariant!(
+        collaterals_and_oracles.len() >= collateral_cnt + 2 * loan_cnt,
+        SundialError::OracleNeeded,
+        &format!(
+            "{:?} collaterals and {:?} loans need {:?} accounts, only {:?} given",
+            collateral_cnt,
+            loan_cnt,
+            collateral_cnt + 2 * loan_cnt,
+            collaterals_and_oracles.len()
+        )
+    );
     let collaterals = &collaterals_and_oracles[0..collateral_cnt];
     let oracles = &collaterals_and_oracles[collateral_cnt..collateral_cnt + loan_cnt];
+    let sundials =
+        &collaterals_and_oracles[collateral_cnt + loan_cnt..collateral_cnt + 2 * loan_cnt];
+    let fallback_oracles = &collaterals_and_oracles[collateral_cnt + 2 * loan_cnt..];
 
     log_then_prop_err!(profile
         .collaterals
         .iter_mut()
         .zip(collaterals.iter())
-        .try_for_each(|(collateral, sundial_collateral)| collateral
-            .refresh_price(sundial_collateral, &ctx.accounts.clock)));
+        .try_for_each(|(collateral, sundial_collateral)| collateral.refresh_price(
+            sundial_collateral,
+            &ctx.accounts.clock,
+            stale_tolerances
+        )));
 
     log_then_prop_err!(profile
         .loans
         .iter_mut()
-        .zip(oracles.iter())
-        .try_for_each(|(loan, oracle)| loan.refresh_price(oracle, &ctx.accounts.clock)));
+        .zip(oracles.iter().zip(sundials.iter()))
+        .try_for_each(|(loan, (oracle, sundial_info))| {
+            vipers::assert_keys_eq!(
+                sundial_info.key,
+                loan.sundial,
+                "Invalid Sundial given for refreshing"
+            );
+            let sundial: Sundial = anchor_lang::AccountDeserialize::try_deserialize(
+                &mut sundial_info.try_borrow_data()?.as_ref(),
+            )?;
+            loan.refresh_price(
+                oracle,
+                sundial.fallback_oracle(
+                    fallback_oracles
+                        .iter()
+                        .find(|o| *o.key == sundial.fallback_oracle),
+                )?,
+                &ctx.accounts.clock,
+                stale_tolerances.oracle(sundial.oracle_stale_tol),
+                &sundial.oracle_config,
+            )
+        }));
 
     Ok(())
 }
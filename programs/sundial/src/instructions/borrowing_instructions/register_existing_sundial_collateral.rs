@@ -0,0 +1,69 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{
+    RegistryEntryKind, SundialCollateral, SundialMarket, SundialMarketRegistry,
+    SundialMarketRegistryEntry,
+};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use vipers::invariant;
+
+/// Add a [SundialCollateral] created before the [SundialMarketRegistry] existed to the registry,
+/// so its status can be changed. Each [SundialCollateral] can only be registered once.
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(listing_admin)]
+#[instruction(registry_entry_bump: u8)]
+pub struct RegisterExistingSundialCollateral<'info> {
+    #[account(mut)]
+    pub sundial_collateral: Account<'info, SundialCollateral>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(
+        mut,
+        has_one = sundial_market
+    )]
+    pub sundial_market_registry: Box<Account<'info, SundialMarketRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_market_registry.key().as_ref(),
+            b"entry",
+            sundial_market_registry.entry_count.to_le_bytes().as_ref()
+        ],
+        bump = registry_entry_bump
+    )]
+    pub registry_entry: Box<Account<'info, SundialMarketRegistryEntry>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_register_existing_sundial_collateral(
+    ctx: Context<RegisterExistingSundialCollateral>,
+    _registry_entry_bump: u8,
+) -> ProgramResult {
+    let sundial_collateral = &mut ctx.accounts.sundial_collateral;
+    log_then_prop_err!(sundial_collateral.check_version());
+    invariant!(
+        !sundial_collateral.registered,
+        SundialError::AlreadyRegistered
+    );
+    sundial_collateral.registered = true;
+
+    let registry_entry = &mut ctx.accounts.registry_entry;
+    registry_entry.registry = ctx.accounts.sundial_market_registry.key();
+    registry_entry.index = log_then_prop_err!(ctx.accounts.sundial_market_registry.next_index());
+    registry_entry.kind = RegistryEntryKind::SundialCollateral;
+    registry_entry.account = sundial_collateral.key();
+    registry_entry.reserve = sundial_collateral.port_collateral_reserve;
+    registry_entry.status = sundial_collateral.status;
+    Ok(())
+}
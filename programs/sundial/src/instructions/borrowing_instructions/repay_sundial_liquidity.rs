@@ -1,10 +1,10 @@
 use crate::error::SundialError;
 use crate::helpers::*;
-use crate::state::{Sundial, SundialProfile};
+use crate::state::{Sundial, SundialMarket, SundialProfile, SundialProfileMut};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialProfileMarket};
+use sundial_derives::{validates, CheckSundialNotPaused, CheckSundialProfileMarket};
 
 use itertools::Itertools;
 
@@ -15,15 +15,12 @@ use anchor_spl::token::transfer;
 
 /// Repay liquidity token, i.e., repay USDC if you mint ppUSDC before.
 /// It will repay min(amount, loan_amount), e.g., you can pass u64::max to amount if you want repay all.
-#[validates(check_sundial_profile_market)]
-#[derive(Accounts, Clone, CheckSundialProfileMarket)]
+#[validates(check_sundial_profile_market, check_sundial_not_paused)]
+#[derive(Accounts, Clone, CheckSundialProfileMarket, CheckSundialNotPaused)]
 #[instruction(amount:u64)]
 pub struct RepaySundialLiquidity<'info> {
-    #[account(
-        mut,
-        has_one = user @ SundialError::InvalidProfileUser
-    )]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(
         has_one = token_program @ SundialError::InvalidTokenProgram
@@ -44,7 +41,10 @@ pub struct RepaySundialLiquidity<'info> {
     pub user_liquidity_wallet: Account<'info, TokenAccount>,
 
     pub transfer_authority: Signer<'info>,
+    /// The profile user, or a [state::ProfileDelegate] passing its record as the last remaining
+    /// account.
     pub user: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
 }
 
@@ -53,7 +53,14 @@ pub fn process_repay_sundial_liquidity(
     repay_amount: u64,
 ) -> ProgramResult {
     let sundial_key = ctx.accounts.sundial.key();
-    let profile = &mut ctx.accounts.sundial_profile;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    profile.header.check_signer(
+        ctx.accounts.sundial_profile.key(),
+        ctx.accounts.user.key(),
+        ctx.remaining_accounts.last(),
+        |p| p.repay,
+    )?;
     let (pos, loan) = vipers::unwrap_opt!(
         profile
             .loans
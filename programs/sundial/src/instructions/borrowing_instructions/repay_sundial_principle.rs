@@ -0,0 +1,109 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{Sundial, SundialMarket, SundialProfile, SundialProfileMut};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use sundial_derives::{validates, CheckSundialNotPaused, CheckSundialProfileMarket};
+
+use itertools::Itertools;
+
+use std::cmp::min;
+
+/// Repay with principal tokens of the same [Sundial] at par, i.e., burn ppUSDC to repay a ppUSDC loan.
+/// It will burn min(amount, loan_amount), before or after the [Sundial] ends.
+#[validates(check_sundial_profile_market, check_sundial_not_paused)]
+#[derive(Accounts, Clone, CheckSundialProfileMarket, CheckSundialNotPaused)]
+#[instruction(amount:u64)]
+pub struct RepaySundialPrinciple<'info> {
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    #[account(
+        has_one = token_program @ SundialError::InvalidTokenProgram
+    )]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub sundial_principle_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub user_principle_wallet: Account<'info, TokenAccount>,
+
+    pub transfer_authority: Signer<'info>,
+    /// The profile user, or a [state::ProfileDelegate] passing its record as the last remaining
+    /// account.
+    pub user: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn process_repay_sundial_principle(
+    ctx: Context<RepaySundialPrinciple>,
+    repay_amount: u64,
+) -> ProgramResult {
+    let sundial_key = ctx.accounts.sundial.key();
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    profile.header.check_signer(
+        ctx.accounts.sundial_profile.key(),
+        ctx.accounts.user.key(),
+        ctx.remaining_accounts.last(),
+        |p| p.repay,
+    )?;
+    let (pos, loan) = vipers::unwrap_opt!(
+        profile
+            .loans
+            .iter_mut()
+            .find_position(|l| l.sundial == sundial_key),
+        "You don't have that asset as loan"
+    );
+
+    let actual_repay_amount = min(loan.asset.amount, repay_amount);
+
+    if 0 == log_then_prop_err!(
+        loan.asset.reduce_amount(actual_repay_amount),
+        SundialError::RepayTooMuchLoan,
+        "Repay too much, you don't have that much loan"
+    ) {
+        profile.loans.remove(pos);
+    };
+
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.sundial_principle_mint.to_account_info(),
+                to: ctx.accounts.user_principle_wallet.to_account_info(),
+                authority: ctx.accounts.transfer_authority.to_account_info(),
+            },
+        ),
+        actual_repay_amount
+    ));
+
+    emit!(RepaySundialPrincipleEvent {
+        sundial: ctx.accounts.sundial.key(),
+        profile: ctx.accounts.sundial_profile.key(),
+        asset_mint: ctx.accounts.sundial_principle_mint.key(),
+        user_wallet: ctx.accounts.user.key(),
+        repay_amount: actual_repay_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct RepaySundialPrincipleEvent {
+    pub sundial: Pubkey,
+    pub profile: Pubkey,
+    pub asset_mint: Pubkey,
+    pub user_wallet: Pubkey,
+    pub repay_amount: u64,
+}
@@ -0,0 +1,116 @@
+use crate::error::SundialError;
+use crate::state::{
+    SundialProfile, SundialProfileCollateral, SundialProfileLoan, SundialProfileMut,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::system_instruction;
+use std::cmp::max;
+use std::mem::size_of;
+use sundial_derives::*;
+use vipers::invariant;
+
+/// Grow the number of collateral and loan slots of a [SundialProfile], the user pays the rent of
+/// the extra space. It touches no [Sundial](crate::state::Sundial) or
+/// [SundialCollateral](crate::state::SundialCollateral), so it works whatever their status.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction(collateral_capacity: u8, loan_capacity: u8)]
+pub struct ResizeSundialProfile<'info> {
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn process_resize_sundial_profile(
+    ctx: Context<ResizeSundialProfile>,
+    collateral_capacity: u8,
+    loan_capacity: u8,
+) -> ProgramResult {
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let (old_collateral_capacity, old_loan_capacity) = {
+        let profile = SundialProfileMut::load(&profile_info)?;
+        (
+            profile.header.collateral_capacity,
+            profile.header.loan_capacity,
+        )
+    };
+    invariant!(
+        collateral_capacity >= old_collateral_capacity && loan_capacity >= old_loan_capacity,
+        SundialError::InvalidProfileCapacity,
+        &format!(
+            "Current capacity is {:?} collaterals and {:?} loans",
+            old_collateral_capacity, old_loan_capacity
+        )
+    );
+
+    let new_len = max(
+        profile_info.data_len(),
+        SundialProfile::space(collateral_capacity, loan_capacity),
+    );
+    let rent_needed = ctx
+        .accounts
+        .rent
+        .minimum_balance(new_len)
+        .saturating_sub(profile_info.lamports());
+    if rent_needed > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.user.key, profile_info.key, rent_needed),
+            &[
+                ctx.accounts.user.to_account_info(),
+                profile_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    if new_len != profile_info.data_len() {
+        profile_info.realloc(new_len, true)?;
+    }
+
+    {
+        // Loans follow the collateral slots, so they move back by the added collateral slots
+        // and both sets of new slots start out zeroed, i.e. unused.
+        let mut data = profile_info.try_borrow_mut_data()?;
+        let collaterals_start = 8 + size_of::<SundialProfile>();
+        let old_loans_start = collaterals_start
+            + old_collateral_capacity as usize * size_of::<SundialProfileCollateral>();
+        let new_loans_start = collaterals_start
+            + collateral_capacity as usize * size_of::<SundialProfileCollateral>();
+        let old_loans_len = old_loan_capacity as usize * size_of::<SundialProfileLoan>();
+        let new_loans_len = loan_capacity as usize * size_of::<SundialProfileLoan>();
+        data.copy_within(
+            old_loans_start..old_loans_start + old_loans_len,
+            new_loans_start,
+        );
+        data[old_loans_start..new_loans_start].fill(0);
+        data[new_loans_start + old_loans_len..new_loans_start + new_loans_len].fill(0);
+    }
+
+    let mut profile = ctx.accounts.sundial_profile.load_mut()?;
+    profile.collateral_capacity = collateral_capacity;
+    profile.loan_capacity = loan_capacity;
+
+    emit!(ResizeSundialProfileEvent {
+        profile: ctx.accounts.sundial_profile.key(),
+        collateral_capacity,
+        loan_capacity,
+        data_len: new_len as u64,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct ResizeSundialProfileEvent {
+    #[index]
+    pub profile: Pubkey,
+    pub collateral_capacity: u8,
+    pub loan_capacity: u8,
+    pub data_len: u64,
+}
@@ -0,0 +1,315 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{
+    AssetInfo, Sundial, SundialAmm, SundialMarket, SundialProfile, SundialProfileLoan,
+    SundialProfileMut, SwapDirection,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, transfer, Mint, Token, TokenAccount};
+
+use paste::paste;
+
+use sundial_derives::{
+    validates, CheckSundialNotPaused, CheckSundialProfileMarket, CheckSundialProfileStale,
+};
+
+use itertools::Itertools;
+
+use std::cmp::min;
+
+use solana_maths::{Decimal, U192};
+
+use vipers::unwrap_int;
+
+/// Roll a loan over from `sundial` into the later `new_sundial`: principal tokens of the new
+/// [Sundial] are minted against the profile and sold through its [SundialAmm], the liquidity
+/// received repays the expiring loan and the debt is moved to the new series.
+#[validates(
+    check_sundial_profile_stale,
+    check_sundial_profile_market,
+    check_new_sundial_not_end,
+    check_sundial_not_paused
+)]
+#[derive(
+    Accounts, Clone, CheckSundialProfileStale, CheckSundialProfileMarket, CheckSundialNotPaused,
+)]
+#[instruction(mint_amount: u64, min_liquidity_out: u64)]
+pub struct RolloverLoan<'info> {
+    /// Refreshed [SundialProfile].
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    /// The [Sundial] of the expiring loan.
+    #[account(
+        has_one = token_program @ SundialError::InvalidTokenProgram
+    )]
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = new_sundial.sundial_market == sundial.sundial_market @ SundialError::SundialMarketNotMatch,
+        constraint = new_sundial.reserve == sundial.reserve @ SundialError::InvalidRolloverSundial,
+        constraint = new_sundial.oracle == sundial.oracle @ SundialError::InvalidRolloverSundial,
+        constraint = new_sundial.end_unix_time_stamp > sundial.end_unix_time_stamp @ SundialError::InvalidRolloverSundial,
+        constraint = new_sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram
+    )]
+    pub new_sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = new_sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the new [Sundial].
+    pub new_sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = new_sundial.bumps.principle_mint_bump
+    )]
+    pub new_sundial_principle_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"fee_receiver"
+        ],
+        bump = new_sundial.bumps.fee_receiver_bump
+    )]
+    pub new_sundial_fee_receiver_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = sundial_amm.sundial == new_sundial.key() @ SundialError::InvalidRolloverSundial
+    )]
+    pub sundial_amm: Box<Account<'info, SundialAmm>>,
+
+    #[account(
+        seeds=[
+            sundial_amm.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial_amm.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [SundialAmm].
+    pub sundial_amm_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial_amm.bumps.liquidity_bump
+    )]
+    pub amm_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial_amm.key().as_ref(),
+            b"principle"
+        ],
+        bump = sundial_amm.bumps.principle_bump
+    )]
+    pub amm_principle_wallet: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the liquidity left after the expiring loan is repaid.
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> RolloverLoan<'info> {
+    fn check_new_sundial_not_end(&self) -> ProgramResult {
+        vipers::invariant!(
+            self.new_sundial.end_unix_time_stamp > self.clock.unix_timestamp,
+            SundialError::AlreadyEnd,
+            &format!(
+                "New sundial ends at {:?}, current time is {:?}",
+                self.new_sundial.end_unix_time_stamp, self.clock.unix_timestamp
+            )
+        );
+        self.new_sundial.status.check_active("New Sundial")?;
+        Ok(())
+    }
+}
+
+pub fn process_rollover_loan(
+    ctx: Context<RolloverLoan>,
+    mint_amount: u64,
+    min_liquidity_out: u64,
+) -> ProgramResult {
+    let fee_amount = log_then_prop_err!(ctx.accounts.new_sundial.config.borrow_fee.mint_fee(
+        mint_amount,
+        create_mint_to_cpi(
+            ctx.accounts.new_sundial_principle_mint.to_account_info(),
+            ctx.accounts
+                .new_sundial_fee_receiver_wallet
+                .to_account_info(),
+            ctx.accounts.new_sundial_authority.to_account_info(),
+            seeds!(ctx, new_sundial, authority),
+            ctx.accounts.token_program.to_account_info()
+        )
+    ));
+    let sell_amount = unwrap_int!(mint_amount.checked_sub(fee_amount));
+
+    let liquidity_out = log_then_prop_err!(ctx.accounts.sundial_amm.get_swap_amount_out(
+        &ctx.accounts.new_sundial,
+        &ctx.accounts.clock,
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+        sell_amount,
+        SwapDirection::PrincipleToLiquidity,
+    ));
+    vipers::invariant!(
+        liquidity_out >= min_liquidity_out,
+        SundialError::AmmSlippageExceeded,
+        &format!(
+            "Amount out {:?}, min {:?}",
+            liquidity_out, min_liquidity_out
+        )
+    );
+
+    // The minted principal tokens are sold straight into the pool.
+    log_then_prop_err!(mint_to(
+        create_mint_to_cpi(
+            ctx.accounts.new_sundial_principle_mint.to_account_info(),
+            ctx.accounts.amm_principle_wallet.to_account_info(),
+            ctx.accounts.new_sundial_authority.to_account_info(),
+            seeds!(ctx, new_sundial, authority),
+            ctx.accounts.token_program.to_account_info()
+        ),
+        sell_amount
+    ));
+
+    let sundial_key = ctx.accounts.sundial.key();
+    let new_sundial_key = ctx.accounts.new_sundial.key();
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    let (pos, loan) = vipers::unwrap_opt!(
+        profile
+            .loans
+            .iter_mut()
+            .find_position(|l| l.sundial == sundial_key),
+        "You don't have that asset as loan"
+    );
+
+    // Both Sundials share the oracle, so the new debt is valued at the price of the old one.
+    let loan_price = Decimal(U192(loan.asset.price));
+    let loan_decimals = loan.asset.decimals;
+    let repay_amount = min(loan.asset.amount, liquidity_out);
+    if 0 == log_then_prop_err!(
+        loan.asset.reduce_amount(repay_amount),
+        SundialError::RepayTooMuchLoan,
+        "Repay too much, you don't have that much loan"
+    ) {
+        profile.loans.remove(pos);
+    };
+
+    let new_sundial = &ctx.accounts.new_sundial;
+    log_then_prop_err!(update_or_insert(
+        &mut profile.loans,
+        |l| l.sundial == new_sundial_key,
+        |l| {
+            l.asset.add_amount(mint_amount)?;
+            l.update_config(new_sundial)?;
+            Ok(())
+        },
+        || {
+            Ok(SundialProfileLoan {
+                asset: AssetInfo::new(mint_amount, loan_price, loan_decimals),
+                oracle: new_sundial.oracle,
+                sundial: new_sundial_key,
+                maturity_unix_timestamp: new_sundial.end_unix_time_stamp,
+                liquidity_decimals: new_sundial.config.liquidity_decimals,
+            })
+        }
+    ));
+
+    profile.check_enough_borrowing_power(
+        SundialError::InvalidMintAmount,
+        "Rollover too much, you don't have enough borrowing power",
+    )?;
+
+    log_then_prop_err!(transfer(
+        create_transfer_cpi(
+            ctx.accounts.amm_liquidity_wallet.to_account_info(),
+            ctx.accounts.sundial_liquidity_wallet.to_account_info(),
+            ctx.accounts.sundial_amm_authority.to_account_info(),
+            seeds!(ctx, sundial_amm, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        repay_amount
+    ));
+
+    let left_liquidity = unwrap_int!(liquidity_out.checked_sub(repay_amount));
+    if left_liquidity > 0 {
+        log_then_prop_err!(transfer(
+            create_transfer_cpi(
+                ctx.accounts.amm_liquidity_wallet.to_account_info(),
+                ctx.accounts.user_liquidity_wallet.to_account_info(),
+                ctx.accounts.sundial_amm_authority.to_account_info(),
+                seeds!(ctx, sundial_amm, authority),
+                ctx.accounts.token_program.to_account_info(),
+            ),
+            left_liquidity
+        ));
+    }
+
+    log_then_prop_err!(ctx.accounts.amm_liquidity_wallet.reload());
+    log_then_prop_err!(ctx.accounts.amm_principle_wallet.reload());
+    log_then_prop_err!(ctx.accounts.sundial_amm.update_implied_apy(
+        ctx.accounts.amm_principle_wallet.amount,
+        ctx.accounts.amm_liquidity_wallet.amount,
+    ));
+
+    emit!(RolloverLoanEvent {
+        sundial: sundial_key,
+        new_sundial: new_sundial_key,
+        profile: ctx.accounts.sundial_profile.key(),
+        user_wallet: ctx.accounts.user.key(),
+        amount_mint: mint_amount,
+        repay_amount,
+        liquidity_left: left_liquidity,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct RolloverLoanEvent {
+    pub sundial: Pubkey,
+    pub new_sundial: Pubkey,
+    pub profile: Pubkey,
+    pub user_wallet: Pubkey,
+    /// Principal tokens of the new [Sundial] borrowed, including the fee.
+    pub amount_mint: u64,
+    /// Loan repaid in the expiring [Sundial].
+    pub repay_amount: u64,
+    /// Liquidity sent back to the user.
+    pub liquidity_left: u64,
+}
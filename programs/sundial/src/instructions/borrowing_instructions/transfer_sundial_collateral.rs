@@ -0,0 +1,112 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{
+    SundialCollateral, SundialMarket, SundialProfile, SundialProfileCollateral, SundialProfileMut,
+};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{
+    validates, CheckSundialNotPaused, CheckSundialProfileMarket, CheckSundialProfileStale,
+};
+
+use itertools::Itertools;
+use std::cmp::min;
+
+/// Move collateral from one of the user's [SundialProfile] to another one of the same market,
+/// the Port LP tokens stay in the [SundialCollateral] wallet.
+#[validates(
+    check_sundial_profile_stale,
+    check_sundial_profile_market,
+    check_sundial_not_paused
+)]
+#[derive(
+    Accounts, Clone, CheckSundialProfileStale, CheckSundialProfileMarket, CheckSundialNotPaused,
+)]
+#[instruction(amount: u64)]
+pub struct TransferSundialCollateral<'info> {
+    /// Refreshed [state::SundialProfile] the collateral is taken from
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    /// [state::SundialProfile] receiving the collateral
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser,
+        has_one = sundial_market @ SundialError::InvalidCollateralTransfer,
+        constraint = destination_sundial_profile.key() != sundial_profile.key()
+            @ SundialError::InvalidCollateralTransfer
+    )]
+    pub destination_sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    pub sundial_collateral: Account<'info, SundialCollateral>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub clock: Sysvar<'info, Clock>,
+    pub user: Signer<'info>,
+}
+
+pub fn process_transfer_sundial_collateral(
+    ctx: Context<TransferSundialCollateral>,
+    max_amount: u64,
+) -> ProgramResult {
+    let collateral_key = ctx.accounts.sundial_collateral.key();
+
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    let (pos, collateral) = vipers::unwrap_opt!(
+        profile
+            .collaterals
+            .iter_mut()
+            .find_position(|c| c.sundial_collateral == collateral_key),
+        "You don't have that asset as collateral"
+    );
+
+    let transfer_amount = min(collateral.asset.amount, max_amount);
+    if 0 == log_then_prop_err!(
+        collateral.asset.reduce_amount(transfer_amount),
+        SundialError::WithdrawTooMuchCollateral,
+        "You are trying to transfer more than you have"
+    ) {
+        profile.collaterals.remove(pos);
+    }
+
+    profile.check_enough_borrowing_power(
+        SundialError::WithdrawTooMuchCollateral,
+        "Transfer too much, you don't have enough borrowing power",
+    )?;
+
+    let destination_info = ctx.accounts.destination_sundial_profile.to_account_info();
+    let mut destination = SundialProfileMut::load(&destination_info)?;
+    log_then_prop_err!(update_or_insert(
+        &mut destination.collaterals,
+        |c| c.sundial_collateral == collateral_key,
+        |c| c.asset.add_amount(transfer_amount),
+        || SundialProfileCollateral::init_collateral(
+            transfer_amount,
+            &ctx.accounts.sundial_collateral,
+            &ctx.accounts.clock
+        )
+    ));
+
+    emit!(TransferSundialCollateralEvent {
+        sundial_collateral: collateral_key,
+        source_profile: ctx.accounts.sundial_profile.key(),
+        destination_profile: ctx.accounts.destination_sundial_profile.key(),
+        user_wallet: ctx.accounts.user.key(),
+        amount: transfer_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TransferSundialCollateralEvent {
+    #[index]
+    pub sundial_collateral: Pubkey,
+    pub source_profile: Pubkey,
+    pub destination_profile: Pubkey,
+    pub user_wallet: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,88 @@
+use crate::error::SundialError;
+use crate::state::{SundialMarket, SundialProfile, SundialProfileMut};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+
+/// Hand a [SundialProfile] with all its collaterals and loans to `new_user`. The profile address
+/// depends on its user, so the position moves into a new profile of `new_user` at
+/// `sub_account_id` and the old one is closed, its rent going back to `user`. The delegates of
+/// `user` have to be closed first. Not possible while the [SundialMarket] is paused.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction(sub_account_id: u16, bump: u8)]
+pub struct TransferSundialProfile<'info> {
+    #[account(
+        mut,
+        has_one = user @ SundialError::InvalidProfileUser,
+        has_one = sundial_market @ SundialError::SundialMarketNotMatch,
+        close = user
+    )]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub new_user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = new_user,
+        space = sundial_profile.to_account_info().data_len(),
+        seeds=[
+            sundial_market.key().as_ref(),
+            new_user.key().as_ref(),
+            b"profile",
+            sub_account_id.to_le_bytes().as_ref()
+        ],
+        bump = bump
+    )]
+    pub new_sundial_profile: AccountLoader<'info, SundialProfile>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn process_transfer_sundial_profile(
+    ctx: Context<TransferSundialProfile>,
+    sub_account_id: u16,
+    _bump: u8,
+) -> ProgramResult {
+    ctx.accounts
+        .sundial_market
+        .status
+        .check_not_paused("Sundial Market")?;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let new_profile_info = ctx.accounts.new_sundial_profile.to_account_info();
+    // Only a migrated profile can be copied over as is, slots included.
+    SundialProfileMut::load(&profile_info)?
+        .header
+        .check_no_delegates()?;
+    new_profile_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(&profile_info.try_borrow_data()?);
+
+    let mut new_profile = ctx.accounts.new_sundial_profile.load_mut()?;
+    new_profile.user = ctx.accounts.new_user.key();
+    new_profile.sub_account_id = sub_account_id;
+
+    emit!(TransferSundialProfileEvent {
+        profile: ctx.accounts.sundial_profile.key(),
+        new_profile: ctx.accounts.new_sundial_profile.key(),
+        user: ctx.accounts.user.key(),
+        new_user: ctx.accounts.new_user.key(),
+    });
+    Ok(())
+}
+
+#[event]
+pub struct TransferSundialProfileEvent {
+    #[index]
+    pub profile: Pubkey,
+    #[index]
+    pub new_profile: Pubkey,
+    pub user: Pubkey,
+    pub new_user: Pubkey,
+}
@@ -1,29 +1,35 @@
 use crate::error::SundialError;
 use crate::helpers::*;
-use crate::state::{SundialCollateral, SundialProfile};
+use crate::state::{SundialCollateral, SundialMarket, SundialProfile, SundialProfileMut};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialProfileMarket, CheckSundialProfileStale};
+use sundial_derives::{
+    validates, CheckSundialNotPaused, CheckSundialProfileMarket, CheckSundialProfileStale,
+};
 
 use itertools::Itertools;
 use paste::paste;
 use std::cmp::min;
+use vipers::invariant;
 
 use crate::helpers::create_transfer_cpi;
 use anchor_spl::token::transfer;
 
 /// Withdraw sundial collateral (Port LP) tokens that users have deposited.
-#[validates(check_sundial_profile_stale, check_sundial_profile_market)]
-#[derive(Accounts, Clone, CheckSundialProfileStale, CheckSundialProfileMarket)]
+#[validates(
+    check_sundial_profile_stale,
+    check_sundial_profile_market,
+    check_sundial_not_paused
+)]
+#[derive(
+    Accounts, Clone, CheckSundialProfileStale, CheckSundialProfileMarket, CheckSundialNotPaused,
+)]
 #[instruction(amount: u64)]
 pub struct WithdrawSundialCollateral<'info> {
     /// Refreshed [state::SundialProfile] containing the user's loans and collaterals
-    #[account(
-        mut,
-        has_one=user @ SundialError::InvalidProfileUser
-    )]
-    pub sundial_profile: Box<Account<'info, SundialProfile>>,
+    #[account(mut)]
+    pub sundial_profile: AccountLoader<'info, SundialProfile>,
 
     #[account(
         has_one=token_program @ SundialError::InvalidTokenProgram
@@ -52,8 +58,11 @@ pub struct WithdrawSundialCollateral<'info> {
 
     #[account(mut)]
     pub user_port_lp_wallet: Account<'info, TokenAccount>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
+    /// The profile user, or a [state::ProfileDelegate] passing its record as the last remaining
+    /// account.
     pub user: Signer<'info>,
 }
 
@@ -61,7 +70,19 @@ pub fn process_withdraw_sundial_collateral(
     ctx: Context<WithdrawSundialCollateral>,
     withdraw_amount: u64,
 ) -> ProgramResult {
-    let profile = &mut ctx.accounts.sundial_profile;
+    let profile_info = ctx.accounts.sundial_profile.to_account_info();
+    let mut profile = SundialProfileMut::load(&profile_info)?;
+    let is_delegate = profile.header.check_signer(
+        ctx.accounts.sundial_profile.key(),
+        ctx.accounts.user.key(),
+        ctx.remaining_accounts.last(),
+        |p| p.withdraw,
+    )?;
+    invariant!(
+        !is_delegate || ctx.accounts.user_port_lp_wallet.owner == profile.header.user,
+        SundialError::ProfileDelegateNotPermitted,
+        "Delegates can only withdraw to the profile user"
+    );
     let collateral_key = ctx.accounts.sundial_collateral.key();
 
     let (pos, collateral) = vipers::unwrap_opt!(
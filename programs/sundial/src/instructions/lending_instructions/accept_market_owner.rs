@@ -0,0 +1,52 @@
+use crate::state::SundialMarket;
+use anchor_lang::prelude::*;
+
+use sundial_derives::validates;
+
+use crate::error::SundialError;
+
+/// Accept the ownership of the [SundialMarket] proposed in [sundial::propose_market_owner].
+#[validates(check_pending_owner)]
+#[derive(Accounts, Clone)]
+pub struct AcceptMarketOwner<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub pending_owner: Signer<'info>,
+}
+
+impl<'info> AcceptMarketOwner<'info> {
+    fn check_pending_owner(&self) -> ProgramResult {
+        vipers::assert_keys_eq!(
+            self.sundial_market.pending_owner,
+            self.pending_owner.key(),
+            SundialError::InvalidPendingOwner,
+            "Not the pending Sundial Market owner"
+        );
+        Ok(())
+    }
+}
+
+pub fn process_accept_market_owner(ctx: Context<AcceptMarketOwner>) -> ProgramResult {
+    let sundial_market = &mut ctx.accounts.sundial_market;
+    let previous_owner = sundial_market.owner;
+    sundial_market.owner = sundial_market.pending_owner;
+    sundial_market.pending_owner = Pubkey::default();
+    emit!(AcceptMarketOwnerEvent {
+        sundial_market: sundial_market.key(),
+        previous_owner,
+        owner: sundial_market.owner,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::accept_market_owner].
+pub struct AcceptMarketOwnerEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// Owner before the transfer.
+    pub previous_owner: Pubkey,
+    /// New owner.
+    pub owner: Pubkey,
+}
@@ -0,0 +1,49 @@
+use crate::helpers::*;
+use crate::state::{PendingConfigChange, RegistryEntryKind, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Drop a [PendingConfigChange] before it is executed.
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        mut,
+        has_one = sundial_market,
+        has_one = payer,
+        close = payer
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    pub sundial_market: Account<'info, SundialMarket>,
+
+    /// CHECK: Receives the rent of the [PendingConfigChange].
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn process_cancel_config_change(ctx: Context<CancelConfigChange>) -> ProgramResult {
+    let pending = &ctx.accounts.pending_config_change;
+    emit!(CancelConfigChangeEvent {
+        pending_config_change: pending.key(),
+        target: pending.target,
+        kind: pending.kind,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::cancel_config_change].
+pub struct CancelConfigChangeEvent {
+    /// The [PendingConfigChange].
+    #[index]
+    pub pending_config_change: Pubkey,
+    /// The [Sundial] or [SundialCollateral].
+    pub target: Pubkey,
+    pub kind: RegistryEntryKind,
+}
@@ -0,0 +1,57 @@
+use crate::helpers::*;
+use crate::state::SundialMarket;
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Raise the delay of [PendingConfigChange]s queued from now on. It can't be lowered,
+/// otherwise the owner could skip the delay right before queueing a change, nor go over
+/// [MAX_CONFIG_CHANGE_DELAY] so the execution time of a change always fits in a timestamp.
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[instruction(config_change_delay: i64)]
+pub struct ChangeConfigChangeDelay<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_config_change_delay(
+    ctx: Context<ChangeConfigChangeDelay>,
+    config_change_delay: i64,
+) -> ProgramResult {
+    vipers::invariant!(
+        config_change_delay >= ctx.accounts.sundial_market.config_change_delay,
+        SundialError::InvalidConfigChangeDelay,
+        &format!(
+            "New delay {:?} is shorter than the current one {:?}",
+            config_change_delay, ctx.accounts.sundial_market.config_change_delay
+        )
+    );
+    vipers::invariant!(
+        config_change_delay <= MAX_CONFIG_CHANGE_DELAY,
+        SundialError::InvalidConfigChangeDelay,
+        &format!(
+            "New delay {:?} is over {:?}",
+            config_change_delay, MAX_CONFIG_CHANGE_DELAY
+        )
+    );
+    ctx.accounts.sundial_market.config_change_delay = config_change_delay;
+    emit!(ChangeConfigChangeDelayEvent {
+        sundial_market: ctx.accounts.sundial_market.key(),
+        config_change_delay,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_config_change_delay].
+pub struct ChangeConfigChangeDelayEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// New delay in seconds.
+    pub config_change_delay: i64,
+}
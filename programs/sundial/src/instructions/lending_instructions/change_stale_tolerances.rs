@@ -0,0 +1,42 @@
+use crate::helpers::*;
+use crate::state::{StaleTolerances, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Change the default staleness tolerances of the [SundialMarket]. Applied at once rather than
+/// queued, [MAX_STALE_TOL] bounds them.
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[sundial_role(risk_admin)]
+#[instruction(stale_tolerances: StaleTolerances)]
+pub struct ChangeStaleTolerances<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_stale_tolerances(
+    ctx: Context<ChangeStaleTolerances>,
+    stale_tolerances: StaleTolerances,
+) -> ProgramResult {
+    log_then_prop_err!(stale_tolerances.sanity_check());
+    ctx.accounts.sundial_market.stale_tolerances = stale_tolerances;
+    emit!(ChangeStaleTolerancesEvent {
+        sundial_market: ctx.accounts.sundial_market.key(),
+        stale_tolerances,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_stale_tolerances].
+pub struct ChangeStaleTolerancesEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// New [StaleTolerances].
+    pub stale_tolerances: StaleTolerances,
+}
@@ -1,7 +1,7 @@
 use crate::helpers::*;
 use crate::state::SundialConfig;
 use crate::state::SundialMarket;
-use crate::state::{Fee, LiquidityCap, Sundial};
+use crate::state::{Fee, LiquidityCap, PendingConfigChange, RegistryEntryKind, Sundial};
 use anchor_lang::prelude::*;
 
 use crate::instructions::SundialInitConfigParams;
@@ -9,44 +9,80 @@ use crate::instructions::SundialInitConfigParams;
 use sundial_derives::{validates, CheckSundialOwner};
 
 use crate::error::SundialError;
+use vipers::unwrap_int;
 
+/// Queue a new [SundialConfig], applied by [sundial::execute_config_change] once the
+/// `config_change_delay` of the [SundialMarket] has passed.
 #[validates(check_sundial_owner)]
 #[derive(Accounts, Clone, CheckSundialOwner)]
-#[instruction(config: SundialInitConfigParams)]
+#[sundial_role(fee_admin)]
+#[instruction(config: SundialInitConfigParams, bump: u8)]
 pub struct ChangeSundialConfig<'info> {
-    #[account(mut)]
     pub sundial: Account<'info, Sundial>,
     pub sundial_market: Account<'info, SundialMarket>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial.key().as_ref(),
+            b"pending_config"
+        ],
+        bump = bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn process_change_sundial_config(
     ctx: Context<ChangeSundialConfig>,
     config: SundialInitConfigParams,
+    _bump: u8,
 ) -> ProgramResult {
-    ctx.accounts.sundial.config.lending_fee = Fee {
-        bips: config.lending_fee,
-    };
-    ctx.accounts.sundial.config.borrow_fee = Fee {
-        bips: config.borrow_fee,
+    let pending = &mut ctx.accounts.pending_config_change;
+    pending.sundial_market = ctx.accounts.sundial_market.key();
+    pending.target = ctx.accounts.sundial.key();
+    pending.payer = ctx.accounts.owner.key();
+    pending.kind = RegistryEntryKind::Sundial;
+    pending.sundial_config = SundialConfig {
+        lending_fee: Fee {
+            bips: config.lending_fee,
+        },
+        borrow_fee: Fee {
+            bips: config.borrow_fee,
+        },
+        liquidity_cap: LiquidityCap {
+            lamports: config.liquidity_cap,
+        },
+        ..ctx.accounts.sundial.config.clone()
     };
-    ctx.accounts.sundial.config.liquidity_cap = LiquidityCap {
-        lamports: config.liquidity_cap,
-    };
-    emit!(ChangeSundialConfigEvent {
-        sundial: ctx.accounts.sundial.key(),
-        config: ctx.accounts.sundial.config.clone(),
+    pending.queued_unix_timestamp = ctx.accounts.clock.unix_timestamp;
+    pending.execute_after_unix_timestamp = unwrap_int!(ctx
+        .accounts
+        .clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.sundial_market.config_change_delay));
+
+    emit!(QueueConfigChangeEvent {
+        pending_config_change: pending.key(),
+        target: pending.target,
+        kind: pending.kind,
+        execute_after_unix_timestamp: pending.execute_after_unix_timestamp,
     });
     Ok(())
 }
 
 #[event]
-/// Event called in [sundial::change_sundial_config].
-pub struct ChangeSundialConfigEvent {
-    /// The [Sundial].
+/// Event called in [sundial::change_sundial_config] and [sundial::change_sundial_collateral_config].
+pub struct QueueConfigChangeEvent {
+    /// The [PendingConfigChange].
     #[index]
-    pub sundial: Pubkey,
-    /// New [EigenParams].
-    pub config: SundialConfig,
+    pub pending_config_change: Pubkey,
+    /// The [Sundial] or [SundialCollateral].
+    pub target: Pubkey,
+    pub kind: RegistryEntryKind,
+    /// Earliest time the change can be executed.
+    pub execute_after_unix_timestamp: i64,
 }
@@ -0,0 +1,39 @@
+use crate::helpers::*;
+use crate::state::{SundialMarket, SundialMarketRoles};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Assign the risk admin, fee admin, pause guardian and listing admin of the [SundialMarket].
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[instruction(roles: SundialMarketRoles)]
+pub struct ChangeSundialMarketRoles<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_market_roles(
+    ctx: Context<ChangeSundialMarketRoles>,
+    roles: SundialMarketRoles,
+) -> ProgramResult {
+    ctx.accounts.sundial_market.roles = roles;
+    emit!(ChangeSundialMarketRolesEvent {
+        sundial_market: ctx.accounts.sundial_market.key(),
+        roles,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_market_roles].
+pub struct ChangeSundialMarketRolesEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// New [SundialMarketRoles].
+    pub roles: SundialMarketRoles,
+}
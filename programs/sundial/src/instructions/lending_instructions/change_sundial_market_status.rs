@@ -0,0 +1,40 @@
+use crate::helpers::*;
+use crate::state::{MarketStatus, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Change the status of the whole [SundialMarket], e.g., pause it when Port or Pyth misbehave.
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[sundial_role(pause_guardian)]
+#[instruction(status: MarketStatus)]
+pub struct ChangeSundialMarketStatus<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_market_status(
+    ctx: Context<ChangeSundialMarketStatus>,
+    status: MarketStatus,
+) -> ProgramResult {
+    ctx.accounts.sundial_market.status = status;
+    emit!(ChangeSundialMarketStatusEvent {
+        sundial_market: ctx.accounts.sundial_market.key(),
+        status,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_market_status].
+pub struct ChangeSundialMarketStatusEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// New [MarketStatus].
+    pub status: MarketStatus,
+}
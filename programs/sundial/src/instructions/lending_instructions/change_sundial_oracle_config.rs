@@ -0,0 +1,75 @@
+use crate::helpers::*;
+use crate::oracle::{OraclePolicy, OracleSource};
+use crate::state::{Sundial, SundialMarket, SundialOracleConfig};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SundialOracleConfigParams {
+    pub max_confidence_bips: u16,
+    pub use_upper_bound_price: bool,
+    pub primary_source: OracleSource,
+    pub fallback_source: OracleSource,
+    pub fallback_oracle: Pubkey,
+    pub policy: OraclePolicy,
+    pub max_deviation_bips: u16,
+}
+
+impl From<SundialOracleConfigParams> for SundialOracleConfig {
+    fn from(config: SundialOracleConfigParams) -> Self {
+        SundialOracleConfig {
+            max_confidence_bips: config.max_confidence_bips,
+            use_upper_bound_price: config.use_upper_bound_price,
+            primary_source: config.primary_source,
+            fallback_source: config.fallback_source,
+            policy: config.policy,
+            max_deviation_bips: config.max_deviation_bips,
+        }
+    }
+}
+
+/// Change the oracle sources of a [Sundial] and how their prices are checked before valuing loans.
+/// Not queued, switching to the fallback can't wait for the delay, see [PendingConfigChange].
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(risk_admin)]
+#[instruction(config: SundialOracleConfigParams)]
+pub struct ChangeSundialOracleConfig<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_oracle_config(
+    ctx: Context<ChangeSundialOracleConfig>,
+    config: SundialOracleConfigParams,
+) -> ProgramResult {
+    let fallback_oracle = config.fallback_oracle;
+    let oracle_config = SundialOracleConfig::from(config);
+    log_then_prop_err!(oracle_config.sanity_check(&fallback_oracle));
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.oracle_config = oracle_config;
+    sundial.fallback_oracle = fallback_oracle;
+    emit!(ChangeSundialOracleConfigEvent {
+        sundial: sundial.key(),
+        oracle_config,
+        fallback_oracle,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_oracle_config].
+pub struct ChangeSundialOracleConfigEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// New [SundialOracleConfig].
+    pub oracle_config: SundialOracleConfig,
+    /// New fallback oracle.
+    pub fallback_oracle: Pubkey,
+}
@@ -0,0 +1,43 @@
+use crate::helpers::*;
+use crate::state::{check_stale_tol, Sundial, SundialMarket};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+/// Override the oracle staleness tolerance of the [SundialMarket] for a [Sundial], zero unsets it.
+/// Like the market defaults it isn't queued.
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(risk_admin)]
+#[instruction(oracle_stale_tol: u64)]
+pub struct ChangeSundialOracleStaleTol<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_oracle_stale_tol(
+    ctx: Context<ChangeSundialOracleStaleTol>,
+    oracle_stale_tol: u64,
+) -> ProgramResult {
+    log_then_prop_err!(check_stale_tol(oracle_stale_tol));
+    ctx.accounts.sundial.oracle_stale_tol = oracle_stale_tol;
+    emit!(ChangeSundialOracleStaleTolEvent {
+        sundial: ctx.accounts.sundial.key(),
+        oracle_stale_tol,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_oracle_stale_tol].
+pub struct ChangeSundialOracleStaleTolEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// New tolerance in slots.
+    pub oracle_stale_tol: u64,
+}
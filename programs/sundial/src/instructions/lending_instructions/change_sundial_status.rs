@@ -0,0 +1,47 @@
+use crate::helpers::*;
+use crate::state::{MarketStatus, Sundial, SundialMarket, SundialMarketRegistryEntry};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use crate::error::SundialError;
+
+/// Change the status of a [Sundial] and its [SundialMarketRegistryEntry].
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(pause_guardian)]
+#[instruction(status: MarketStatus)]
+pub struct ChangeSundialStatus<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+    #[account(
+        mut,
+        constraint = registry_entry.account == sundial.key()
+    )]
+    pub registry_entry: Account<'info, SundialMarketRegistryEntry>,
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_change_sundial_status(
+    ctx: Context<ChangeSundialStatus>,
+    status: MarketStatus,
+) -> ProgramResult {
+    ctx.accounts.sundial.status = status;
+    ctx.accounts.registry_entry.status = status;
+    emit!(ChangeSundialStatusEvent {
+        sundial: ctx.accounts.sundial.key(),
+        status,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::change_sundial_status].
+pub struct ChangeSundialStatusEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// New [MarketStatus].
+    pub status: MarketStatus,
+}
@@ -0,0 +1,217 @@
+use crate::helpers::*;
+use crate::instructions::*;
+
+use crate::state::{Sundial, SundialMarket, YieldPosition};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount};
+
+use paste::paste;
+
+use port_anchor_adaptor::redeem;
+
+use crate::error::SundialError;
+
+use solana_maths::Decimal;
+use sundial_derives::{validates, CheckSundialNotPaused};
+use vipers::unwrap_int;
+
+/// Claim the interest accrued by a [YieldPosition] since its last claim. Before the [Sundial] ends the
+/// interest is measured by the Port exchange rate and paid by redeeming Port LP, afterwards the position
+/// is settled like yield tokens from the redeemed liquidity.
+#[validates(check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotPaused)]
+pub struct ClaimYieldPosition<'info> {
+    #[account(
+        mut,
+        constraint = sundial.reserve == port_accounts.reserve.key() @ SundialError::InvalidPortReserve,
+        constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram,
+        constraint = sundial.port_lending_program == port_accounts.port_lending_program.key() @ SundialError::InvalidPortLendingProgram
+    )]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(mut, has_one = sundial, has_one = owner @ SundialError::InvalidOwner)]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = sundial.bumps.port_lp_bump
+    )]
+    pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = sundial.bumps.yield_mint_bump
+    )]
+    pub yield_token_mint: Box<Account<'info, Mint>>,
+
+    pub port_accounts: PortAccounts<'info>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_claim_yield_position(ctx: Context<ClaimYieldPosition>) -> ProgramResult {
+    let is_ended = ctx.accounts.sundial.end_unix_time_stamp <= ctx.accounts.clock.unix_timestamp;
+
+    let liquidity_claimed = if is_ended {
+        let liquidity_per_yield_token =
+            log_then_prop_err!(ctx.accounts.sundial.get_liquidity_per_yield_token(
+                ctx.accounts.yield_token_mint.supply,
+                ctx.accounts.principle_token_mint.supply,
+                ctx.accounts.sundial_port_liquidity_wallet.amount,
+                ctx.accounts.sundial_port_lp_wallet.amount,
+            ));
+        let claim_amount = log_then_prop_err!(ctx
+            .accounts
+            .yield_position
+            .get_claimable_amount(liquidity_per_yield_token));
+        log_then_prop_err!(ctx.accounts.sundial.check_yield_redeemable(
+            claim_amount,
+            ctx.accounts.principle_token_mint.supply,
+            ctx.accounts.sundial_port_liquidity_wallet.amount,
+            ctx.accounts.sundial_port_lp_wallet.amount,
+        ));
+
+        if claim_amount != 0 {
+            log_then_prop_err!(transfer(
+                create_transfer_cpi(
+                    ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
+                    ctx.accounts.user_liquidity_wallet.to_account_info(),
+                    ctx.accounts.sundial_authority.to_account_info(),
+                    seeds!(ctx, sundial, authority),
+                    ctx.accounts.token_program.to_account_info(),
+                ),
+                claim_amount,
+            ));
+        }
+
+        // The position is settled, take it out of the yield token accounting.
+        let yield_position = &mut ctx.accounts.yield_position;
+        let sundial = &mut ctx.accounts.sundial;
+        sundial.yield_liquidity_redeemed_amount = unwrap_int!(sundial
+            .yield_liquidity_redeemed_amount
+            .checked_add(claim_amount));
+        sundial.yield_position_token_amount = unwrap_int!(sundial
+            .yield_position_token_amount
+            .checked_sub(yield_position.yield_token_amount));
+        sundial.yield_position_claimed_amount = unwrap_int!(sundial
+            .yield_position_claimed_amount
+            .checked_sub(yield_position.claimed_liquidity_amount));
+        yield_position.yield_token_amount = 0;
+        yield_position.claimed_liquidity_amount = 0;
+        claim_amount
+    } else {
+        let exchange_rate = log_then_prop_err!(ctx
+            .accounts
+            .port_accounts
+            .reserve
+            .collateral_exchange_rate());
+        let interest_per_yield_token = log_then_prop_err!(ctx
+            .accounts
+            .sundial
+            .get_accrued_interest_per_yield_token(exchange_rate));
+        let claim_amount = log_then_prop_err!(ctx
+            .accounts
+            .yield_position
+            .get_claimable_amount(interest_per_yield_token));
+        // Rounded down, the Port LP left has to keep covering the principal tokens.
+        let lp_amount = log_then_prop_err!(exchange_rate
+            .decimal_liquidity_to_collateral(Decimal::from(claim_amount))
+            .and_then(|d| d.try_floor_u64()));
+
+        let existed_liquidity_amount = ctx.accounts.user_liquidity_wallet.amount;
+        if lp_amount != 0 {
+            log_then_prop_err!(redeem(
+                ctx.accounts.port_accounts.create_redeem_context(
+                    ctx.accounts.user_liquidity_wallet.to_account_info(),
+                    ctx.accounts.sundial_port_lp_wallet.to_account_info(),
+                    ctx.accounts.sundial_authority.to_account_info(),
+                    ctx.accounts.clock.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    seeds!(ctx, sundial, authority),
+                ),
+                lp_amount,
+            ));
+            log_then_prop_err!(ctx.accounts.user_liquidity_wallet.reload());
+        }
+        let liquidity_amount = unwrap_int!(ctx
+            .accounts
+            .user_liquidity_wallet
+            .amount
+            .checked_sub(existed_liquidity_amount));
+
+        let yield_position = &mut ctx.accounts.yield_position;
+        yield_position.claimed_liquidity_amount = unwrap_int!(yield_position
+            .claimed_liquidity_amount
+            .checked_add(liquidity_amount));
+        let sundial = &mut ctx.accounts.sundial;
+        sundial.yield_position_claimed_amount = unwrap_int!(sundial
+            .yield_position_claimed_amount
+            .checked_add(liquidity_amount));
+        liquidity_amount
+    };
+    ctx.accounts.yield_position.last_claim_unix_time_stamp = ctx.accounts.clock.unix_timestamp;
+
+    emit!(ClaimYieldPositionEvent {
+        sundial: ctx.accounts.sundial.key(),
+        yield_position: ctx.accounts.yield_position.key(),
+        liquidity_claimed,
+        settled: is_ended,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::claim_yield_position].
+pub struct ClaimYieldPositionEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// The [YieldPosition].
+    pub yield_position: Pubkey,
+    /// Liquidity paid to the owner.
+    pub liquidity_claimed: u64,
+    /// Whether the position was settled after the [Sundial] ended.
+    pub settled: bool,
+}
@@ -0,0 +1,178 @@
+use crate::helpers::*;
+
+use crate::state::{Sundial, SundialMarket, SundialMarketRegistryEntry};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount};
+
+use sundial_derives::{validates, CheckSundialAlreadyEnd, CheckSundialOwner};
+
+use paste::paste;
+
+use crate::error::SundialError;
+
+use vipers::invariant;
+
+/// Close a fully redeemed [Sundial] together with its liquidity, lp and fee receiver wallets and its
+/// [SundialMarketRegistryEntry], all the rent goes to `receiver`. Liquidity and Port LP tokens left
+/// in the wallets go to the treasury wallets. The principal and yield token mints can't be closed
+/// by the token program, so they stay with a zero supply.
+#[validates(check_sundial_owner, check_sundial_already_end)]
+#[derive(Accounts, CheckSundialOwner, CheckSundialAlreadyEnd)]
+pub struct CloseSundial<'info> {
+    #[account(
+        mut,
+        constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram,
+        close = receiver
+    )]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(
+        mut,
+        constraint = registry_entry.account == sundial.key(),
+        close = receiver
+    )]
+    pub registry_entry: Box<Account<'info, SundialMarketRegistryEntry>>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = sundial.bumps.port_lp_bump
+    )]
+    pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"fee_receiver"
+        ],
+        bump = sundial.bumps.fee_receiver_bump
+    )]
+    pub sundial_fee_receiver_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = sundial.bumps.yield_mint_bump
+    )]
+    pub yield_token_mint: Box<Account<'info, Mint>>,
+
+    /// Receives the liquidity left in the [Sundial], e.g., rounding dust or tokens sent to it.
+    #[account(mut)]
+    pub treasury_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the Port LP tokens left in the [Sundial].
+    #[account(mut)]
+    pub treasury_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Receives the rent of the [Sundial] and its wallets.
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_close_sundial(ctx: Context<CloseSundial>) -> ProgramResult {
+    invariant!(
+        ctx.accounts.principle_token_mint.supply == 0 && ctx.accounts.yield_token_mint.supply == 0,
+        SundialError::SundialNotEmpty,
+        "Principal and yield tokens have to be redeemed first"
+    );
+
+    // Anyone can send tokens to the wallets, so what is left is swept instead of blocking the
+    // close. The fee receiver only holds principal tokens, none are left at a zero supply.
+    for (wallet, treasury_wallet) in [
+        (
+            &ctx.accounts.sundial_port_liquidity_wallet,
+            &ctx.accounts.treasury_liquidity_wallet,
+        ),
+        (
+            &ctx.accounts.sundial_port_lp_wallet,
+            &ctx.accounts.treasury_lp_wallet,
+        ),
+    ] {
+        if wallet.amount > 0 {
+            log_then_prop_err!(transfer(
+                create_transfer_cpi(
+                    wallet.to_account_info(),
+                    treasury_wallet.to_account_info(),
+                    ctx.accounts.sundial_authority.to_account_info(),
+                    seeds!(ctx, sundial, authority),
+                    ctx.accounts.token_program.to_account_info(),
+                ),
+                wallet.amount
+            ));
+        }
+    }
+
+    for wallet in [
+        ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
+        ctx.accounts.sundial_port_lp_wallet.to_account_info(),
+        ctx.accounts.sundial_fee_receiver_wallet.to_account_info(),
+    ] {
+        log_then_prop_err!(close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: wallet,
+                destination: ctx.accounts.receiver.to_account_info(),
+                authority: ctx.accounts.sundial_authority.to_account_info(),
+            },
+            seeds!(ctx, sundial, authority),
+        )));
+    }
+
+    emit!(CloseSundialEvent {
+        sundial: ctx.accounts.sundial.key(),
+        receiver: ctx.accounts.receiver.key(),
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::close_sundial].
+pub struct CloseSundialEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// Where the rent went.
+    pub receiver: Pubkey,
+}
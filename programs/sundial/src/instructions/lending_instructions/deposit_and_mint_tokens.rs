@@ -3,6 +3,7 @@ use crate::instructions::*;
 
 use crate::helpers::create_mint_to_cpi;
 use crate::state::Sundial;
+use crate::state::SundialMarket;
 use anchor_lang::prelude::*;
 use anchor_spl::token::mint_to;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -17,10 +18,10 @@ use solana_maths::{Rate, U128};
 
 use vipers::unwrap_int;
 
-use sundial_derives::{validates, CheckSundialNotEnd};
+use sundial_derives::{validates, CheckSundialActive, CheckSundialNotEnd};
 
-#[validates(check_sundial_not_end)]
-#[derive(Accounts, CheckSundialNotEnd)]
+#[validates(check_sundial_not_end, check_sundial_active)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialActive)]
 #[instruction(amount: u64)]
 pub struct DepositAndMintTokens<'info> {
     #[account(
@@ -93,6 +94,8 @@ pub struct DepositAndMintTokens<'info> {
 
     pub user_authority: Signer<'info>,
 
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
     pub token_program: Program<'info, Token>,
 
     pub clock: Sysvar<'info, Clock>,
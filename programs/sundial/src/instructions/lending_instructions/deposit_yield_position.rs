@@ -0,0 +1,84 @@
+use crate::helpers::*;
+use crate::state::{Sundial, SundialMarket, YieldPosition};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use sundial_derives::{validates, CheckSundialNotEnd, CheckSundialNotPaused};
+use vipers::unwrap_int;
+
+use crate::error::SundialError;
+
+/// Deposit yield tokens into a [YieldPosition] so the interest they accrue can be claimed before
+/// the [Sundial] ends. The yield tokens are burned, [sundial::withdraw_yield_position] mints them
+/// back.
+#[validates(check_sundial_not_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialNotPaused)]
+#[instruction(amount: u64)]
+pub struct DepositYieldPosition<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(mut, has_one = sundial, has_one = owner @ SundialError::InvalidOwner)]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = sundial.bumps.yield_mint_bump
+    )]
+    pub yield_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_yield_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_deposit_yield_position(
+    ctx: Context<DepositYieldPosition>,
+    amount: u64,
+) -> ProgramResult {
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.yield_token_mint.to_account_info(),
+                to: ctx.accounts.user_yield_token_wallet.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+    ));
+
+    let yield_position = &mut ctx.accounts.yield_position;
+    yield_position.yield_token_amount =
+        unwrap_int!(yield_position.yield_token_amount.checked_add(amount));
+
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.yield_position_token_amount =
+        unwrap_int!(sundial.yield_position_token_amount.checked_add(amount));
+
+    emit!(DepositYieldPositionEvent {
+        sundial: sundial.key(),
+        yield_position: yield_position.key(),
+        amount,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::deposit_yield_position].
+pub struct DepositYieldPositionEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// The [YieldPosition].
+    pub yield_position: Pubkey,
+    /// Yield tokens deposited.
+    pub amount: u64,
+}
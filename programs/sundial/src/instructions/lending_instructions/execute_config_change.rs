@@ -0,0 +1,82 @@
+use crate::error::SundialError;
+use crate::state::{PendingConfigChange, RegistryEntryKind, Sundial, SundialCollateral};
+use anchor_lang::prelude::*;
+
+use sundial_derives::validates;
+
+/// Apply a [PendingConfigChange] whose delay has passed, anyone can crank it.
+#[validates(check_config_change_ready)]
+#[derive(Accounts, Clone)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        has_one = target,
+        has_one = payer,
+        close = payer
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    /// CHECK: The [Sundial] or [SundialCollateral], deserialized by its kind.
+    #[account(mut)]
+    pub target: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the rent of the [PendingConfigChange].
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> ExecuteConfigChange<'info> {
+    fn check_config_change_ready(&self) -> ProgramResult {
+        vipers::invariant!(
+            self.clock.unix_timestamp >= self.pending_config_change.execute_after_unix_timestamp,
+            SundialError::ConfigChangeNotReady,
+            &format!(
+                "Config change can be executed at {:?}, current time is {:?}",
+                self.pending_config_change.execute_after_unix_timestamp, self.clock.unix_timestamp
+            )
+        );
+        Ok(())
+    }
+}
+
+pub fn process_execute_config_change(ctx: Context<ExecuteConfigChange>) -> ProgramResult {
+    let pending = &ctx.accounts.pending_config_change;
+    let target_info = ctx.accounts.target.to_account_info();
+    match pending.kind {
+        RegistryEntryKind::Sundial => {
+            let mut sundial: Account<Sundial> = Account::try_from(&target_info)?;
+            sundial.config = pending.sundial_config.clone();
+            sundial.exit(ctx.program_id)?;
+        }
+        RegistryEntryKind::SundialCollateral => {
+            let mut sundial_collateral: Account<SundialCollateral> =
+                Account::try_from(&target_info)?;
+            sundial_collateral.start_config_ramp(
+                pending.sundial_collateral_config.clone(),
+                ctx.accounts.clock.unix_timestamp,
+                pending.ramp_duration_in_seconds,
+            )?;
+            sundial_collateral.exit(ctx.program_id)?;
+        }
+    }
+
+    emit!(ExecuteConfigChangeEvent {
+        pending_config_change: pending.key(),
+        target: pending.target,
+        kind: pending.kind,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::execute_config_change].
+pub struct ExecuteConfigChangeEvent {
+    /// The [PendingConfigChange].
+    #[index]
+    pub pending_config_change: Pubkey,
+    /// The [Sundial] or [SundialCollateral].
+    pub target: Pubkey,
+    pub kind: RegistryEntryKind,
+}
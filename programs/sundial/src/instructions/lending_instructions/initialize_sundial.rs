@@ -1,7 +1,10 @@
 use crate::error::*;
 use crate::helpers::*;
-use crate::state::{Fee, LiquidityCap, SundialConfig, SundialMarket};
-use crate::state::{Sundial, SundialBumps};
+use crate::state::{
+    Fee, LiquidityCap, RegistryEntryKind, SundialConfig, SundialMarket, SundialMarketRegistry,
+    SundialMarketRegistryEntry,
+};
+use crate::state::{Sundial, SundialBumps, SUNDIAL_VERSION};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use port_anchor_adaptor::PortReserve;
@@ -35,6 +38,7 @@ impl From<SundialInitConfigParams> for SundialConfig {
 
 #[validates(check_sundial_market_owner)]
 #[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[sundial_role(listing_admin)]
 #[instruction(
     bumps: SundialBumps,
     duration_in_seconds: i64,
@@ -42,7 +46,8 @@ impl From<SundialInitConfigParams> for SundialConfig {
     config: SundialInitConfigParams,
     oracle: Pubkey,
     name: String,
-    pda_bump: u8
+    pda_bump: u8,
+    registry_entry_bump: u8
 )]
 pub struct InitializeSundial<'info> {
     #[account(
@@ -152,6 +157,24 @@ pub struct InitializeSundial<'info> {
 
     pub sundial_market: Box<Account<'info, SundialMarket>>,
 
+    #[account(
+        mut,
+        has_one = sundial_market
+    )]
+    pub sundial_market_registry: Box<Account<'info, SundialMarketRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_market_registry.key().as_ref(),
+            b"entry",
+            sundial_market_registry.entry_count.to_le_bytes().as_ref()
+        ],
+        bump = registry_entry_bump
+    )]
+    pub registry_entry: Box<Account<'info, SundialMarketRegistryEntry>>,
+
     pub rent: Sysvar<'info, Rent>,
 
     #[account(
@@ -170,6 +193,7 @@ pub fn process_initialize_sundial(
     oracle: Pubkey,
     _name: String,
     _pda_bump: u8,
+    _registry_entry_bump: u8,
 ) -> ProgramResult {
     let sundial = &mut ctx.accounts.sundial;
 
@@ -190,8 +214,18 @@ pub fn process_initialize_sundial(
     sundial.config = config.into();
     sundial.sundial_market = ctx.accounts.sundial_market.key();
     sundial.oracle = oracle;
+    sundial.version = SUNDIAL_VERSION;
+    sundial.registered = true;
     sundial.config.liquidity_decimals = ctx.accounts.port_liquidity_mint.decimals;
 
+    let registry_entry = &mut ctx.accounts.registry_entry;
+    registry_entry.registry = ctx.accounts.sundial_market_registry.key();
+    registry_entry.index = log_then_prop_err!(ctx.accounts.sundial_market_registry.next_index());
+    registry_entry.kind = RegistryEntryKind::Sundial;
+    registry_entry.account = sundial.key();
+    registry_entry.reserve = sundial.reserve;
+    registry_entry.maturity_unix_timestamp = end_unix_time_stamp;
+
     emit!(InitializeSundialEvent {
         sundial: sundial.key(),
         duration_in_seconds,
@@ -0,0 +1,35 @@
+use crate::state::{SundialMarket, SundialMarketRegistry};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+
+/// Initialize the [SundialMarketRegistry] of a [SundialMarket], anyone can pay for it.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction(bump: u8)]
+pub struct InitializeSundialMarketRegistry<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            sundial_market.key().as_ref(),
+            b"registry"
+        ],
+        bump = bump
+    )]
+    pub sundial_market_registry: Box<Account<'info, SundialMarketRegistry>>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_initialize_sundial_market_registry(
+    ctx: Context<InitializeSundialMarketRegistry>,
+    _bump: u8,
+) -> ProgramResult {
+    ctx.accounts.sundial_market_registry.sundial_market = ctx.accounts.sundial_market.key();
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::helpers::*;
+use crate::state::{Sundial, SundialMarket, YieldPosition};
+use anchor_lang::prelude::*;
+use sundial_derives::*;
+
+/// Initialize a [YieldPosition] for the owner in the given [Sundial].
+#[validates(check_sundial_not_paused)]
+#[derive(Accounts, Clone, CheckSundialNotPaused)]
+#[instruction(bump: u8)]
+pub struct InitializeYieldPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial.key().as_ref(),
+            owner.key().as_ref(),
+            b"yield_position"
+        ],
+        bump = bump
+    )]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    pub sundial: Account<'info, Sundial>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn process_initialize_yield_position(
+    ctx: Context<InitializeYieldPosition>,
+    _bump: u8,
+) -> ProgramResult {
+    let yield_position = &mut ctx.accounts.yield_position;
+    yield_position.owner = ctx.accounts.owner.key();
+    yield_position.sundial = ctx.accounts.sundial.key();
+    Ok(())
+}
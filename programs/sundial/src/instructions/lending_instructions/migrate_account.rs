@@ -0,0 +1,79 @@
+use crate::error::SundialError;
+use crate::migration::migrate_account_data;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::system_instruction;
+use std::cmp::max;
+use sundial_derives::*;
+use vipers::invariant;
+
+/// Upgrade a [SundialMarket](crate::state::SundialMarket), [Sundial](crate::state::Sundial),
+/// [SundialCollateral](crate::state::SundialCollateral) or
+/// [SundialProfile](crate::state::SundialProfile) written by an older version of the program to
+/// the current layout. Anyone can migrate an account, the payer covers the rent if it grows.
+#[validates()]
+#[derive(Accounts, Clone)]
+#[instruction()]
+pub struct MigrateAccount<'info> {
+    /// The account to migrate, checked in the handler as older layouts can't be deserialized.
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn process_migrate_account(ctx: Context<MigrateAccount>) -> ProgramResult {
+    let account_info = ctx.accounts.account.to_account_info();
+    invariant!(
+        account_info.owner == ctx.program_id,
+        SundialError::InvalidAccountMigration,
+        "Account should be owned by the Sundial program"
+    );
+    let migrated_data = match migrate_account_data(&account_info.try_borrow_data()?)? {
+        Some(migrated_data) => migrated_data,
+        None => {
+            msg!("Account is already at the latest version");
+            return Ok(());
+        }
+    };
+
+    let new_len = max(account_info.data_len(), migrated_data.len());
+    let rent_needed = ctx
+        .accounts
+        .rent
+        .minimum_balance(new_len)
+        .saturating_sub(account_info.lamports());
+    if rent_needed > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.payer.key, account_info.key, rent_needed),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                account_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    if new_len != account_info.data_len() {
+        account_info.realloc(new_len, true)?;
+    }
+
+    account_info.try_borrow_mut_data()?[..migrated_data.len()].copy_from_slice(&migrated_data);
+    emit!(AccountMigratedEvent {
+        account: account_info.key(),
+        data_len: new_len as u64,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::migrate_account].
+pub struct AccountMigratedEvent {
+    /// The migrated account.
+    #[index]
+    pub account: Pubkey,
+    /// Account size after the migration.
+    pub data_len: u64,
+}
@@ -1,18 +1,62 @@
+mod accept_market_owner;
+mod cancel_config_change;
+mod change_config_change_delay;
+mod change_stale_tolerances;
 mod change_sundial_config;
+mod change_sundial_market_roles;
+mod change_sundial_market_status;
+mod change_sundial_oracle_config;
+mod change_sundial_oracle_stale_tol;
+mod change_sundial_status;
+mod claim_yield_position;
+mod close_sundial;
 mod deposit_and_mint_tokens;
+mod deposit_yield_position;
+mod execute_config_change;
 mod initialize_sundial;
 mod initialize_sundial_market;
+mod initialize_sundial_market_registry;
+mod initialize_yield_position;
+mod migrate_account;
+mod propose_market_owner;
+mod recombine_tokens;
 mod redeem_lp;
 mod redeem_principle_token;
 mod redeem_yield_token;
+mod register_existing_sundial;
+mod rollover_principal;
+mod withdraw_sundial_fees;
+mod withdraw_yield_position;
 
+pub use accept_market_owner::*;
+pub use cancel_config_change::*;
+pub use change_config_change_delay::*;
+pub use change_stale_tolerances::*;
 pub use change_sundial_config::*;
+pub use change_sundial_market_roles::*;
+pub use change_sundial_market_status::*;
+pub use change_sundial_oracle_config::*;
+pub use change_sundial_oracle_stale_tol::*;
+pub use change_sundial_status::*;
+pub use claim_yield_position::*;
+pub use close_sundial::*;
 pub use deposit_and_mint_tokens::*;
+pub use deposit_yield_position::*;
+pub use execute_config_change::*;
 pub use initialize_sundial::*;
 pub use initialize_sundial_market::*;
+pub use initialize_sundial_market_registry::*;
+pub use initialize_yield_position::*;
+pub use migrate_account::*;
+pub use propose_market_owner::*;
+pub use recombine_tokens::*;
 pub use redeem_lp::*;
 pub use redeem_principle_token::*;
 pub use redeem_yield_token::*;
+pub use register_existing_sundial::*;
+pub use rollover_principal::*;
+pub use withdraw_sundial_fees::*;
+pub use withdraw_yield_position::*;
 
 use crate::error::*;
 
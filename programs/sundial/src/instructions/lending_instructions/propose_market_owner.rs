@@ -0,0 +1,42 @@
+use crate::helpers::*;
+use crate::state::SundialMarket;
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialMarketOwner};
+
+use crate::error::SundialError;
+
+/// Propose a new owner for the [SundialMarket], it only takes over after [sundial::accept_market_owner].
+#[validates(check_sundial_market_owner)]
+#[derive(Accounts, Clone, CheckSundialMarketOwner)]
+#[instruction(new_owner: Pubkey)]
+pub struct ProposeMarketOwner<'info> {
+    #[account(mut)]
+    pub sundial_market: Account<'info, SundialMarket>,
+    pub owner: Signer<'info>,
+}
+
+pub fn process_propose_market_owner(
+    ctx: Context<ProposeMarketOwner>,
+    new_owner: Pubkey,
+) -> ProgramResult {
+    ctx.accounts.sundial_market.pending_owner = new_owner;
+    emit!(ProposeMarketOwnerEvent {
+        sundial_market: ctx.accounts.sundial_market.key(),
+        owner: ctx.accounts.owner.key(),
+        pending_owner: new_owner,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::propose_market_owner].
+pub struct ProposeMarketOwnerEvent {
+    /// The [SundialMarket].
+    #[index]
+    pub sundial_market: Pubkey,
+    /// Current owner.
+    pub owner: Pubkey,
+    /// Proposed owner.
+    pub pending_owner: Pubkey,
+}
@@ -0,0 +1,165 @@
+use crate::helpers::*;
+use crate::instructions::*;
+
+use crate::state::Sundial;
+use crate::state::SundialMarket;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use sundial_derives::{validates, CheckSundialNotEnd, CheckSundialNotPaused};
+
+use paste::paste;
+
+use port_anchor_adaptor::redeem;
+
+use crate::error::SundialError;
+
+use vipers::unwrap_int;
+
+/// Burn the same amount of principal and yield tokens before the [Sundial] ends,
+/// and get back the Port LP they were minted with, redeemed to liquidity at the current exchange rate.
+#[validates(check_sundial_not_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialNotPaused)]
+#[instruction(amount: u64)]
+pub struct RecombineTokens<'info> {
+    #[account(
+        constraint = sundial.reserve == port_accounts.reserve.key() @ SundialError::InvalidPortReserve,
+        constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram,
+        constraint = sundial.port_lending_program == port_accounts.port_lending_program.key() @ SundialError::InvalidPortLendingProgram
+    )]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = sundial.bumps.port_lp_bump
+    )]
+    pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = sundial.bumps.yield_mint_bump
+    )]
+    pub yield_token_mint: Box<Account<'info, Mint>>,
+
+    pub port_accounts: PortAccounts<'info>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_yield_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user_authority: Signer<'info>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_recombine_tokens(ctx: Context<RecombineTokens>, amount: u64) -> ProgramResult {
+    let lp_amount = log_then_prop_err!(ctx.accounts.sundial.get_recombine_lp_amount(amount));
+    vipers::invariant!(
+        lp_amount <= ctx.accounts.sundial_port_lp_wallet.amount,
+        SundialError::InvalidRecombineAmount,
+        &format!(
+            "Recombine {:?} principal tokens needs {:?} lp, only {:?} left",
+            amount, lp_amount, ctx.accounts.sundial_port_lp_wallet.amount
+        )
+    );
+
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.principle_token_mint.to_account_info(),
+                to: ctx.accounts.user_principle_token_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        amount,
+    ));
+
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.yield_token_mint.to_account_info(),
+                to: ctx.accounts.user_yield_token_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        amount,
+    ));
+
+    let existed_liquidity_amount = ctx.accounts.user_liquidity_wallet.amount;
+    log_then_prop_err!(redeem(
+        ctx.accounts.port_accounts.create_redeem_context(
+            ctx.accounts.user_liquidity_wallet.to_account_info(),
+            ctx.accounts.sundial_port_lp_wallet.to_account_info(),
+            ctx.accounts.sundial_authority.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            seeds!(ctx, sundial, authority),
+        ),
+        lp_amount,
+    ));
+    log_then_prop_err!(ctx.accounts.user_liquidity_wallet.reload());
+
+    emit!(RecombineTokensEvent {
+        sundial: ctx.accounts.sundial.key(),
+        principal_burned: amount,
+        yield_burned: amount,
+        lp_redeemed: lp_amount,
+        liquidity_redeemed: unwrap_int!(ctx
+            .accounts
+            .user_liquidity_wallet
+            .amount
+            .checked_sub(existed_liquidity_amount)),
+    });
+    Ok(())
+}
+
+#[event]
+pub struct RecombineTokensEvent {
+    #[index]
+    pub sundial: Pubkey,
+    pub principal_burned: u64,
+    pub yield_burned: u64,
+    /// The amount of Port LP redeemed from [Sundial].
+    pub lp_redeemed: u64,
+    /// The amount of liquidity the user received.
+    pub liquidity_redeemed: u64,
+}
@@ -2,10 +2,11 @@ use crate::helpers::*;
 use crate::instructions::*;
 
 use crate::state::Sundial;
+use crate::state::SundialMarket;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialAlreadyEnd};
+use sundial_derives::{validates, CheckSundialAlreadyEnd, CheckSundialNotPaused};
 
 use paste::paste;
 
@@ -13,11 +14,18 @@ use port_anchor_adaptor::redeem;
 
 use crate::error::SundialError;
 
-#[validates(check_sundial_already_end)]
-#[derive(Accounts, CheckSundialAlreadyEnd)]
-#[instruction()]
+use std::cmp::min;
+
+use vipers::unwrap_int;
+
+/// Redeem Port LP of an ended [Sundial] into liquidity. It will redeem min(amount, lp left), so it
+/// can be done in parts when the Port reserve does not have enough liquidity to redeem all at once.
+#[validates(check_sundial_already_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialAlreadyEnd, CheckSundialNotPaused)]
+#[instruction(amount: u64)]
 pub struct RedeemLp<'info> {
     #[account(
+        mut,
         constraint = sundial.reserve == port_accounts.reserve.key() @ SundialError::InvalidPortReserve,
         constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram,
         constraint = sundial.port_lending_program == port_accounts.port_lending_program.key() @ SundialError::InvalidPortLendingProgram
@@ -56,12 +64,15 @@ pub struct RedeemLp<'info> {
 
     pub port_accounts: PortAccounts<'info>,
 
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     #[account(executable)]
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
 }
 
-pub fn process_redeem_lp(ctx: Context<RedeemLp>) -> ProgramResult {
+pub fn process_redeem_lp(ctx: Context<RedeemLp>, amount: u64) -> ProgramResult {
+    let lp_amount = min(ctx.accounts.sundial_port_lp_wallet.amount, amount);
+    let existed_liquidity_amount = ctx.accounts.sundial_port_liquidity_wallet.amount;
     log_then_prop_err!(redeem(
         ctx.accounts.port_accounts.create_redeem_context(
             ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
@@ -71,12 +82,26 @@ pub fn process_redeem_lp(ctx: Context<RedeemLp>) -> ProgramResult {
             ctx.accounts.token_program.to_account_info(),
             seeds!(ctx, sundial, authority),
         ),
-        ctx.accounts.sundial_port_lp_wallet.amount,
+        lp_amount,
     ));
+    log_then_prop_err!(ctx.accounts.sundial_port_liquidity_wallet.reload());
+    let liquidity_amount = unwrap_int!(ctx
+        .accounts
+        .sundial_port_liquidity_wallet
+        .amount
+        .checked_sub(existed_liquidity_amount));
+
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.redeemed_lp_amount = unwrap_int!(sundial.redeemed_lp_amount.checked_add(lp_amount));
+    sundial.redeemed_liquidity_amount = unwrap_int!(sundial
+        .redeemed_liquidity_amount
+        .checked_add(liquidity_amount));
 
     emit!(RedeemLpEvent {
-        sundial: ctx.accounts.sundial.key(),
+        sundial: sundial.key(),
         timestamp: ctx.accounts.clock.unix_timestamp,
+        lp_redeemed: lp_amount,
+        liquidity_redeemed: liquidity_amount,
     });
 
     Ok(())
@@ -89,4 +114,8 @@ pub struct RedeemLpEvent {
     pub sundial: Pubkey,
     /// The current timestamp.
     pub timestamp: i64,
+    /// The amount of Port LP redeemed this time.
+    pub lp_redeemed: u64,
+    /// The amount of liquidity received this time.
+    pub liquidity_redeemed: u64,
 }
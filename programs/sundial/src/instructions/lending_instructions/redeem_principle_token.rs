@@ -1,10 +1,11 @@
 use crate::helpers::*;
 
 use crate::state::Sundial;
+use crate::state::SundialMarket;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialAlreadyEnd};
+use sundial_derives::{validates, CheckSundialAlreadyEnd, CheckSundialNotPaused};
 
 use paste::paste;
 
@@ -13,8 +14,10 @@ use anchor_spl::token::{burn, transfer, Burn};
 
 use crate::error::SundialError;
 
-#[validates(check_sundial_already_end)]
-#[derive(Accounts, CheckSundialAlreadyEnd)]
+use vipers::unwrap_int;
+
+#[validates(check_sundial_already_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialAlreadyEnd, CheckSundialNotPaused)]
 #[instruction(amount: u64)]
 pub struct RedeemPrincipleToken<'info> {
     #[account(
@@ -39,19 +42,16 @@ pub struct RedeemPrincipleToken<'info> {
             sundial.key().as_ref(),
             b"liquidity"
         ],
-        bump = sundial.bumps.port_liquidity_bump,
-        constraint = sundial_port_liquidity_wallet.amount != 0 @ SundialError::NotRedeemLpYet
+        bump = sundial.bumps.port_liquidity_bump
     )]
     pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
         seeds = [
             sundial.key().as_ref(),
             b"lp"
         ],
-        bump = sundial.bumps.port_lp_bump,
-        constraint = sundial_port_lp_wallet.amount == 0 @ SundialError::NotRedeemLpYet
+        bump = sundial.bumps.port_lp_bump
     )]
     pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
 
@@ -72,6 +72,7 @@ pub struct RedeemPrincipleToken<'info> {
     pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
 
     pub user_authority: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
 }
@@ -80,6 +81,12 @@ pub fn process_redeem_principle_tokens(
     ctx: Context<RedeemPrincipleToken>,
     amount: u64,
 ) -> ProgramResult {
+    log_then_prop_err!(ctx.accounts.sundial.check_principle_redeemable(
+        amount,
+        ctx.accounts.principle_token_mint.supply,
+        ctx.accounts.sundial_port_lp_wallet.amount,
+    ));
+
     log_then_prop_err!(burn(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -103,6 +110,10 @@ pub fn process_redeem_principle_tokens(
         amount,
     ));
 
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.principle_redeemed_amount =
+        unwrap_int!(sundial.principle_redeemed_amount.checked_add(amount));
+
     emit!(RedeemPrincipalTokenEvent {
         sundial: ctx.accounts.sundial.key(),
         principle_burned: amount,
@@ -1,10 +1,11 @@
 use crate::helpers::*;
 
 use crate::state::Sundial;
+use crate::state::SundialMarket;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
-use sundial_derives::{validates, CheckSundialAlreadyEnd};
+use sundial_derives::{validates, CheckSundialAlreadyEnd, CheckSundialNotPaused};
 
 use paste::paste;
 
@@ -13,14 +14,13 @@ use anchor_spl::token::{burn, transfer, Burn};
 
 use crate::error::SundialError;
 
-use solana_maths::{Decimal, TryDiv, TryMul};
-
 use vipers::unwrap_int;
-#[validates(check_sundial_already_end)]
-#[derive(Accounts, CheckSundialAlreadyEnd)]
+#[validates(check_sundial_already_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialAlreadyEnd, CheckSundialNotPaused)]
 #[instruction(amount: u64)]
 pub struct RedeemYieldToken<'info> {
     #[account(
+        mut,
         constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram
     )]
     pub sundial: Account<'info, Sundial>,
@@ -41,19 +41,16 @@ pub struct RedeemYieldToken<'info> {
             sundial.key().as_ref(),
             b"liquidity"
         ],
-        bump = sundial.bumps.port_liquidity_bump,
-        constraint = sundial_port_liquidity_wallet.amount != 0 @ SundialError::NotRedeemLpYet
+        bump = sundial.bumps.port_liquidity_bump
     )]
     pub sundial_port_liquidity_wallet: Account<'info, TokenAccount>,
 
     #[account(
-        mut,
         seeds = [
             sundial.key().as_ref(),
             b"lp"
         ],
-        bump = sundial.bumps.port_lp_bump,
-        constraint = sundial_port_lp_wallet.amount == 0 @ SundialError::NotRedeemLpYet
+        bump = sundial.bumps.port_lp_bump
     )]
     pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
 
@@ -83,23 +80,19 @@ pub struct RedeemYieldToken<'info> {
     #[account(mut)]
     pub user_yield_token_wallet: Box<Account<'info, TokenAccount>>,
     pub user_authority: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
     pub token_program: Program<'info, Token>,
     pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn process_redeem_yield_tokens(ctx: Context<RedeemYieldToken>, amount: u64) -> ProgramResult {
-    let principle_supply_amount = ctx.accounts.principle_token_mint.supply;
-    let liquidity_of_yield = unwrap_int!(ctx
-        .accounts
-        .sundial_port_liquidity_wallet
-        .amount
-        .checked_sub(principle_supply_amount));
-    let yield_supply_amount = ctx.accounts.yield_token_mint.supply;
-    let amount_to_redeem = log_then_prop_err!(log_then_prop_err!(log_then_prop_err!(
-        Decimal::from(liquidity_of_yield).try_div(yield_supply_amount)
-    )
-    .try_mul(amount))
-    .try_floor_u64());
+    let amount_to_redeem = log_then_prop_err!(ctx.accounts.sundial.get_yield_redeem_amount(
+        amount,
+        ctx.accounts.yield_token_mint.supply,
+        ctx.accounts.principle_token_mint.supply,
+        ctx.accounts.sundial_port_liquidity_wallet.amount,
+        ctx.accounts.sundial_port_lp_wallet.amount,
+    ));
 
     log_then_prop_err!(burn(
         CpiContext::new(
@@ -125,6 +118,12 @@ pub fn process_redeem_yield_tokens(ctx: Context<RedeemYieldToken>, amount: u64)
             amount_to_redeem,
         ));
     }
+
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.yield_liquidity_redeemed_amount = unwrap_int!(sundial
+        .yield_liquidity_redeemed_amount
+        .checked_add(amount_to_redeem));
+
     emit!(RedeemYieldTokenEvent {
         sundial: ctx.accounts.sundial.key(),
         yield_burned: amount,
@@ -0,0 +1,71 @@
+use crate::error::SundialError;
+use crate::helpers::*;
+use crate::state::{
+    RegistryEntryKind, Sundial, SundialMarket, SundialMarketRegistry, SundialMarketRegistryEntry,
+    SUNDIAL_VERSION,
+};
+use anchor_lang::prelude::*;
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use vipers::invariant;
+
+/// Add a [Sundial] created before the [SundialMarketRegistry] existed to the registry, so its
+/// status can be changed and it can be closed. Each [Sundial] can only be registered once.
+#[validates(check_sundial_owner)]
+#[derive(Accounts, Clone, CheckSundialOwner)]
+#[sundial_role(listing_admin)]
+#[instruction(registry_entry_bump: u8)]
+pub struct RegisterExistingSundial<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    #[account(
+        mut,
+        has_one = sundial_market
+    )]
+    pub sundial_market_registry: Box<Account<'info, SundialMarketRegistry>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            sundial_market_registry.key().as_ref(),
+            b"entry",
+            sundial_market_registry.entry_count.to_le_bytes().as_ref()
+        ],
+        bump = registry_entry_bump
+    )]
+    pub registry_entry: Box<Account<'info, SundialMarketRegistryEntry>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_register_existing_sundial(
+    ctx: Context<RegisterExistingSundial>,
+    _registry_entry_bump: u8,
+) -> ProgramResult {
+    let sundial = &mut ctx.accounts.sundial;
+    invariant!(
+        sundial.version == SUNDIAL_VERSION,
+        SundialError::AccountNotMigrated,
+        "Migrate the Sundial before registering it"
+    );
+    invariant!(!sundial.registered, SundialError::AlreadyRegistered);
+    sundial.registered = true;
+
+    let registry_entry = &mut ctx.accounts.registry_entry;
+    registry_entry.registry = ctx.accounts.sundial_market_registry.key();
+    registry_entry.index = log_then_prop_err!(ctx.accounts.sundial_market_registry.next_index());
+    registry_entry.kind = RegistryEntryKind::Sundial;
+    registry_entry.account = sundial.key();
+    registry_entry.reserve = sundial.reserve;
+    registry_entry.maturity_unix_timestamp = sundial.end_unix_time_stamp;
+    registry_entry.status = sundial.status;
+    Ok(())
+}
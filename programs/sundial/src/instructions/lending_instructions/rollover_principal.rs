@@ -0,0 +1,283 @@
+use crate::helpers::*;
+use crate::instructions::*;
+
+use crate::state::Sundial;
+use crate::state::SundialMarket;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, mint_to, Burn, Mint, Token, TokenAccount};
+use paste::paste;
+
+use port_anchor_adaptor::deposit_reserve;
+
+use crate::error::SundialError;
+
+use port_variable_rate_lending_instructions::state::CollateralExchangeRate;
+use solana_maths::{Rate, U128};
+
+use vipers::unwrap_int;
+
+use sundial_derives::{validates, CheckSundialAlreadyEnd, CheckSundialNotPaused};
+
+/// Burn principal tokens of an ended [Sundial] and deposit the liquidity they redeem for into a later
+/// [Sundial] on the same Port reserve, minting its principal and yield tokens in one go.
+#[validates(
+    check_sundial_already_end,
+    check_new_sundial_not_end,
+    check_sundial_not_paused
+)]
+#[derive(Accounts, CheckSundialAlreadyEnd, CheckSundialNotPaused)]
+#[instruction(amount: u64)]
+pub struct RolloverPrincipal<'info> {
+    #[account(
+        mut,
+        constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram
+    )]
+    pub sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the ended [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = sundial.bumps.port_lp_bump
+    )]
+    pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = new_sundial.reserve == port_accounts.reserve.key() @ SundialError::InvalidPortReserve,
+        constraint = new_sundial.reserve == sundial.reserve @ SundialError::InvalidPortReserve,
+        constraint = new_sundial.sundial_market == sundial.sundial_market @ SundialError::SundialMarketNotMatch,
+        constraint = new_sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram,
+        constraint = new_sundial.port_lending_program == port_accounts.port_lending_program.key() @ SundialError::InvalidPortLendingProgram
+    )]
+    pub new_sundial: Box<Account<'info, Sundial>>,
+
+    #[account(
+        seeds=[
+            new_sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = new_sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the new [Sundial].
+    pub new_sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = new_sundial.bumps.port_lp_bump
+    )]
+    pub new_sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"fee_receiver"
+        ],
+        bump = new_sundial.bumps.fee_receiver_bump
+    )]
+    pub new_sundial_fee_receiver_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = new_sundial.bumps.principle_mint_bump
+    )]
+    pub new_principle_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            new_sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = new_sundial.bumps.yield_mint_bump
+    )]
+    pub new_yield_token_mint: Box<Account<'info, Mint>>,
+
+    pub port_accounts: PortAccounts<'info>,
+
+    #[account(mut)]
+    pub user_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_new_principle_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_new_yield_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub user_authority: Signer<'info>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> RolloverPrincipal<'info> {
+    fn check_new_sundial_not_end(&self) -> ProgramResult {
+        vipers::invariant!(
+            self.new_sundial.end_unix_time_stamp > self.clock.unix_timestamp
+                && self.new_sundial.end_unix_time_stamp > self.sundial.end_unix_time_stamp,
+            SundialError::AlreadyEnd,
+            &format!(
+                "New sundial ends at {:?}, current {:?}",
+                self.new_sundial.end_unix_time_stamp, self.clock.unix_timestamp
+            )
+        );
+        self.new_sundial.status.check_active("New Sundial")?;
+        Ok(())
+    }
+}
+
+pub fn process_rollover_principal(ctx: Context<RolloverPrincipal>, amount: u64) -> ProgramResult {
+    log_then_prop_err!(ctx.accounts.sundial.check_principle_redeemable(
+        amount,
+        ctx.accounts.principle_token_mint.supply,
+        ctx.accounts.sundial_port_lp_wallet.amount,
+    ));
+
+    log_then_prop_err!(burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.principle_token_mint.to_account_info(),
+                to: ctx.accounts.user_principle_token_wallet.to_account_info(),
+                authority: ctx.accounts.user_authority.to_account_info(),
+            },
+        ),
+        amount,
+    ));
+
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.principle_redeemed_amount =
+        unwrap_int!(sundial.principle_redeemed_amount.checked_add(amount));
+
+    // The redeemed liquidity goes straight from the ended [Sundial] into Port for the new one.
+    let existed_lp_amount = ctx.accounts.new_sundial_port_lp_wallet.amount;
+    log_then_prop_err!(deposit_reserve(
+        ctx.accounts.port_accounts.create_deposit_reserve_context(
+            ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
+            ctx.accounts.new_sundial_port_lp_wallet.to_account_info(),
+            ctx.accounts.sundial_authority.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            seeds!(ctx, sundial, authority),
+        ),
+        amount,
+    ));
+    log_then_prop_err!(ctx.accounts.new_sundial_port_lp_wallet.reload());
+
+    let new_sundial = &ctx.accounts.new_sundial;
+    let start_exchange_rate = CollateralExchangeRate(Rate(U128(new_sundial.start_exchange_rate)));
+    let principal_token_amount =
+        log_then_prop_err!(start_exchange_rate.collateral_to_liquidity(unwrap_int!(ctx
+            .accounts
+            .new_sundial_port_lp_wallet
+            .amount
+            .checked_sub(existed_lp_amount))));
+
+    let fee_amount = log_then_prop_err!(new_sundial.config.lending_fee.mint_fee(
+        amount,
+        create_mint_to_cpi(
+            ctx.accounts.new_principle_token_mint.to_account_info(),
+            ctx.accounts
+                .new_sundial_fee_receiver_wallet
+                .to_account_info(),
+            ctx.accounts.new_sundial_authority.to_account_info(),
+            seeds!(ctx, new_sundial, authority),
+            ctx.accounts.token_program.to_account_info(),
+        )
+    ));
+
+    log_then_prop_err!(mint_to(
+        create_mint_to_cpi(
+            ctx.accounts.new_principle_token_mint.to_account_info(),
+            ctx.accounts
+                .user_new_principle_token_wallet
+                .to_account_info(),
+            ctx.accounts.new_sundial_authority.to_account_info(),
+            seeds!(ctx, new_sundial, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        unwrap_int!(principal_token_amount.checked_sub(fee_amount))
+    ));
+
+    log_then_prop_err!(mint_to(
+        create_mint_to_cpi(
+            ctx.accounts.new_yield_token_mint.to_account_info(),
+            ctx.accounts.user_new_yield_token_wallet.to_account_info(),
+            ctx.accounts.new_sundial_authority.to_account_info(),
+            seeds!(ctx, new_sundial, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        principal_token_amount
+    ));
+
+    let liquidity_cap = &ctx.accounts.new_sundial.config.liquidity_cap;
+    log_then_prop_err!(liquidity_cap.check_mint(&mut ctx.accounts.new_principle_token_mint));
+
+    emit!(RolloverPrincipalEvent {
+        sundial: ctx.accounts.sundial.key(),
+        new_sundial: ctx.accounts.new_sundial.key(),
+        principle_burned: amount,
+        principal_token_minted: principal_token_amount,
+        yield_token_minted: principal_token_amount,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::rollover_principal].
+pub struct RolloverPrincipalEvent {
+    /// The ended [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// The [Sundial] rolled into.
+    #[index]
+    pub new_sundial: Pubkey,
+    /// Principal tokens burned in the ended [Sundial].
+    pub principle_burned: u64,
+    /// Principal tokens minted in the new [Sundial], including the fee.
+    pub principal_token_minted: u64,
+    /// Yield tokens minted in the new [Sundial].
+    pub yield_token_minted: u64,
+}
@@ -0,0 +1,170 @@
+use crate::helpers::*;
+
+use crate::state::{Sundial, SundialMarket};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use sundial_derives::{validates, CheckSundialOwner};
+
+use paste::paste;
+
+use crate::helpers::create_transfer_cpi;
+use anchor_spl::token::{burn, transfer, Burn};
+
+use crate::error::SundialError;
+
+use std::cmp::min;
+
+use vipers::unwrap_int;
+
+/// Withdraw the principal tokens collected as lending and borrowing fees into a treasury account.
+/// Before the [Sundial] ends the fees are transferred as principal tokens, afterwards they are
+/// redeemed and transferred as liquidity. It will withdraw min(amount, fee balance).
+#[validates(check_sundial_owner)]
+#[derive(Accounts, CheckSundialOwner)]
+#[instruction(amount: u64)]
+pub struct WithdrawSundialFees<'info> {
+    #[account(
+        mut,
+        constraint = sundial.token_program == token_program.key() @ SundialError::InvalidTokenProgram
+    )]
+    pub sundial: Account<'info, Sundial>,
+
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"fee_receiver"
+        ],
+        bump = sundial.bumps.fee_receiver_bump
+    )]
+    pub sundial_fee_receiver_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            sundial.key().as_ref(),
+            b"lp"
+        ],
+        bump = sundial.bumps.port_lp_bump
+    )]
+    pub sundial_port_lp_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"principle_mint"
+        ],
+        bump = sundial.bumps.principle_mint_bump
+    )]
+    pub principle_token_mint: Box<Account<'info, Mint>>,
+
+    /// Principal token wallet before the [Sundial] ends, liquidity wallet afterwards.
+    #[account(mut)]
+    pub treasury_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_withdraw_sundial_fees(
+    ctx: Context<WithdrawSundialFees>,
+    amount: u64,
+) -> ProgramResult {
+    let withdraw_amount = min(ctx.accounts.sundial_fee_receiver_wallet.amount, amount);
+    let is_ended = ctx.accounts.sundial.end_unix_time_stamp <= ctx.accounts.clock.unix_timestamp;
+
+    if is_ended {
+        log_then_prop_err!(ctx.accounts.sundial.check_principle_redeemable(
+            withdraw_amount,
+            ctx.accounts.principle_token_mint.supply,
+            ctx.accounts.sundial_port_lp_wallet.amount,
+        ));
+
+        log_then_prop_err!(burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.principle_token_mint.to_account_info(),
+                    to: ctx.accounts.sundial_fee_receiver_wallet.to_account_info(),
+                    authority: ctx.accounts.sundial_authority.to_account_info(),
+                },
+                seeds!(ctx, sundial, authority),
+            ),
+            withdraw_amount,
+        ));
+
+        log_then_prop_err!(transfer(
+            create_transfer_cpi(
+                ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
+                ctx.accounts.treasury_wallet.to_account_info(),
+                ctx.accounts.sundial_authority.to_account_info(),
+                seeds!(ctx, sundial, authority),
+                ctx.accounts.token_program.to_account_info(),
+            ),
+            withdraw_amount,
+        ));
+
+        let sundial = &mut ctx.accounts.sundial;
+        sundial.principle_redeemed_amount = unwrap_int!(sundial
+            .principle_redeemed_amount
+            .checked_add(withdraw_amount));
+    } else {
+        log_then_prop_err!(transfer(
+            create_transfer_cpi(
+                ctx.accounts.sundial_fee_receiver_wallet.to_account_info(),
+                ctx.accounts.treasury_wallet.to_account_info(),
+                ctx.accounts.sundial_authority.to_account_info(),
+                seeds!(ctx, sundial, authority),
+                ctx.accounts.token_program.to_account_info(),
+            ),
+            withdraw_amount,
+        ));
+    }
+
+    emit!(WithdrawSundialFeesEvent {
+        sundial: ctx.accounts.sundial.key(),
+        treasury_wallet: ctx.accounts.treasury_wallet.key(),
+        principal_withdrawn: if is_ended { 0 } else { withdraw_amount },
+        liquidity_withdrawn: if is_ended { withdraw_amount } else { 0 },
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::withdraw_sundial_fees].
+pub struct WithdrawSundialFeesEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// Where the fees went.
+    pub treasury_wallet: Pubkey,
+    /// Fees transferred as principal tokens.
+    pub principal_withdrawn: u64,
+    /// Fees redeemed and transferred as liquidity.
+    pub liquidity_withdrawn: u64,
+}
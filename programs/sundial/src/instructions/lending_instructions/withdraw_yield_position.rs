@@ -0,0 +1,124 @@
+use crate::helpers::*;
+use crate::state::{Sundial, SundialMarket, YieldPosition};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, transfer, Mint, Token, TokenAccount};
+use paste::paste;
+use sundial_derives::{validates, CheckSundialNotEnd, CheckSundialNotPaused};
+use vipers::unwrap_int;
+
+use crate::error::SundialError;
+
+/// Take yield tokens out of a [YieldPosition] before the [Sundial] ends, they are minted back to
+/// the owner. The liquidity already claimed for them is paid back into the [Sundial] from
+/// `user_liquidity_wallet`. After the end the position is settled by claiming it instead.
+#[validates(check_sundial_not_end, check_sundial_not_paused)]
+#[derive(Accounts, CheckSundialNotEnd, CheckSundialNotPaused)]
+#[instruction(amount: u64)]
+pub struct WithdrawYieldPosition<'info> {
+    #[account(mut)]
+    pub sundial: Account<'info, Sundial>,
+
+    #[account(mut, has_one = sundial, has_one = owner @ SundialError::InvalidOwner)]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    #[account(
+        seeds=[
+            sundial.key().as_ref(),
+            b"authority"
+        ],
+        bump = sundial.bumps.authority_bump
+    )]
+    /// CHECK: Authority of the [Sundial].
+    pub sundial_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"liquidity"
+        ],
+        bump = sundial.bumps.port_liquidity_bump
+    )]
+    pub sundial_port_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            sundial.key().as_ref(),
+            b"yield_mint"
+        ],
+        bump = sundial.bumps.yield_mint_bump
+    )]
+    pub yield_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_yield_token_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user_liquidity_wallet: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub sundial_market: Box<Account<'info, SundialMarket>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn process_withdraw_yield_position(
+    ctx: Context<WithdrawYieldPosition>,
+    amount: u64,
+) -> ProgramResult {
+    let claimed_amount = log_then_prop_err!(ctx.accounts.yield_position.withdraw(amount));
+
+    let sundial = &mut ctx.accounts.sundial;
+    sundial.yield_position_token_amount =
+        unwrap_int!(sundial.yield_position_token_amount.checked_sub(amount));
+    sundial.yield_position_claimed_amount = unwrap_int!(sundial
+        .yield_position_claimed_amount
+        .checked_sub(claimed_amount));
+
+    if claimed_amount != 0 {
+        log_then_prop_err!(transfer(
+            create_transfer_cpi(
+                ctx.accounts.user_liquidity_wallet.to_account_info(),
+                ctx.accounts.sundial_port_liquidity_wallet.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                &[],
+                ctx.accounts.token_program.to_account_info(),
+            ),
+            claimed_amount
+        ));
+    }
+
+    log_then_prop_err!(mint_to(
+        create_mint_to_cpi(
+            ctx.accounts.yield_token_mint.to_account_info(),
+            ctx.accounts.user_yield_token_wallet.to_account_info(),
+            ctx.accounts.sundial_authority.to_account_info(),
+            seeds!(ctx, sundial, authority),
+            ctx.accounts.token_program.to_account_info(),
+        ),
+        amount,
+    ));
+
+    emit!(WithdrawYieldPositionEvent {
+        sundial: ctx.accounts.sundial.key(),
+        yield_position: ctx.accounts.yield_position.key(),
+        amount,
+        liquidity_paid_back: claimed_amount,
+    });
+    Ok(())
+}
+
+#[event]
+/// Event called in [sundial::withdraw_yield_position].
+pub struct WithdrawYieldPositionEvent {
+    /// The [Sundial].
+    #[index]
+    pub sundial: Pubkey,
+    /// The [YieldPosition].
+    pub yield_position: Pubkey,
+    /// Yield tokens withdrawn.
+    pub amount: u64,
+    /// Liquidity claimed for the withdrawn yield tokens, paid back.
+    pub liquidity_paid_back: u64,
+}
@@ -1,5 +1,7 @@
+mod amm_instructions;
 mod borrowing_instructions;
 mod lending_instructions;
 
+pub use amm_instructions::*;
 pub use borrowing_instructions::*;
 pub use lending_instructions::*;
@@ -1,7 +1,13 @@
 use crate::instructions::*;
 
+use crate::state::MarketStatus;
+use crate::state::ProfileDelegatePermissions;
+use crate::state::StaleTolerances;
+use crate::state::SundialAmmBumps;
 use crate::state::SundialBumps;
 use crate::state::SundialCollateralBumps;
+use crate::state::SundialMarketRoles;
+use crate::state::SwapDirection;
 use anchor_lang::prelude::*;
 
 use sundial_derives::process;
@@ -10,6 +16,8 @@ pub mod error;
 #[macro_use]
 pub mod helpers;
 pub mod instructions;
+pub mod migration;
+pub mod oracle;
 pub mod state;
 
 declare_id!("SDLxV7m1qmoqkytqYRGY1x438AbYCqekPsPxK4kvwuk");
@@ -30,6 +38,7 @@ pub mod sundial {
         oracle: Pubkey,
         _name: String,
         _pda_bump: u8,
+        _registry_entry_bump: u8,
     ) {
     }
 
@@ -43,7 +52,60 @@ pub mod sundial {
     fn redeem_yield_tokens(ctx: Context<RedeemYieldToken>, amount: u64) {}
 
     #[process]
-    fn redeem_lp(ctx: Context<RedeemLp>) -> ProgramResult {}
+    fn redeem_lp(ctx: Context<RedeemLp>, amount: u64) {}
+
+    #[process]
+    fn recombine_tokens(ctx: Context<RecombineTokens>, amount: u64) {}
+
+    #[process]
+    fn withdraw_sundial_fees(ctx: Context<WithdrawSundialFees>, amount: u64) {}
+
+    #[process]
+    fn close_sundial(ctx: Context<CloseSundial>) {}
+
+    #[process]
+    fn rollover_principal(ctx: Context<RolloverPrincipal>, amount: u64) {}
+
+    #[process]
+    fn initialize_yield_position(ctx: Context<InitializeYieldPosition>, _bump: u8) {}
+
+    #[process]
+    fn deposit_yield_position(ctx: Context<DepositYieldPosition>, amount: u64) {}
+
+    #[process]
+    fn claim_yield_position(ctx: Context<ClaimYieldPosition>) {}
+
+    #[process]
+    fn withdraw_yield_position(ctx: Context<WithdrawYieldPosition>, amount: u64) {}
+
+    #[process]
+    fn initialize_sundial_amm(
+        ctx: Context<InitializeSundialAmm>,
+        bumps: SundialAmmBumps,
+        config: SundialAmmConfigParams,
+        _pda_bump: u8,
+    ) {
+    }
+
+    #[process]
+    fn add_amm_liquidity(
+        ctx: Context<AddAmmLiquidity>,
+        liquidity_amount: u64,
+        max_principle_amount: u64,
+    ) {
+    }
+
+    #[process]
+    fn remove_amm_liquidity(ctx: Context<RemoveAmmLiquidity>, lp_amount: u64) {}
+
+    #[process]
+    fn swap_amm(
+        ctx: Context<SwapAmm>,
+        amount_in: u64,
+        min_amount_out: u64,
+        direction: SwapDirection,
+    ) {
+    }
 
     #[process]
     fn initialize_sundial_collateral(
@@ -52,6 +114,7 @@ pub mod sundial {
         config: SundialCollateralConfigParams,
         _name: String,
         _pda_bump: u8,
+        _registry_entry_bump: u8,
     ) {
     }
 
@@ -85,6 +148,15 @@ pub mod sundial {
     #[process]
     fn repay_sundial_liquidity(ctx: Context<RepaySundialLiquidity>, max_repay_amount: u64) {}
 
+    #[process]
+    fn repay_sundial_principle(ctx: Context<RepaySundialPrinciple>, max_repay_amount: u64) {}
+
+    #[process]
+    fn transfer_sundial_collateral(ctx: Context<TransferSundialCollateral>, max_amount: u64) {}
+
+    #[process]
+    fn rollover_loan(ctx: Context<RolloverLoan>, mint_amount: u64, min_liquidity_out: u64) {}
+
     #[process]
     fn liquidate_sundial_profile(ctx: Context<LiquidateSundialProfile>) {}
 
@@ -93,22 +165,165 @@ pub mod sundial {
         ctx: Context<InitializeSundialProfile>,
         sundial_market: Pubkey,
         _bump: u8,
+        sub_account_id: u16,
+    ) {
+    }
+
+    #[process]
+    fn resize_sundial_profile(
+        ctx: Context<ResizeSundialProfile>,
+        collateral_capacity: u8,
+        loan_capacity: u8,
     ) {
     }
 
     #[process]
-    fn refresh_sundial_collateral(ctx: Context<RefreshSundialCollateral>) {}
+    fn close_sundial_profile(ctx: Context<CloseSundialProfile>) {}
+
+    #[process]
+    fn transfer_sundial_profile(
+        ctx: Context<TransferSundialProfile>,
+        sub_account_id: u16,
+        _bump: u8,
+    ) {
+    }
+
+    #[process]
+    fn initialize_profile_delegate(
+        ctx: Context<InitializeProfileDelegate>,
+        delegate: Pubkey,
+        permissions: ProfileDelegatePermissions,
+        expiry_unix_timestamp: i64,
+        _bump: u8,
+    ) {
+    }
+
+    #[process]
+    fn change_profile_delegate(
+        ctx: Context<ChangeProfileDelegate>,
+        permissions: ProfileDelegatePermissions,
+        expiry_unix_timestamp: i64,
+    ) {
+    }
+
+    #[process]
+    fn close_profile_delegate(ctx: Context<CloseProfileDelegate>) {}
+
+    #[process]
+    fn refresh_sundial_collateral<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefreshSundialCollateral<'info>>,
+    ) {
+    }
 
     #[process]
     fn change_sundial_collateral_config(
         ctx: Context<ChangeSundialCollateralConfig>,
         config: SundialCollateralConfigParams,
+        _bump: u8,
+        ramp_duration_in_seconds: i64,
     ) {
     }
 
     #[process]
-    fn change_sundial_config(ctx: Context<ChangeSundialConfig>, config: SundialInitConfigParams) {}
+    fn change_sundial_config(
+        ctx: Context<ChangeSundialConfig>,
+        config: SundialInitConfigParams,
+        _bump: u8,
+    ) {
+    }
+
+    #[process]
+    fn execute_config_change(ctx: Context<ExecuteConfigChange>) {}
+
+    #[process]
+    fn cancel_config_change(ctx: Context<CancelConfigChange>) {}
+
+    #[process]
+    fn change_config_change_delay(ctx: Context<ChangeConfigChangeDelay>, config_change_delay: i64) {
+    }
+
+    #[process]
+    fn change_sundial_market_status(ctx: Context<ChangeSundialMarketStatus>, status: MarketStatus) {
+    }
+
+    #[process]
+    fn change_sundial_status(ctx: Context<ChangeSundialStatus>, status: MarketStatus) {}
+
+    #[process]
+    fn change_sundial_collateral_status(
+        ctx: Context<ChangeSundialCollateralStatus>,
+        status: MarketStatus,
+    ) {
+    }
+
+    #[process]
+    fn migrate_account(ctx: Context<MigrateAccount>) {}
+
+    #[process]
+    fn change_stale_tolerances(
+        ctx: Context<ChangeStaleTolerances>,
+        stale_tolerances: StaleTolerances,
+    ) {
+    }
+
+    #[process]
+    fn change_sundial_oracle_config(
+        ctx: Context<ChangeSundialOracleConfig>,
+        config: SundialOracleConfigParams,
+    ) {
+    }
+
+    #[process]
+    fn change_sundial_oracle_stale_tol(
+        ctx: Context<ChangeSundialOracleStaleTol>,
+        oracle_stale_tol: u64,
+    ) {
+    }
+
+    #[process]
+    fn change_sundial_collateral_price_check(
+        ctx: Context<ChangeSundialCollateralPriceCheck>,
+        config: SundialCollateralPriceCheckParams,
+    ) {
+    }
+
+    #[process]
+    fn change_sundial_collateral_stale_tol(
+        ctx: Context<ChangeSundialCollateralStaleTol>,
+        collateral_stale_tol: u64,
+    ) {
+    }
 
     #[process]
     fn initialize_sundial_market(ctx: Context<InitializeSundialMarket>, owner: Pubkey) {}
+
+    #[process]
+    fn propose_market_owner(ctx: Context<ProposeMarketOwner>, new_owner: Pubkey) {}
+
+    #[process]
+    fn accept_market_owner(ctx: Context<AcceptMarketOwner>) {}
+
+    #[process]
+    fn change_sundial_market_roles(
+        ctx: Context<ChangeSundialMarketRoles>,
+        roles: SundialMarketRoles,
+    ) {
+    }
+
+    #[process]
+    fn initialize_sundial_market_registry(
+        ctx: Context<InitializeSundialMarketRegistry>,
+        _bump: u8,
+    ) {
+    }
+
+    #[process]
+    fn register_existing_sundial(ctx: Context<RegisterExistingSundial>, _registry_entry_bump: u8) {}
+
+    #[process]
+    fn register_existing_sundial_collateral(
+        ctx: Context<RegisterExistingSundialCollateral>,
+        _registry_entry_bump: u8,
+    ) {
+    }
 }
@@ -0,0 +1,715 @@
+use crate::error::SundialError;
+use crate::state::{
+    AssetInfo, LastUpdatedSlot, Sundial, SundialCollateral, SundialMarket, SundialProfile,
+    SundialProfileCollateral, SundialProfileCollateralConfig, SundialProfileLoan,
+    DEFAULT_PROFILE_COLLATERAL_CAPACITY, DEFAULT_PROFILE_LOAN_CAPACITY, SUNDIAL_COLLATERAL_VERSION,
+    SUNDIAL_PROFILE_VERSION, SUNDIAL_VERSION,
+};
+use anchor_lang::__private::bytemuck;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use solana_maths::{Decimal, TryDiv, TryMul, U192};
+use std::cmp::max;
+use vipers::unwrap_int;
+
+/// Size of the unversioned [SundialProfile]s created before asset prices were stored per whole
+/// token.
+pub const LEGACY_SUNDIAL_PROFILE_LEN: usize = 1356;
+
+/// Size of the [SundialMarket]s created before the market held anything but its owner.
+pub const LEGACY_SUNDIAL_MARKET_LEN: usize = 40;
+
+/// Offset of the version byte of [SundialProfile]s from version 1 on, after the discriminator,
+/// `user`, `sundial_market` and `last_update`.
+const SUNDIAL_PROFILE_VERSION_OFFSET: usize = 80;
+
+#[derive(AnchorDeserialize)]
+struct LegacyAssetInfo {
+    amount: u64,
+    total_value: [u64; 3],
+}
+
+#[derive(AnchorDeserialize)]
+struct LegacySundialProfileCollateral {
+    asset: LegacyAssetInfo,
+    sundial_collateral: Pubkey,
+    config: SundialProfileCollateralConfig,
+}
+
+#[derive(AnchorDeserialize)]
+struct LegacySundialProfileLoan {
+    asset: LegacyAssetInfo,
+    oracle: Pubkey,
+    sundial: Pubkey,
+    maturity_unix_timestamp: i64,
+    liquidity_decimals: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct LegacySundialProfile {
+    user: Pubkey,
+    sundial_market: Pubkey,
+    _last_update: LastUpdatedSlot,
+    collaterals: Vec<LegacySundialProfileCollateral>,
+    loans: Vec<LegacySundialProfileLoan>,
+}
+
+impl LegacySundialProfileLoan {
+    /// Price per whole token implied by the stored total value.
+    fn price(&self) -> Result<Decimal, ProgramError> {
+        if self.asset.amount == 0 {
+            return Ok(Decimal::zero());
+        }
+        let multiplier = unwrap_int!(10u64.checked_pow(self.liquidity_decimals as u32));
+        Decimal(U192(self.asset.total_value))
+            .try_mul(multiplier)?
+            .try_div(self.asset.amount)
+    }
+}
+
+#[derive(AnchorDeserialize)]
+struct AssetInfoV1 {
+    amount: u64,
+    price: [u64; 3],
+    decimals: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct SundialProfileCollateralV1 {
+    asset: AssetInfoV1,
+    sundial_collateral: Pubkey,
+    config: SundialProfileCollateralConfig,
+    borrowing_price: [u64; 3],
+}
+
+#[derive(AnchorDeserialize)]
+struct SundialProfileLoanV1 {
+    asset: AssetInfoV1,
+    oracle: Pubkey,
+    sundial: Pubkey,
+    maturity_unix_timestamp: i64,
+    liquidity_decimals: u8,
+}
+
+/// The Borsh layout of version 1, with the assets in vectors.
+#[derive(AnchorDeserialize)]
+struct SundialProfileV1 {
+    user: Pubkey,
+    sundial_market: Pubkey,
+    last_update: LastUpdatedSlot,
+    _version: u8,
+    collaterals: Vec<SundialProfileCollateralV1>,
+    loans: Vec<SundialProfileLoanV1>,
+}
+
+impl From<AssetInfoV1> for AssetInfo {
+    fn from(asset: AssetInfoV1) -> Self {
+        AssetInfo {
+            amount: asset.amount,
+            price: asset.price,
+            decimals: asset.decimals,
+        }
+    }
+}
+
+/// A [SundialProfile] header with its used slots, written out in the zero-copy layout.
+struct UnpackedSundialProfile {
+    header: SundialProfile,
+    collaterals: Vec<SundialProfileCollateral>,
+    loans: Vec<SundialProfileLoan>,
+}
+
+impl TryFrom<LegacySundialProfile> for UnpackedSundialProfile {
+    type Error = ProgramError;
+
+    fn try_from(legacy: LegacySundialProfile) -> Result<Self, Self::Error> {
+        let loans = legacy
+            .loans
+            .into_iter()
+            .map(|l| {
+                Ok(SundialProfileLoan {
+                    asset: AssetInfo::new(l.asset.amount, l.price()?, l.liquidity_decimals),
+                    oracle: l.oracle,
+                    sundial: l.sundial,
+                    maturity_unix_timestamp: l.maturity_unix_timestamp,
+                    liquidity_decimals: l.liquidity_decimals,
+                })
+            })
+            .collect::<Result<Vec<_>, ProgramError>>()?;
+        // The collateral decimals aren't stored in the profile, prices are zero until the next
+        // refresh which the reset `last_update_slot` requires anyway.
+        let collaterals = legacy
+            .collaterals
+            .into_iter()
+            .map(|c| SundialProfileCollateral {
+                asset: AssetInfo::new(c.asset.amount, Decimal::zero(), 0),
+                sundial_collateral: c.sundial_collateral,
+                config: c.config,
+                borrowing_price: Default::default(),
+            })
+            .collect();
+
+        Ok(UnpackedSundialProfile {
+            header: SundialProfile {
+                user: legacy.user,
+                sundial_market: legacy.sundial_market,
+                ..Default::default()
+            },
+            collaterals,
+            loans,
+        })
+    }
+}
+
+impl From<SundialProfileV1> for UnpackedSundialProfile {
+    fn from(profile: SundialProfileV1) -> Self {
+        UnpackedSundialProfile {
+            header: SundialProfile {
+                user: profile.user,
+                sundial_market: profile.sundial_market,
+                last_update_slot: profile.last_update.slot,
+                ..Default::default()
+            },
+            collaterals: profile
+                .collaterals
+                .into_iter()
+                .map(|c| SundialProfileCollateral {
+                    asset: c.asset.into(),
+                    sundial_collateral: c.sundial_collateral,
+                    config: c.config,
+                    borrowing_price: c.borrowing_price,
+                })
+                .collect(),
+            loans: profile
+                .loans
+                .into_iter()
+                .map(|l| SundialProfileLoan {
+                    asset: l.asset.into(),
+                    oracle: l.oracle,
+                    sundial: l.sundial,
+                    maturity_unix_timestamp: l.maturity_unix_timestamp,
+                    liquidity_decimals: l.liquidity_decimals,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl UnpackedSundialProfile {
+    /// Account data with at least the default capacity, zero-padded to `min_len`.
+    fn into_account_data(self, min_len: usize) -> Result<Vec<u8>, ProgramError> {
+        let UnpackedSundialProfile {
+            mut header,
+            collaterals,
+            loans,
+        } = self;
+        let collateral_capacity = max(
+            collaterals.len(),
+            DEFAULT_PROFILE_COLLATERAL_CAPACITY as usize,
+        );
+        let loan_capacity = max(loans.len(), DEFAULT_PROFILE_LOAN_CAPACITY as usize);
+        header.version = SUNDIAL_PROFILE_VERSION;
+        header.collateral_capacity = unwrap_int!(u8::try_from(collateral_capacity).ok());
+        header.loan_capacity = unwrap_int!(u8::try_from(loan_capacity).ok());
+
+        let mut collateral_slots = vec![SundialProfileCollateral::default(); collateral_capacity];
+        collateral_slots[..collaterals.len()].copy_from_slice(&collaterals);
+        let mut loan_slots = vec![SundialProfileLoan::default(); loan_capacity];
+        loan_slots[..loans.len()].copy_from_slice(&loans);
+
+        let mut data = SundialProfile::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&header));
+        data.extend_from_slice(bytemuck::cast_slice(&collateral_slots));
+        data.extend_from_slice(bytemuck::cast_slice(&loan_slots));
+        if data.len() < min_len {
+            data.resize(min_len, 0);
+        }
+        Ok(data)
+    }
+}
+
+/// Data of `account_data` upgraded to the current layout, `None` if it is already current.
+/// Migrated profiles cover all of the old data and hold at least the default capacity.
+pub fn migrate_account_data(account_data: &[u8]) -> Result<Option<Vec<u8>>, ProgramError> {
+    let discriminator = account_data
+        .get(..8)
+        .ok_or(SundialError::InvalidAccountMigration)?;
+    let migrated_data = if discriminator == Sundial::discriminator() {
+        let mut sundial = Sundial::try_deserialize(&mut &account_data[..])?;
+        if sundial.version == SUNDIAL_VERSION {
+            return Ok(None);
+        }
+        // Version 1 only took the version byte from the padding.
+        sundial.version = SUNDIAL_VERSION;
+        serialize_account(&sundial)?
+    } else if discriminator == SundialCollateral::discriminator() {
+        let mut sundial_collateral = SundialCollateral::try_deserialize(&mut &account_data[..])?;
+        if sundial_collateral.version == SUNDIAL_COLLATERAL_VERSION {
+            return Ok(None);
+        }
+        sundial_collateral.migrate_price_per_token()?;
+        sundial_collateral.version = SUNDIAL_COLLATERAL_VERSION;
+        serialize_account(&sundial_collateral)?
+    } else if discriminator == SundialProfile::discriminator() {
+        let profile = if account_data.len() == LEGACY_SUNDIAL_PROFILE_LEN {
+            let legacy = LegacySundialProfile::deserialize(&mut &account_data[8..])?;
+            UnpackedSundialProfile::try_from(legacy)?
+        } else {
+            match account_data.get(SUNDIAL_PROFILE_VERSION_OFFSET) {
+                Some(&SUNDIAL_PROFILE_VERSION) => return Ok(None),
+                Some(1) => SundialProfileV1::deserialize(&mut &account_data[8..])?.into(),
+                _ => return Err(SundialError::InvalidAccountMigration.into()),
+            }
+        };
+        // Covers all of the old data so no stale bytes are left behind the slots.
+        profile.into_account_data(account_data.len())?
+    } else if discriminator == SundialMarket::discriminator() {
+        // Markets aren't versioned, the first layout is the only one without the status.
+        if account_data.len() != LEGACY_SUNDIAL_MARKET_LEN {
+            return Ok(None);
+        }
+        // Active, with the roles left to the owner, no config change delay and the default
+        // stale tolerances.
+        let owner = Pubkey::deserialize(&mut &account_data[8..])?;
+        serialize_account(&SundialMarket {
+            owner,
+            ..Default::default()
+        })?
+    } else {
+        msg!("Only Sundial Markets, Sundials, Sundial Collaterals and Profiles can be migrated");
+        return Err(SundialError::InvalidAccountMigration.into());
+    };
+    Ok(Some(migrated_data))
+}
+
+fn serialize_account<T: AccountSerialize>(account: &T) -> Result<Vec<u8>, ProgramError> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{MarketStatus, StaleTolerances, SundialMarketRoles, SundialProfileMut};
+
+    /// Account bytes written field by field in the layout of an older program version.
+    struct Fixture(Vec<u8>);
+
+    impl Fixture {
+        fn new<T: Discriminator>() -> Self {
+            Fixture(T::discriminator().to_vec())
+        }
+
+        fn u8(mut self, value: u8) -> Self {
+            self.0.push(value);
+            self
+        }
+
+        fn u64(mut self, value: u64) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn i64(mut self, value: i64) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn pubkey(mut self, key: &Pubkey) -> Self {
+            self.0.extend_from_slice(key.as_ref());
+            self
+        }
+
+        fn decimal(self, value: Decimal) -> Self {
+            let raw = get_raw_from_uint!(value);
+            self.u64(raw[0]).u64(raw[1]).u64(raw[2])
+        }
+
+        fn zeros(mut self, len: usize) -> Self {
+            self.0.resize(self.0.len() + len, 0);
+            self
+        }
+
+        fn pad_to(mut self, len: usize) -> Self {
+            self.0.resize(len, 0);
+            self
+        }
+    }
+
+    fn account_len<T: Default + AnchorSerialize>() -> usize {
+        8 + T::default().try_to_vec().unwrap().len()
+    }
+
+    fn migrate(data: &[u8]) -> Vec<u8> {
+        let migrated = migrate_account_data(data).unwrap().unwrap();
+        assert_eq!(migrate_account_data(&migrated).unwrap(), None);
+        migrated
+    }
+
+    #[test]
+    fn sundial_v0() {
+        let reserve = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let fixture = Fixture::new::<Sundial>()
+            // bumps
+            .u8(1)
+            .u8(2)
+            .u8(3)
+            .u8(4)
+            .u8(5)
+            .u8(6)
+            // duration_in_seconds, end_unix_time_stamp
+            .i64(86_400)
+            .i64(1_700_000_000)
+            // start_exchange_rate
+            .u64(7)
+            .u64(8)
+            // reserve, token_program, port_lending_program, sundial_market, oracle
+            .pubkey(&reserve)
+            .pubkey(&Pubkey::new_unique())
+            .pubkey(&Pubkey::new_unique())
+            .pubkey(&Pubkey::new_unique())
+            .pubkey(&oracle)
+            // config: lending_fee, borrow_fee, liquidity_cap, liquidity_decimals, padding
+            .u8(10)
+            .u8(20)
+            .u64(1_000_000)
+            .u8(6)
+            .zeros(5)
+            // _padding: [u64; 14]
+            .zeros(14 * 8);
+        assert_eq!(fixture.0.len(), account_len::<Sundial>());
+
+        let sundial = Sundial::try_deserialize(&mut fixture.0.as_slice()).unwrap();
+        assert_eq!(sundial.version, 0);
+        assert_eq!(sundial.bumps.fee_receiver_bump, 6);
+        assert_eq!(sundial.end_unix_time_stamp, 1_700_000_000);
+        assert_eq!(sundial.reserve, reserve);
+        assert_eq!(sundial.oracle, oracle);
+        assert_eq!(sundial.config.borrow_fee.bips, 20);
+        assert_eq!(sundial.config.liquidity_cap.lamports, 1_000_000);
+        assert_eq!(sundial.config.liquidity_decimals, 6);
+        assert_eq!(sundial.redeemed_lp_amount, 0);
+        assert_eq!(sundial.oracle_stale_tol, 0);
+        assert_eq!(sundial.fallback_oracle, Pubkey::default());
+
+        let migrated = migrate(&fixture.0);
+        assert_eq!(migrated.len(), fixture.0.len());
+        let migrated_sundial = Sundial::try_deserialize(&mut migrated.as_slice()).unwrap();
+        assert_eq!(
+            migrated_sundial,
+            Sundial {
+                version: SUNDIAL_VERSION,
+                ..sundial
+            }
+        );
+    }
+
+    #[test]
+    fn sundial_collateral_v0() {
+        let reserve = Pubkey::new_unique();
+        let fixture = Fixture::new::<SundialCollateral>()
+            // bumps
+            .u8(1)
+            .u8(2)
+            // config: ltv, liquidation_threshold, liquidation_penalty, liquidity_cap, decimals
+            .u8(70)
+            .u8(80)
+            .u8(5)
+            .u64(1_000_000)
+            .u8(6)
+            // port_collateral_reserve, collateral_mint
+            .pubkey(&reserve)
+            .pubkey(&Pubkey::new_unique())
+            // collateral_price per lamport
+            .decimal(Decimal::from(3u64).try_div(1_000_000u64).unwrap())
+            // last_updated_slot
+            .u64(42)
+            // sundial_market, token_program
+            .pubkey(&Pubkey::new_unique())
+            .pubkey(&Pubkey::new_unique())
+            // _padding: [u64; 32]
+            .zeros(32 * 8);
+        assert_eq!(fixture.0.len(), account_len::<SundialCollateral>());
+
+        let sundial_collateral =
+            SundialCollateral::try_deserialize(&mut fixture.0.as_slice()).unwrap();
+        assert_eq!(sundial_collateral.version, 0);
+        assert!(sundial_collateral.check_version().is_err());
+        assert_eq!(sundial_collateral.port_collateral_reserve, reserve);
+        assert_eq!(sundial_collateral.sundial_collateral_config.ltv.ltv, 70);
+        assert_eq!(
+            sundial_collateral
+                .sundial_collateral_config
+                .liquidation_config
+                .liquidation_penalty,
+            5
+        );
+        assert_eq!(
+            sundial_collateral
+                .sundial_collateral_config
+                .collateral_decimals,
+            6
+        );
+        assert_eq!(
+            sundial_collateral.last_updated_slot,
+            LastUpdatedSlot::from(42)
+        );
+        assert_eq!(sundial_collateral.price_check.oracle, Pubkey::default());
+        assert_eq!(sundial_collateral.ema_price, [0; 3]);
+
+        let migrated = migrate(&fixture.0);
+        assert_eq!(migrated.len(), fixture.0.len());
+        let migrated_collateral =
+            SundialCollateral::try_deserialize(&mut migrated.as_slice()).unwrap();
+        assert!(migrated_collateral.check_version().is_ok());
+        assert_eq!(
+            Decimal(U192(migrated_collateral.collateral_price)),
+            Decimal::from(3u64)
+        );
+        assert_eq!(
+            migrated_collateral.sundial_collateral_config,
+            sundial_collateral.sundial_collateral_config
+        );
+    }
+
+    fn sundial_profile_v0(collaterals: &[(u64, Decimal)], loans: &[(u64, Decimal, u8)]) -> Fixture {
+        let mut fixture = Fixture::new::<SundialProfile>()
+            // user, sundial_market
+            .pubkey(&Pubkey::new_unique())
+            .pubkey(&Pubkey::new_unique())
+            // last_update
+            .u64(42)
+            .u8(collaterals.len() as u8)
+            .zeros(3);
+        for (amount, total_value) in collaterals {
+            fixture = fixture
+                .u64(*amount)
+                .decimal(*total_value)
+                .pubkey(&Pubkey::new_unique())
+                // ltv, liquidation_threshold, liquidation_penalty
+                .u8(70)
+                .u8(80)
+                .u8(5);
+        }
+        fixture = fixture.u8(loans.len() as u8).zeros(3);
+        for (amount, total_value, decimals) in loans {
+            fixture = fixture
+                .u64(*amount)
+                .decimal(*total_value)
+                .pubkey(&Pubkey::new_unique())
+                .pubkey(&Pubkey::new_unique())
+                .i64(1_700_000_000)
+                .u8(*decimals);
+        }
+        // _padding: [u64; 32]
+        fixture.zeros(32 * 8)
+    }
+
+    fn with_profile<R>(data: &mut [u8], f: impl FnOnce(SundialProfileMut) -> R) -> R {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, true, &mut lamports, data, &owner, false, 0);
+        let profile = SundialProfileMut::load(&info).unwrap();
+        f(profile)
+    }
+
+    #[test]
+    fn sundial_profile_v0_default_size() {
+        let fixture = sundial_profile_v0(&[], &[]).pad_to(LEGACY_SUNDIAL_PROFILE_LEN);
+
+        let mut migrated = migrate(&fixture.0);
+        assert_eq!(
+            migrated.len(),
+            SundialProfile::space(
+                DEFAULT_PROFILE_COLLATERAL_CAPACITY,
+                DEFAULT_PROFILE_LOAN_CAPACITY
+            )
+        );
+        assert!(migrated.len() > LEGACY_SUNDIAL_PROFILE_LEN);
+        with_profile(&mut migrated, |profile| {
+            assert_eq!(profile.header.version, SUNDIAL_PROFILE_VERSION);
+            assert!(profile.collaterals.is_empty());
+            assert!(profile.loans.is_empty());
+            assert_eq!(
+                profile.collaterals.capacity(),
+                DEFAULT_PROFILE_COLLATERAL_CAPACITY as usize
+            );
+            assert_eq!(
+                profile.loans.capacity(),
+                DEFAULT_PROFILE_LOAN_CAPACITY as usize
+            );
+        });
+    }
+
+    #[test]
+    fn sundial_profile_v0_keeps_loan_values() {
+        // 2_500 lamports of a 2 decimals token worth 30 in total, i.e. 1.2 per whole token.
+        let fixture = sundial_profile_v0(
+            &[(7, Decimal::from(30u64))],
+            &[(2_500, Decimal::from(30u64), 2)],
+        )
+        .pad_to(LEGACY_SUNDIAL_PROFILE_LEN);
+
+        let mut migrated = migrate(&fixture.0);
+        with_profile(&mut migrated, |profile| {
+            assert_eq!(profile.header.last_update(), LastUpdatedSlot::from(0));
+            let loan = &profile.loans[0];
+            assert_eq!(loan.asset.decimals, 2);
+            assert_eq!(
+                Decimal(U192(loan.asset.price)),
+                Decimal::from(12u64).try_div(10u64).unwrap()
+            );
+            assert_eq!(loan.asset.total_value().unwrap(), Decimal::from(30u64));
+            assert_eq!({ loan.maturity_unix_timestamp }, 1_700_000_000);
+
+            // Valued at zero until the next refresh provides the collateral decimals.
+            let collateral = &profile.collaterals[0];
+            assert_eq!({ collateral.asset.amount }, 7);
+            assert_eq!(collateral.config.ltv.ltv, 70);
+            assert_eq!(collateral.asset.total_value().unwrap(), Decimal::zero());
+        });
+    }
+
+    #[test]
+    fn sundial_profile_v0_loads_after_migration() {
+        // Two collaterals put the legacy count where the current version byte is.
+        let mut fixture = sundial_profile_v0(
+            &[(7, Decimal::from(30u64)), (9, Decimal::from(40u64))],
+            &[(2_500, Decimal::from(30u64), 2)],
+        )
+        .pad_to(LEGACY_SUNDIAL_PROFILE_LEN);
+        assert_eq!(fixture.0.len(), LEGACY_SUNDIAL_PROFILE_LEN);
+        assert_eq!(
+            fixture.0[SUNDIAL_PROFILE_VERSION_OFFSET],
+            SUNDIAL_PROFILE_VERSION
+        );
+
+        // Its zero capacities are still rejected.
+        {
+            let key = Pubkey::new_unique();
+            let owner = crate::ID;
+            let mut lamports = 0;
+            let info = AccountInfo::new(
+                &key,
+                false,
+                true,
+                &mut lamports,
+                &mut fixture.0,
+                &owner,
+                false,
+                0,
+            );
+            assert!(SundialProfileMut::load(&info).is_err());
+        }
+
+        let mut migrated = migrate(&fixture.0);
+        with_profile(&mut migrated, |profile| {
+            assert_eq!(profile.header.version, SUNDIAL_PROFILE_VERSION);
+            assert_eq!(profile.collaterals.len(), 2);
+            assert_eq!({ profile.collaterals[1].asset.amount }, 9);
+            assert_eq!(profile.loans.len(), 1);
+            assert_eq!({ profile.loans[0].asset.amount }, 2_500);
+        });
+    }
+
+    #[test]
+    fn sundial_profile_v1() {
+        let user = Pubkey::new_unique();
+        let sundial_collateral = Pubkey::new_unique();
+        let sundial = Pubkey::new_unique();
+        let collateral_price = Decimal::from(3u64);
+        let loan_price = Decimal::from(12u64).try_div(10u64).unwrap();
+        let mut fixture = Fixture::new::<SundialProfile>()
+            .pubkey(&user)
+            .pubkey(&Pubkey::new_unique())
+            // last_update, version
+            .u64(42)
+            .u8(1)
+            // collaterals: amount, price, decimals, sundial_collateral, config, borrowing_price
+            .u8(1)
+            .zeros(3)
+            .u64(5_000)
+            .decimal(collateral_price)
+            .u8(3)
+            .pubkey(&sundial_collateral)
+            .u8(70)
+            .u8(80)
+            .u8(5)
+            .decimal(collateral_price);
+        // loans: amount, price, decimals, oracle, sundial, maturity, liquidity_decimals
+        fixture = fixture.u8(9).zeros(3);
+        for i in 0..9 {
+            fixture = fixture
+                .u64(1_000 + i)
+                .decimal(loan_price)
+                .u8(2)
+                .pubkey(&Pubkey::new_unique())
+                .pubkey(if i == 0 { &sundial } else { &user })
+                .i64(1_700_000_000)
+                .u8(2);
+        }
+        // _padding: [u64; 29]
+        let fixture = fixture.zeros(29 * 8);
+
+        let mut migrated = migrate(&fixture.0);
+        assert!(migrated.len() >= fixture.0.len());
+        with_profile(&mut migrated, |profile| {
+            assert_eq!(profile.header.user, user);
+            assert_eq!(profile.header.last_update(), LastUpdatedSlot::from(42));
+            assert_eq!(profile.collaterals.len(), 1);
+            assert_eq!(profile.loans.len(), 9);
+            assert_eq!(profile.loans.capacity(), 9);
+            assert_eq!(
+                profile.collaterals[0].sundial_collateral,
+                sundial_collateral
+            );
+            // 5 whole tokens at 3 each, 70% ltv.
+            assert_eq!(
+                profile.get_borrowing_power().unwrap(),
+                Decimal::from(21u64).try_div(2u64).unwrap()
+            );
+            assert_eq!(profile.loans[0].sundial, sundial);
+            assert_eq!(
+                profile.loans[8].asset.total_value().unwrap(),
+                loan_price
+                    .try_mul(1_008u64)
+                    .unwrap()
+                    .try_div(100u64)
+                    .unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn sundial_market_v0() {
+        let owner = Pubkey::new_unique();
+        let fixture = Fixture::new::<SundialMarket>().pubkey(&owner);
+        assert_eq!(fixture.0.len(), LEGACY_SUNDIAL_MARKET_LEN);
+        assert!(SundialMarket::try_deserialize(&mut fixture.0.as_slice()).is_err());
+
+        let migrated = migrate(&fixture.0);
+        assert_eq!(migrated.len(), account_len::<SundialMarket>());
+        let sundial_market = SundialMarket::try_deserialize(&mut migrated.as_slice()).unwrap();
+        assert_eq!(
+            sundial_market,
+            SundialMarket {
+                owner,
+                ..Default::default()
+            }
+        );
+        assert_eq!(sundial_market.status, MarketStatus::Active);
+        assert_eq!(sundial_market.roles, SundialMarketRoles::default());
+        assert_eq!(sundial_market.config_change_delay, 0);
+        assert_eq!(sundial_market.stale_tolerances, StaleTolerances::default());
+    }
+
+    #[test]
+    fn other_accounts_are_not_migrated() {
+        let fixture = Fixture::new::<crate::state::SundialMarketRegistry>().zeros(64);
+        assert!(migrate_account_data(&fixture.0).is_err());
+        assert!(migrate_account_data(&[1, 2, 3]).is_err());
+    }
+}
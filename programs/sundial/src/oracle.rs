@@ -0,0 +1,215 @@
+use crate::error::SundialError;
+use crate::helpers::get_pyth_oracle_price;
+use crate::state::SundialOracleConfig;
+use anchor_lang::prelude::*;
+use port_anchor_adaptor::PortReserve;
+use solana_maths::{Decimal, TryAdd, TryDiv, TryMul, TrySub, U192};
+use vipers::{invariant, unwrap_int};
+
+/// Kind of account a [Sundial](crate::state::Sundial) reads its liquidity price from.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum OracleSource {
+    /// A Pyth price account.
+    Pyth,
+    /// The result account of a Switchboard v1 aggregator.
+    Switchboard,
+    /// The `liquidity.market_price` of a refreshed Port reserve.
+    PortReserve,
+}
+
+impl Default for OracleSource {
+    fn default() -> Self {
+        OracleSource::Pyth
+    }
+}
+
+/// How the primary and fallback prices are combined.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum OraclePolicy {
+    /// Use the primary price, or the fallback one if the primary is unavailable.
+    PrimaryWithFallback,
+    /// Both prices should be available, use their median.
+    Median,
+}
+
+impl Default for OraclePolicy {
+    fn default() -> Self {
+        OraclePolicy::PrimaryWithFallback
+    }
+}
+
+/// Account type byte of a parse optimized Switchboard v1 aggregator result.
+const SWITCHBOARD_RESULT_ACCOUNT_TYPE: u8 = 4;
+/// Precision kept when converting the float Switchboard result.
+const SWITCHBOARD_PRICE_QUOTIENT: u64 = 1_000_000_000;
+
+/// Leading fields of a Switchboard v1 aggregator result, following the account type byte.
+#[derive(AnchorDeserialize)]
+struct SwitchboardRoundResult {
+    _parent: [u8; 32],
+    num_success: i32,
+    _num_error: i32,
+    result: f64,
+    round_open_slot: u64,
+}
+
+/// Price of the primary oracle, checked against and combined with the fallback oracle if there is
+/// one according to the [OraclePolicy].
+pub fn get_oracle_price(
+    oracle: &AccountInfo,
+    fallback_oracle: Option<&AccountInfo>,
+    clock: &Clock,
+    stale_tol: u64,
+    oracle_config: &SundialOracleConfig,
+) -> Result<Decimal, ProgramError> {
+    let primary_price = get_source_price(
+        oracle_config.primary_source,
+        oracle,
+        clock,
+        stale_tol,
+        oracle_config,
+    );
+    let fallback_oracle = match fallback_oracle {
+        Some(fallback_oracle) => fallback_oracle,
+        None => return primary_price,
+    };
+    let fallback_price = get_source_price(
+        oracle_config.fallback_source,
+        fallback_oracle,
+        clock,
+        stale_tol,
+        oracle_config,
+    );
+
+    match oracle_config.policy {
+        OraclePolicy::PrimaryWithFallback => match (primary_price, fallback_price) {
+            (Ok(primary_price), Ok(fallback_price)) => {
+                check_deviation(primary_price, fallback_price, oracle_config)?;
+                Ok(primary_price)
+            }
+            (Ok(primary_price), Err(_)) => Ok(primary_price),
+            (Err(_), Ok(fallback_price)) => {
+                msg!("Primary oracle is unavailable, using the fallback oracle");
+                Ok(fallback_price)
+            }
+            (Err(err), Err(_)) => Err(err),
+        },
+        OraclePolicy::Median => {
+            let primary_price = primary_price?;
+            let fallback_price = fallback_price?;
+            check_deviation(primary_price, fallback_price, oracle_config)?;
+            primary_price.try_add(fallback_price)?.try_div(2)
+        }
+    }
+}
+
+/// Price read from a single oracle of the given [OracleSource].
+pub fn get_source_price(
+    source: OracleSource,
+    oracle: &AccountInfo,
+    clock: &Clock,
+    stale_tol: u64,
+    oracle_config: &SundialOracleConfig,
+) -> Result<Decimal, ProgramError> {
+    match source {
+        OracleSource::Pyth => get_pyth_oracle_price(oracle, clock, stale_tol, oracle_config),
+        OracleSource::Switchboard => get_switchboard_price(oracle, clock, stale_tol),
+        OracleSource::PortReserve => get_port_reserve_price(oracle, clock, stale_tol),
+    }
+}
+
+fn check_deviation(
+    primary_price: Decimal,
+    fallback_price: Decimal,
+    oracle_config: &SundialOracleConfig,
+) -> ProgramResult {
+    if oracle_config.max_deviation_bips == 0 {
+        return Ok(());
+    }
+    invariant!(
+        deviation_bips(fallback_price, primary_price)? <= oracle_config.max_deviation_bips as u64,
+        SundialError::OracleDeviationTooLarge,
+        &format!(
+            "Primary price {:?} and fallback price {:?} deviate too much",
+            primary_price, fallback_price
+        )
+    );
+    Ok(())
+}
+
+/// How far `price` is from `reference_price` in bips of `reference_price`, rounded up.
+pub fn deviation_bips(price: Decimal, reference_price: Decimal) -> Result<u64, ProgramError> {
+    let deviation = if price > reference_price {
+        price.try_sub(reference_price)?
+    } else {
+        reference_price.try_sub(price)?
+    };
+    deviation
+        .try_mul(10000)?
+        .try_div(reference_price)?
+        .try_ceil_u64()
+}
+
+pub fn get_switchboard_price(
+    oracle: &AccountInfo,
+    clock: &Clock,
+    stale_tol: u64,
+) -> Result<Decimal, ProgramError> {
+    let data = oracle.try_borrow_data()?;
+    invariant!(
+        data.first() == Some(&SWITCHBOARD_RESULT_ACCOUNT_TYPE),
+        SundialError::InvalidOracleConfig,
+        "Invalid switchboard account type"
+    );
+    let round_result = SwitchboardRoundResult::deserialize(&mut &data[1..]).map_err(|_| {
+        msg!("Invalid switchboard account data");
+        SundialError::InvalidOracleConfig
+    })?;
+    invariant!(
+        round_result.num_success > 0,
+        SundialError::InvalidOracleConfig,
+        "Switchboard round has no successful response"
+    );
+
+    let slots_elapsed = unwrap_int!(clock.slot.checked_sub(round_result.round_open_slot));
+    invariant!(
+        slots_elapsed <= stale_tol,
+        SundialError::InvalidOracleConfig,
+        &format!(
+            "Switchboard price is stale, current {:?}, last_update {:?}:",
+            clock.slot, round_result.round_open_slot
+        )
+    );
+
+    invariant!(
+        round_result.result.is_finite() && round_result.result > 0.0,
+        SundialError::InvalidOracleConfig,
+        &format!("Invalid switchboard price {:?}", round_result.result)
+    );
+    let price = (round_result.result * SWITCHBOARD_PRICE_QUOTIENT as f64) as u128;
+    Decimal::from(price).try_div(SWITCHBOARD_PRICE_QUOTIENT)
+}
+
+pub fn get_port_reserve_price(
+    oracle: &AccountInfo,
+    clock: &Clock,
+    stale_tol: u64,
+) -> Result<Decimal, ProgramError> {
+    let reserve = Account::<PortReserve>::try_from(oracle)?;
+    invariant!(
+        !reserve.last_update.stale,
+        SundialError::ReserveIsNotRefreshed
+    );
+    let slots_elapsed = unwrap_int!(clock.slot.checked_sub(reserve.last_update.slot));
+    invariant!(
+        slots_elapsed <= stale_tol,
+        SundialError::InvalidOracleConfig,
+        &format!(
+            "Reserve price is stale, current {:?}, last_update {:?}:",
+            clock.slot, reserve.last_update.slot
+        )
+    );
+    Ok(Decimal(U192(get_raw_from_uint!(
+        reserve.liquidity.market_price
+    ))))
+}
@@ -1,9 +1,20 @@
 use crate::error::SundialError;
+use anchor_lang::__private::bytemuck::{self, Pod};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::Sysvar as _;
+use anchor_lang::Discriminator;
 use anchor_spl::token::{mint_to, transfer, Mint, MintTo, TokenAccount, Transfer};
 
-use crate::helpers::{get_pyth_oracle_price, price_per_lamport, SUNDIAL_COLLATERAL_STALE_TOL};
-use solana_maths::{Decimal, Rate, TryAdd, TryDiv, TryMul, TrySub, U192};
+use crate::helpers::{
+    decimal_pow, exp2_wad, token_amount, token_value, COLLATERAL_EMA_HALF_LIFE, DEFAULT_STALE_TOL,
+    MAX_STALE_TOL, WAD,
+};
+use crate::oracle::{get_oracle_price, OraclePolicy, OracleSource};
+use port_variable_rate_lending_instructions::state::CollateralExchangeRate;
+use solana_maths::{Decimal, Rate, TryAdd, TryDiv, TryMul, TrySub, U128, U192};
+use std::cell::RefMut;
+use std::mem::size_of;
+use std::ops::{Deref, DerefMut};
 use vipers::{invariant, unwrap_int};
 
 #[account]
@@ -11,8 +22,215 @@ use vipers::{invariant, unwrap_int};
 pub struct SundialMarket {
     /// The owner for the set of [Sundial]s and [SundialCollateral]s.
     pub owner: Pubkey,
+    /// Status applied on top of every [Sundial] and [SundialCollateral] in the market.
+    pub status: MarketStatus,
+    /// Owner proposed by the current one, becomes the owner once it accepts.
+    pub pending_owner: Pubkey,
+    /// Keys allowed to act on behalf of the owner for a subset of instructions.
+    pub roles: SundialMarketRoles,
+    /// Seconds a queued [PendingConfigChange] waits before it can be executed.
+    pub config_change_delay: i64,
+    /// Default staleness tolerances, [Sundial]s and [SundialCollateral]s can override them.
+    pub stale_tolerances: StaleTolerances,
+    pub _padding: [u64; 4],
+}
+
+/// Slots before a price or a refreshed [SundialProfile] becomes stale, zero means [DEFAULT_STALE_TOL].
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct StaleTolerances {
+    /// For the Port LP price of [SundialCollateral]s.
+    pub collateral_slots: u64,
+    /// For [SundialProfile]s after being refreshed.
+    pub profile_slots: u64,
+    /// For the oracles of [Sundial]s.
+    pub oracle_slots: u64,
+}
+
+impl StaleTolerances {
+    pub fn sanity_check(&self) -> ProgramResult {
+        check_stale_tol(self.collateral_slots)?;
+        check_stale_tol(self.profile_slots)?;
+        check_stale_tol(self.oracle_slots)
+    }
+
+    /// Tolerance for a [SundialCollateral] price given its own override.
+    pub fn collateral(&self, collateral_stale_tol: u64) -> u64 {
+        or_default_stale_tol(collateral_stale_tol, self.collateral_slots)
+    }
+
+    pub fn profile(&self) -> u64 {
+        or_default_stale_tol(0, self.profile_slots)
+    }
+
+    /// Tolerance for a [Sundial] oracle price given its own override.
+    pub fn oracle(&self, oracle_stale_tol: u64) -> u64 {
+        or_default_stale_tol(oracle_stale_tol, self.oracle_slots)
+    }
+}
+
+/// Zero leaves the tolerance unset, otherwise it should be within [MAX_STALE_TOL].
+pub fn check_stale_tol(stale_tol: u64) -> ProgramResult {
+    invariant!(
+        stale_tol <= MAX_STALE_TOL,
+        SundialError::InvalidStaleTolerance,
+        &format!(
+            "Stale tolerance {:?} is over {:?}",
+            stale_tol, MAX_STALE_TOL
+        )
+    );
+    Ok(())
+}
+
+fn or_default_stale_tol(override_tol: u64, market_tol: u64) -> u64 {
+    if override_tol != 0 {
+        override_tol
+    } else if market_tol != 0 {
+        market_tol
+    } else {
+        DEFAULT_STALE_TOL
+    }
+}
+
+/// Unset roles are [Pubkey::default], leaving the instructions to the owner only.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct SundialMarketRoles {
+    /// Changes [SundialCollateral] configs, e.g., LTVs and liquidity caps.
+    pub risk_admin: Pubkey,
+    /// Changes [Sundial] configs, e.g., fees and liquidity caps.
+    pub fee_admin: Pubkey,
+    /// Changes the [MarketStatus] of the market, its [Sundial]s and [SundialCollateral]s.
+    pub pause_guardian: Pubkey,
+    /// Lists new [Sundial]s, [SundialAmm]s and [SundialCollateral]s.
+    pub listing_admin: Pubkey,
+}
+
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct SundialMarketRegistry {
+    /// The [SundialMarket] whose [Sundial]s and [SundialCollateral]s are listed.
+    pub sundial_market: Pubkey,
+    /// Number of [SundialMarketRegistryEntry]s created, they are PDAs of
+    /// `[registry, "entry", index]`. Entries of closed [Sundial]s are gone, leaving gaps.
+    pub entry_count: u64,
+    pub _padding: [u64; 8],
+}
+
+impl SundialMarketRegistry {
+    /// Index of the entry being registered, bumps `entry_count`.
+    pub fn next_index(&mut self) -> Result<u64, ProgramError> {
+        let index = self.entry_count;
+        self.entry_count = unwrap_int!(self.entry_count.checked_add(1));
+        Ok(index)
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Copy)]
+pub enum RegistryEntryKind {
+    Sundial,
+    SundialCollateral,
+}
+
+impl Default for RegistryEntryKind {
+    fn default() -> Self {
+        RegistryEntryKind::Sundial
+    }
+}
+
+/// A [SundialConfig] or [SundialCollateralConfig] change waiting out the `config_change_delay` of
+/// the [SundialMarket]. Only these are queued as they change the terms of open positions. Oracle
+/// configs, price checks and staleness tolerances apply at once so the risk admin can react to a
+/// failing oracle, their sanity checks bound them instead.
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct PendingConfigChange {
+    /// The [SundialMarket] of the target.
+    pub sundial_market: Pubkey,
+    /// The [Sundial] or [SundialCollateral] whose config is changed.
+    pub target: Pubkey,
+    /// Receives the rent back once the change is executed or cancelled.
+    pub payer: Pubkey,
+    pub kind: RegistryEntryKind,
+    /// New config if the target is a [Sundial].
+    pub sundial_config: SundialConfig,
+    /// New config if the target is a [SundialCollateral].
+    pub sundial_collateral_config: SundialCollateralConfig,
+    pub queued_unix_timestamp: i64,
+    /// The change can't be executed before this timestamp.
+    pub execute_after_unix_timestamp: i64,
+    /// Seconds to ramp a [SundialCollateral] from its current LTV and liquidation threshold to the new ones.
+    pub ramp_duration_in_seconds: i64,
+    pub _padding: [u64; 7],
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Copy)]
+pub enum MarketStatus {
+    Active,
+    ReduceOnly,
+    Paused,
+}
+
+impl Default for MarketStatus {
+    fn default() -> Self {
+        MarketStatus::Active
+    }
+}
+
+impl MarketStatus {
+    /// Active only, for instructions opening or increasing positions.
+    pub fn check_active(&self, name: &str) -> ProgramResult {
+        match self {
+            MarketStatus::Active => Ok(()),
+            MarketStatus::ReduceOnly => {
+                msg!("{} is reduce only", name);
+                Err(SundialError::ReduceOnly.into())
+            }
+            MarketStatus::Paused => {
+                msg!("{} is paused", name);
+                Err(SundialError::Paused.into())
+            }
+        }
+    }
+
+    /// Active or reduce only, for instructions reducing or closing positions.
+    pub fn check_not_paused(&self, name: &str) -> ProgramResult {
+        match self {
+            MarketStatus::Paused => {
+                msg!("{} is paused", name);
+                Err(SundialError::Paused.into())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct SundialMarketRegistryEntry {
+    /// The [SundialMarketRegistry] it belongs to.
+    pub registry: Pubkey,
+    /// Position in the registry.
+    pub index: u64,
+    /// Whether `account` is a [Sundial] or a [SundialCollateral].
+    pub kind: RegistryEntryKind,
+    /// The listed [Sundial] or [SundialCollateral].
+    pub account: Pubkey,
+    /// The Port reserve behind it.
+    pub reserve: Pubkey,
+    /// When the [Sundial] ends, 0 for a [SundialCollateral].
+    pub maturity_unix_timestamp: i64,
+    pub status: MarketStatus,
+    pub _padding: [u64; 4],
+}
+
+/// Current layout version of [Sundial], accounts of older versions are upgraded by
+/// [sundial::migrate_account](crate::sundial::migrate_account).
+pub const SUNDIAL_VERSION: u8 = 1;
+/// Current layout version of [SundialCollateral], version 1 stores prices per whole token.
+pub const SUNDIAL_COLLATERAL_VERSION: u8 = 1;
+/// Current layout version of [SundialProfile], version 1 stores asset prices per whole token and
+/// version 2 is the zero-copy layout with fixed-capacity slots.
+pub const SUNDIAL_PROFILE_VERSION: u8 = 2;
+
 #[account]
 #[derive(Debug, PartialEq, Default)]
 pub struct Sundial {
@@ -37,8 +255,259 @@ pub struct Sundial {
     pub oracle: Pubkey,
     /// Configuration for the given [Sundial].
     pub config: SundialConfig,
+    /// Port LP redeemed after the [Sundial] ends, it can be redeemed in several parts.
+    pub redeemed_lp_amount: u64,
+    /// Liquidity received for the redeemed Port LP.
+    pub redeemed_liquidity_amount: u64,
+    /// Principal tokens redeemed after the [Sundial] ends.
+    pub principle_redeemed_amount: u64,
+    /// Liquidity paid to yield token holders after the [Sundial] ends.
+    pub yield_liquidity_redeemed_amount: u64,
+    /// Yield tokens deposited into [YieldPosition]s.
+    pub yield_position_token_amount: u64,
+    /// Liquidity claimed by the [YieldPosition]s that still hold yield tokens.
+    pub yield_position_claimed_amount: u64,
+    /// Whether the [Sundial] is active, reduce only or paused.
+    pub status: MarketStatus,
+    /// Layout version, see [SUNDIAL_VERSION].
+    pub version: u8,
+    /// Whether the [Sundial] has a [SundialMarketRegistryEntry].
+    pub registered: bool,
+    pub _status_padding: [u8; 5],
+    /// Overrides the oracle staleness tolerance of the [SundialMarket] if not zero.
+    pub oracle_stale_tol: u64,
+    /// Checks applied to the oracle price when valuing loans.
+    pub oracle_config: SundialOracleConfig,
+    /// Oracle read as the fallback source if not the default key.
+    pub fallback_oracle: Pubkey,
+    /// Space in case we need to add more data.
+    pub _padding: [u64; 1],
+}
+
+impl Sundial {
+    /// The fallback oracle account if the [Sundial] has one, checked against its key.
+    pub fn fallback_oracle<'a, 'info>(
+        &self,
+        fallback_oracle: Option<&'a AccountInfo<'info>>,
+    ) -> Result<Option<&'a AccountInfo<'info>>, ProgramError> {
+        if self.fallback_oracle == Pubkey::default() {
+            return Ok(None);
+        }
+        let fallback_oracle = fallback_oracle.ok_or(SundialError::OracleNeeded)?;
+        vipers::assert_keys_eq!(
+            fallback_oracle.key,
+            self.fallback_oracle,
+            "Invalid fallback oracle"
+        );
+        Ok(Some(fallback_oracle))
+    }
+
+    /// The share of Port LP redeemed so far, `lp_left` is the amount still in the lp wallet.
+    pub fn redeemed_lp_fraction(&self, lp_left: u64) -> Result<Decimal, ProgramError> {
+        if lp_left == 0 {
+            return Ok(Decimal::one());
+        }
+        let total_lp = unwrap_int!(self.redeemed_lp_amount.checked_add(lp_left));
+        Decimal::from(self.redeemed_lp_amount).try_div(total_lp)
+    }
+
+    /// All the liquidity left for principal and yield token holders, valuing the Port LP not
+    /// redeemed yet at the average rate it has been redeemed at so far.
+    pub fn get_liquidity_left(
+        &self,
+        liquidity_wallet_amount: u64,
+        lp_left: u64,
+    ) -> Result<Decimal, ProgramError> {
+        if lp_left == 0 {
+            return Ok(Decimal::from(liquidity_wallet_amount));
+        }
+        invariant!(self.redeemed_lp_amount != 0, SundialError::NotRedeemLpYet);
+        Decimal::from(lp_left)
+            .try_mul(self.redeemed_liquidity_amount)?
+            .try_div(self.redeemed_lp_amount)?
+            .try_add(Decimal::from(liquidity_wallet_amount))
+    }
+
+    /// Principal tokens are redeemed one to one, but until all the Port LP is redeemed only
+    /// the redeemed share of all the principal tokens can be redeemed.
+    pub fn check_principle_redeemable(
+        &self,
+        amount: u64,
+        principle_supply: u64,
+        lp_left: u64,
+    ) -> ProgramResult {
+        let total_principle =
+            unwrap_int!(principle_supply.checked_add(self.principle_redeemed_amount));
+        let redeemable = self
+            .redeemed_lp_fraction(lp_left)?
+            .try_mul(total_principle)?
+            .try_floor_u64()?;
+        let redeemed = unwrap_int!(self.principle_redeemed_amount.checked_add(amount));
+        invariant!(
+            redeemed <= redeemable,
+            SundialError::ExceedRedeemedLiquidity,
+            &format!(
+                "Principal redeemed {:?}, redeemable so far {:?}",
+                redeemed, redeemable
+            )
+        );
+        Ok(())
+    }
+
+    /// The liquidity every yield token gets at the end. Yield tokens share what is left after the
+    /// outstanding principal tokens, together with the liquidity [YieldPosition]s already claimed.
+    pub fn get_liquidity_per_yield_token(
+        &self,
+        yield_supply: u64,
+        principle_supply: u64,
+        liquidity_wallet_amount: u64,
+        lp_left: u64,
+    ) -> Result<Decimal, ProgramError> {
+        let total_yield_tokens =
+            unwrap_int!(yield_supply.checked_add(self.yield_position_token_amount));
+        self.get_liquidity_left(liquidity_wallet_amount, lp_left)?
+            .try_sub(Decimal::from(principle_supply))?
+            .try_add(Decimal::from(self.yield_position_claimed_amount))?
+            .try_div(total_yield_tokens)
+    }
+
+    /// Liquidity paid to yield token holders is capped by the redeemed share the same way as
+    /// [Sundial::check_principle_redeemable].
+    pub fn check_yield_redeemable(
+        &self,
+        redeem_amount: u64,
+        principle_supply: u64,
+        liquidity_wallet_amount: u64,
+        lp_left: u64,
+    ) -> ProgramResult {
+        let liquidity_of_yield = self
+            .get_liquidity_left(liquidity_wallet_amount, lp_left)?
+            .try_sub(Decimal::from(principle_supply))?;
+        let redeemable = self
+            .redeemed_lp_fraction(lp_left)?
+            .try_mul(
+                liquidity_of_yield.try_add(Decimal::from(self.yield_liquidity_redeemed_amount))?,
+            )?
+            .try_floor_u64()?;
+        let redeemed = unwrap_int!(self
+            .yield_liquidity_redeemed_amount
+            .checked_add(redeem_amount));
+        invariant!(
+            redeemed <= redeemable,
+            SundialError::ExceedRedeemedLiquidity,
+            &format!(
+                "Yield liquidity redeemed {:?}, redeemable so far {:?}",
+                redeemed, redeemable
+            )
+        );
+        Ok(())
+    }
+
+    /// The liquidity paid for `amount` of yield tokens after the [Sundial] ends.
+    pub fn get_yield_redeem_amount(
+        &self,
+        amount: u64,
+        yield_supply: u64,
+        principle_supply: u64,
+        liquidity_wallet_amount: u64,
+        lp_left: u64,
+    ) -> Result<u64, ProgramError> {
+        let redeem_amount = self
+            .get_liquidity_per_yield_token(
+                yield_supply,
+                principle_supply,
+                liquidity_wallet_amount,
+                lp_left,
+            )?
+            .try_mul(amount)?
+            .try_floor_u64()?;
+        self.check_yield_redeemable(
+            redeem_amount,
+            principle_supply,
+            liquidity_wallet_amount,
+            lp_left,
+        )?;
+        Ok(redeem_amount)
+    }
+
+    /// Interest accrued by one yield token before the [Sundial] ends: what the Port LP minted for one
+    /// liquidity at the start exchange rate is worth at `exchange_rate`, minus the principal.
+    pub fn get_accrued_interest_per_yield_token(
+        &self,
+        exchange_rate: CollateralExchangeRate,
+    ) -> Result<Decimal, ProgramError> {
+        let start_exchange_rate = CollateralExchangeRate(Rate(U128(self.start_exchange_rate)));
+        let value = exchange_rate.decimal_collateral_to_liquidity(
+            start_exchange_rate.decimal_liquidity_to_collateral(Decimal::one())?,
+        )?;
+        if value > Decimal::one() {
+            value.try_sub(Decimal::one())
+        } else {
+            Ok(Decimal::zero())
+        }
+    }
+
+    /// Port LP given back for recombining `amount` of principal and yield tokens, the inverse of
+    /// what `deposit_and_mint_tokens` does. Rounded down so the Port LP left in the [Sundial] still
+    /// covers the outstanding principal tokens.
+    pub fn get_recombine_lp_amount(&self, amount: u64) -> Result<u64, ProgramError> {
+        CollateralExchangeRate(Rate(U128(self.start_exchange_rate)))
+            .decimal_liquidity_to_collateral(Decimal::from(amount))?
+            .try_floor_u64()
+    }
+}
+
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct YieldPosition {
+    /// The owner of the position.
+    pub owner: Pubkey,
+    /// The [Sundial] whose yield tokens are deposited.
+    pub sundial: Pubkey,
+    /// Yield tokens deposited, they are burned on deposit.
+    pub yield_token_amount: u64,
+    /// Liquidity claimed by the position so far.
+    pub claimed_liquidity_amount: u64,
+    /// The unix time stamp of the last claim.
+    pub last_claim_unix_time_stamp: i64,
     /// Space in case we need to add more data.
-    pub _padding: [u64; 14],
+    pub _padding: [u64; 8],
+}
+
+impl YieldPosition {
+    /// Interest accrued since the last claim. Newly deposited yield tokens carry all the interest
+    /// accrued since the [Sundial] started, as they have not been claimed yet.
+    pub fn get_claimable_amount(
+        &self,
+        liquidity_per_yield_token: Decimal,
+    ) -> Result<u64, ProgramError> {
+        Ok(liquidity_per_yield_token
+            .try_mul(self.yield_token_amount)?
+            .try_floor_u64()?
+            .saturating_sub(self.claimed_liquidity_amount))
+    }
+
+    /// Take `amount` yield tokens out of the position. Returns their share of the liquidity claimed
+    /// so far, rounded up: the yield tokens minted back carry all the interest since the [Sundial]
+    /// started, so it has to be paid back.
+    pub fn withdraw(&mut self, amount: u64) -> Result<u64, ProgramError> {
+        invariant!(
+            amount != 0 && amount <= self.yield_token_amount,
+            SundialError::InvalidYieldPositionAmount,
+            &format!(
+                "Withdraw {:?} yield tokens, the position holds {:?}",
+                amount, self.yield_token_amount
+            )
+        );
+        let claimed = Decimal::from(self.claimed_liquidity_amount)
+            .try_mul(amount)?
+            .try_div(self.yield_token_amount)?
+            .try_ceil_u64()?;
+        self.yield_token_amount = unwrap_int!(self.yield_token_amount.checked_sub(amount));
+        self.claimed_liquidity_amount =
+            unwrap_int!(self.claimed_liquidity_amount.checked_sub(claimed));
+        Ok(claimed)
+    }
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
@@ -55,6 +524,39 @@ pub struct SundialConfig {
     pub _config_padding: [u8; 5],
 }
 
+/// How the oracle prices of a [Sundial] are read and checked before valuing loans with them.
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct SundialOracleConfig {
+    /// Widest Pyth confidence interval accepted in bips of the price, zero disables the check.
+    pub max_confidence_bips: u16,
+    /// Value loans at Pyth price plus confidence, which is conservative for debt.
+    pub use_upper_bound_price: bool,
+    /// Source of the [Sundial] oracle.
+    pub primary_source: OracleSource,
+    /// Source of the [Sundial] fallback oracle.
+    pub fallback_source: OracleSource,
+    /// How the primary and fallback prices are combined.
+    pub policy: OraclePolicy,
+    /// Largest deviation between the primary and fallback prices in bips, zero disables the check.
+    pub max_deviation_bips: u16,
+}
+
+impl SundialOracleConfig {
+    pub fn sanity_check(&self, fallback_oracle: &Pubkey) -> ProgramResult {
+        invariant!(
+            self.max_confidence_bips <= 10000 && self.max_deviation_bips <= 10000,
+            SundialError::InvalidOracleConfig,
+            "Max confidence and deviation should be at most 10000 bips"
+        );
+        invariant!(
+            self.policy != OraclePolicy::Median || *fallback_oracle != Pubkey::default(),
+            SundialError::InvalidOracleConfig,
+            "Median policy needs a fallback oracle"
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default, Copy)]
 pub struct LiquidityCap {
     pub lamports: u64,
@@ -128,6 +630,157 @@ pub struct SundialBumps {
     pub fee_receiver_bump: u8,
 }
 
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct SundialAmm {
+    /// Bump seeds for the PDAs owned by the pool.
+    pub bumps: SundialAmmBumps,
+    /// The [Sundial] whose principal tokens are traded against its liquidity.
+    pub sundial: Pubkey,
+    /// The sundial market that the [Sundial] belongs to.
+    pub sundial_market: Pubkey,
+    /// Configuration for the given [SundialAmm].
+    pub config: SundialAmmConfig,
+    /// The annual rate implied by the pool reserves after the last update.
+    pub implied_apy: [u64; 3], // Decimal
+    /// Space in case we need to add more data.
+    pub _padding: [u64; 16],
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+pub struct SundialAmmBumps {
+    pub authority_bump: u8,
+    pub lp_mint_bump: u8,
+    pub liquidity_bump: u8,
+    pub principle_bump: u8,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+pub struct SundialAmmConfig {
+    /// Swap fee bips charged on the input token, it stays in the pool for the liquidity providers.
+    pub swap_fee: Fee,
+    /// The time to maturity on the curve is measured in units of this many seconds,
+    /// it needs to be longer than the time left in the [Sundial].
+    pub time_scale_in_seconds: u64,
+}
+
+impl SundialAmmConfig {
+    pub fn sanity_check(&self, seconds_to_end: i64) -> ProgramResult {
+        invariant!(
+            seconds_to_end >= 0 && self.time_scale_in_seconds > seconds_to_end as u64,
+            SundialError::InvalidSundialAmmConfig,
+            &format!(
+                "Time scale {:?} should be longer than the time to end {:?}",
+                self.time_scale_in_seconds, seconds_to_end
+            )
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy)]
+pub enum SwapDirection {
+    PrincipleToLiquidity,
+    LiquidityToPrinciple,
+}
+
+impl SundialAmm {
+    /// `1 - t` where `t` is the time to the end of the [Sundial] in units of `time_scale_in_seconds`.
+    pub fn curve_exponent(
+        &self,
+        sundial: &Sundial,
+        clock: &Clock,
+    ) -> Result<Decimal, ProgramError> {
+        let time_to_end = unwrap_int!(sundial
+            .end_unix_time_stamp
+            .checked_sub(clock.unix_timestamp))
+        .max(0) as u64;
+        let exponent = Decimal::one()
+            .try_sub(Decimal::from(time_to_end).try_div(self.config.time_scale_in_seconds)?)?;
+        invariant!(
+            exponent > Decimal::zero(),
+            SundialError::InvalidSundialAmmConfig
+        );
+        Ok(exponent)
+    }
+
+    /// Get how many tokens are swapped out for `amount_in` following the yield space invariant
+    /// `x^(1-t) + y^(1-t) = k`, where `x` is the principal token reserve and `y` the liquidity reserve.
+    /// The marginal price of one principal token is `(y/x)^t`, which converges to one at the end.
+    pub fn get_swap_amount_out(
+        &self,
+        sundial: &Sundial,
+        clock: &Clock,
+        principle_reserve: u64,
+        liquidity_reserve: u64,
+        amount_in: u64,
+        direction: SwapDirection,
+    ) -> Result<u64, ProgramError> {
+        let (reserve_in, reserve_out) = match direction {
+            SwapDirection::PrincipleToLiquidity => (principle_reserve, liquidity_reserve),
+            SwapDirection::LiquidityToPrinciple => (liquidity_reserve, principle_reserve),
+        };
+        let fee = self.config.swap_fee.calculate_fee(amount_in)?;
+        let amount_in_after_fee = unwrap_int!(amount_in.checked_sub(fee));
+
+        let exponent = self.curve_exponent(sundial, clock)?;
+        let curve_constant = decimal_pow(Decimal::from(reserve_in), exponent)?
+            .try_add(decimal_pow(Decimal::from(reserve_out), exponent)?)?;
+        let new_reserve_in = unwrap_int!(reserve_in.checked_add(amount_in_after_fee));
+        let new_reserve_in_term = decimal_pow(Decimal::from(new_reserve_in), exponent)?;
+        invariant!(
+            curve_constant > new_reserve_in_term,
+            SundialError::InsufficientAmmLiquidity
+        );
+        // Round the reserve left up so the pool never pays out more than the curve allows.
+        let new_reserve_out = decimal_pow(
+            curve_constant.try_sub(new_reserve_in_term)?,
+            Decimal::one().try_div(exponent)?,
+        )?
+        .try_ceil_u64()?;
+        invariant!(
+            new_reserve_out < reserve_out,
+            SundialError::InsufficientAmmLiquidity
+        );
+        let amount_out = reserve_out - new_reserve_out;
+
+        if direction == SwapDirection::LiquidityToPrinciple {
+            // Principal tokens can't be sold for more than their face value.
+            invariant!(
+                principle_reserve - amount_out
+                    >= unwrap_int!(liquidity_reserve.checked_add(amount_in)),
+                SundialError::InsufficientAmmLiquidity,
+                "Swap would push the implied rate below zero"
+            );
+        }
+        Ok(amount_out)
+    }
+
+    /// The implied apy is `(x/y)^(SECONDS_PER_YEAR / time_scale_in_seconds) - 1`, it does not
+    /// change with time as the curve flattens. Saturates if it is too large to represent.
+    pub fn update_implied_apy(
+        &mut self,
+        principle_reserve: u64,
+        liquidity_reserve: u64,
+    ) -> ProgramResult {
+        if principle_reserve <= liquidity_reserve {
+            self.implied_apy = [0; 3];
+            return Ok(());
+        }
+        let annualize_exponent =
+            Decimal::from(SECONDS_PER_YEAR).try_div(self.config.time_scale_in_seconds)?;
+        let growth = decimal_pow(
+            Decimal::from(principle_reserve).try_div(Decimal::from(liquidity_reserve))?,
+            annualize_exponent,
+        )
+        .unwrap_or(Decimal(U192::MAX));
+        self.implied_apy = get_raw_from_uint!(growth.try_sub(Decimal::one())?);
+        Ok(())
+    }
+}
+
 #[account]
 #[derive(Debug, PartialEq, Default)]
 pub struct SundialCollateral {
@@ -139,7 +792,7 @@ pub struct SundialCollateral {
     pub port_collateral_reserve: Pubkey,
     //Mint of Port LP Collateral
     pub collateral_mint: Pubkey,
-    /// The current price of the Port LP tokens in USD.
+    /// The current price of a whole Port LP token in USD.
     pub collateral_price: [u64; 3], // Decimal
     /// The last updated slot.
     pub last_updated_slot: LastUpdatedSlot,
@@ -147,7 +800,162 @@ pub struct SundialCollateral {
     pub sundial_market: Pubkey,
     /// Token program ID.
     pub token_program: Pubkey,
-    pub _padding: [u64; 32],
+    /// Whether the [SundialCollateral] is active, reduce only or paused.
+    pub status: MarketStatus,
+    /// Layout version, see [SUNDIAL_COLLATERAL_VERSION].
+    pub version: u8,
+    /// Whether the [SundialCollateral] has a [SundialMarketRegistryEntry].
+    pub registered: bool,
+    pub _status_padding: [u8; 5],
+    /// Ramp from the previous LTV and liquidation threshold to the ones in `sundial_collateral_config`.
+    pub config_ramp: SundialCollateralConfigRamp,
+    /// Overrides the collateral staleness tolerance of the [SundialMarket] if not zero.
+    pub collateral_stale_tol: u64,
+    /// Independent oracle the Port reserve price is checked against when refreshing.
+    pub price_check: SundialCollateralPriceCheck,
+    /// Exponential moving average of `collateral_price`, zero until the first refresh.
+    pub ema_price: [u64; 3], // Decimal
+    /// The last time `ema_price` got updated.
+    pub ema_updated_unix_timestamp: i64,
+    pub _padding: [u64; 16],
+}
+
+impl SundialCollateral {
+    /// Rescale prices stored per lamport by version 0 to prices per whole token.
+    pub fn migrate_price_per_token(&mut self) -> ProgramResult {
+        let multiplier = unwrap_int!(
+            10u64.checked_pow(self.sundial_collateral_config.collateral_decimals as u32)
+        );
+        self.collateral_price =
+            get_raw_from_uint!(Decimal(U192(self.collateral_price)).try_mul(multiplier)?);
+        self.ema_price = get_raw_from_uint!(Decimal(U192(self.ema_price)).try_mul(multiplier)?);
+        Ok(())
+    }
+
+    pub fn check_version(&self) -> ProgramResult {
+        invariant!(
+            self.version == SUNDIAL_COLLATERAL_VERSION,
+            SundialError::AccountNotMigrated,
+            &format!("Sundial Collateral version {:?}", self.version)
+        );
+        Ok(())
+    }
+
+    /// Move `ema_price` towards `collateral_price` by `1 - 2^(-elapsed / half life)` of the gap,
+    /// see [COLLATERAL_EMA_HALF_LIFE]. Unlike a weight linear in the elapsed time, splitting the
+    /// same time across more refreshes doesn't move the ema any faster.
+    pub fn update_ema_price(&mut self, unix_timestamp: i64) -> ProgramResult {
+        let spot_price = Decimal(U192(self.collateral_price));
+        let ema_price = Decimal(U192(self.ema_price));
+        let new_ema_price = if ema_price == Decimal::zero() {
+            spot_price
+        } else {
+            let elapsed =
+                unwrap_int!(unix_timestamp.checked_sub(self.ema_updated_unix_timestamp)).max(0);
+            let exponent = unwrap_int!((elapsed as i128)
+                .checked_mul(WAD as i128)
+                .map(|e| e / COLLATERAL_EMA_HALF_LIFE as i128));
+            let weight = Decimal::one().try_sub(exp2_wad(-exponent)?)?;
+            if spot_price > ema_price {
+                ema_price.try_add(spot_price.try_sub(ema_price)?.try_mul(weight)?)?
+            } else {
+                ema_price.try_sub(ema_price.try_sub(spot_price)?.try_mul(weight)?)?
+            }
+        };
+        self.ema_price = get_raw_from_uint!(new_ema_price);
+        self.ema_updated_unix_timestamp = unix_timestamp;
+        Ok(())
+    }
+
+    /// Price used for borrowing power, the lower of the spot and ema prices.
+    pub fn borrowing_price(&self) -> Decimal {
+        let spot_price = Decimal(U192(self.collateral_price));
+        let ema_price = Decimal(U192(self.ema_price));
+        if ema_price == Decimal::zero() || spot_price < ema_price {
+            spot_price
+        } else {
+            ema_price
+        }
+    }
+
+    /// LTV and liquidation threshold interpolated along `config_ramp` at `unix_timestamp`.
+    pub fn effective_config(
+        &self,
+        unix_timestamp: i64,
+    ) -> Result<SundialProfileCollateralConfig, ProgramError> {
+        let target: SundialProfileCollateralConfig = self.sundial_collateral_config.clone().into();
+        let ramp = &self.config_ramp;
+        Ok(SundialProfileCollateralConfig {
+            ltv: LTV {
+                ltv: ramp.interpolate(ramp.start_ltv, target.ltv.ltv, unix_timestamp)?,
+            },
+            liquidation_config: LiquidationConfig {
+                liquidation_threshold: ramp.interpolate(
+                    ramp.start_liquidation_threshold,
+                    target.liquidation_config.liquidation_threshold,
+                    unix_timestamp,
+                )?,
+                ..target.liquidation_config
+            },
+        })
+    }
+
+    /// Make `config` the new target, ramping to it from the current effective values.
+    pub fn start_config_ramp(
+        &mut self,
+        config: SundialCollateralConfig,
+        unix_timestamp: i64,
+        ramp_duration_in_seconds: i64,
+    ) -> ProgramResult {
+        let current = self.effective_config(unix_timestamp)?;
+        self.config_ramp = SundialCollateralConfigRamp {
+            start_ltv: current.ltv.ltv,
+            start_liquidation_threshold: current.liquidation_config.liquidation_threshold,
+            _ramp_padding: [0; 6],
+            start_unix_timestamp: unix_timestamp,
+            end_unix_timestamp: unwrap_int!(unix_timestamp.checked_add(ramp_duration_in_seconds)),
+        };
+        self.sundial_collateral_config = config;
+        Ok(())
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+pub struct SundialCollateralConfigRamp {
+    /// LTV when the ramp starts.
+    pub start_ltv: u8,
+    /// Liquidation threshold when the ramp starts.
+    pub start_liquidation_threshold: u8,
+    pub _ramp_padding: [u8; 6],
+    pub start_unix_timestamp: i64,
+    /// The target values in [SundialCollateralConfig] fully apply from this timestamp.
+    pub end_unix_timestamp: i64,
+}
+
+impl SundialCollateralConfigRamp {
+    /// Linear from `start` to `target`, rounded towards `start`.
+    pub fn interpolate(
+        &self,
+        start: u8,
+        target: u8,
+        unix_timestamp: i64,
+    ) -> Result<u8, ProgramError> {
+        if unix_timestamp >= self.end_unix_timestamp {
+            return Ok(target);
+        }
+        if unix_timestamp <= self.start_unix_timestamp {
+            return Ok(start);
+        }
+        let elapsed = unwrap_int!(unix_timestamp.checked_sub(self.start_unix_timestamp));
+        let duration = unwrap_int!(self
+            .end_unix_timestamp
+            .checked_sub(self.start_unix_timestamp));
+        let delta = unwrap_int!((target as i64)
+            .checked_sub(start as i64)
+            .and_then(|d| d.checked_mul(elapsed))
+            .and_then(|d| d.checked_div(duration)));
+        Ok(unwrap_int!((start as i64).checked_add(delta)) as u8)
+    }
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
@@ -174,6 +982,35 @@ impl LastUpdatedSlot {
     }
 }
 
+/// Check of the Port reserve price of the underlying liquidity against an independent oracle.
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+pub struct SundialCollateralPriceCheck {
+    /// Oracle of the underlying liquidity, the check is off if it is the default key.
+    pub oracle: Pubkey,
+    /// Source of `oracle`.
+    pub source: OracleSource,
+    pub _price_check_padding: [u8; 5],
+    /// Largest deviation accepted in bips of the reserve price.
+    pub max_deviation_bips: u16,
+    /// Deviation found by the last check in bips of the reserve price, a refresh leaves the
+    /// collateral price untouched if it is over `max_deviation_bips`.
+    pub last_deviation_bips: u64,
+    /// Slot of the last check.
+    pub last_checked_slot: u64,
+}
+
+impl SundialCollateralPriceCheck {
+    pub fn sanity_check(&self) -> ProgramResult {
+        invariant!(
+            self.oracle == Pubkey::default()
+                || (self.max_deviation_bips != 0 && self.max_deviation_bips <= 10000),
+            SundialError::InvalidOracleConfig,
+            "Max deviation should be within 1 and 10000 bips"
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
 pub struct SundialCollateralBumps {
     pub authority_bump: u8,
@@ -208,7 +1045,8 @@ impl SundialCollateralConfig {
     }
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Default)]
 pub struct LTV {
     pub ltv: u8,
 }
@@ -219,7 +1057,8 @@ impl LTV {
     }
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Default)]
 pub struct LiquidationConfig {
     pub liquidation_threshold: u8,
     pub liquidation_penalty: u8,
@@ -244,30 +1083,272 @@ impl LiquidationConfig {
     }
 }
 
-#[account]
-#[derive(Debug, PartialEq)]
+/// Number of [SundialProfileCollateral] slots of a newly created [SundialProfile].
+pub const DEFAULT_PROFILE_COLLATERAL_CAPACITY: u8 = 4;
+/// Number of [SundialProfileLoan] slots of a newly created [SundialProfile].
+pub const DEFAULT_PROFILE_LOAN_CAPACITY: u8 = 8;
+
+/// Header of a zero-copy Sundial Profile account. It is followed by `collateral_capacity`
+/// [SundialProfileCollateral] slots and then `loan_capacity` [SundialProfileLoan] slots, which
+/// are accessed through [SundialProfileMut].
+#[account(zero_copy)]
+#[derive(Debug, PartialEq, Default)]
 pub struct SundialProfile {
     /// The owner of the [SundialProfile].
     pub user: Pubkey,
     pub sundial_market: Pubkey,
     /// The last slot the price of the asset got updated.
-    pub last_update: LastUpdatedSlot,
-    /// A list of [SundialProfileCollateral].
-    pub collaterals: Vec<SundialProfileCollateral>,
-    /// A list of [SundialProfileLoan].
-    pub loans: Vec<SundialProfileLoan>,
-    pub _padding: [u64; 32],
+    pub last_update_slot: u64,
+    /// Layout version, see [SUNDIAL_PROFILE_VERSION].
+    pub version: u8,
+    /// Number of [SundialProfileCollateral] slots.
+    pub collateral_capacity: u8,
+    /// Number of [SundialProfileLoan] slots.
+    pub loan_capacity: u8,
+    /// Index of the profile among the ones of `user` in `sundial_market`, part of its address.
+    pub sub_account_id: u16,
+    /// Number of open [ProfileDelegate]s, the profile can't be closed or transferred before
+    /// they are.
+    pub delegate_count: u8,
+    pub _capacity_padding: [u8; 2],
+    pub _padding: [u64; 16],
 }
 
 impl SundialProfile {
+    /// Account size of a [SundialProfile] with the given number of slots.
+    pub fn space(collateral_capacity: u8, loan_capacity: u8) -> usize {
+        8 + size_of::<SundialProfile>()
+            + collateral_capacity as usize * size_of::<SundialProfileCollateral>()
+            + loan_capacity as usize * size_of::<SundialProfileLoan>()
+    }
+
+    #[inline(always)]
+    pub fn last_update(&self) -> LastUpdatedSlot {
+        self.last_update_slot.into()
+    }
+
+    /// Profiles of older versions need [sundial::migrate_account](crate::sundial::migrate_account)
+    /// first. The collateral count of the unversioned legacy layout can read as the current
+    /// version, its upper bytes then read as zero capacities which no migrated or new profile has.
+    pub fn check_version(&self, data_len: usize) -> ProgramResult {
+        invariant!(
+            self.version == SUNDIAL_PROFILE_VERSION
+                && self.collateral_capacity >= DEFAULT_PROFILE_COLLATERAL_CAPACITY
+                && self.loan_capacity >= DEFAULT_PROFILE_LOAN_CAPACITY
+                && data_len >= Self::space(self.collateral_capacity, self.loan_capacity),
+            SundialError::AccountNotMigrated,
+            &format!("Sundial Profile version {:?}", self.version)
+        );
+        Ok(())
+    }
+
+    /// Open [ProfileDelegate]s would apply again to a profile created at the same address.
+    pub fn check_no_delegates(&self) -> ProgramResult {
+        invariant!(
+            self.delegate_count == 0,
+            SundialError::ProfileHasDelegates,
+            &format!("Profile has {:?} delegates", self.delegate_count)
+        );
+        Ok(())
+    }
+
+    /// Checks `signer` is the user of the profile at `profile`, or a [ProfileDelegate] of it
+    /// allowed by `permitted` whose record is passed as `delegate`. Returns whether it is a
+    /// delegate, which can only move tokens out to wallets of the user.
+    pub fn check_signer(
+        &self,
+        profile: Pubkey,
+        signer: Pubkey,
+        delegate: Option<&AccountInfo>,
+        permitted: fn(&ProfileDelegatePermissions) -> bool,
+    ) -> Result<bool, ProgramError> {
+        if signer == self.user {
+            return Ok(false);
+        }
+        let delegate_info = delegate.ok_or(SundialError::InvalidProfileUser)?;
+        invariant!(
+            *delegate_info.owner == crate::ID,
+            SundialError::InvalidProfileDelegate
+        );
+        let delegate: ProfileDelegate = anchor_lang::AccountDeserialize::try_deserialize(
+            &mut &delegate_info.try_borrow_data()?[..],
+        )?;
+        vipers::assert_keys_eq!(
+            delegate.sundial_profile,
+            profile,
+            SundialError::InvalidProfileDelegate,
+            "Delegate of another profile"
+        );
+        vipers::assert_keys_eq!(
+            delegate.user,
+            self.user,
+            SundialError::InvalidProfileDelegate,
+            "Delegate granted by another user"
+        );
+        vipers::assert_keys_eq!(
+            delegate.delegate,
+            signer,
+            SundialError::InvalidProfileDelegate,
+            "Delegate of another key"
+        );
+        invariant!(
+            permitted(&delegate.permissions),
+            SundialError::ProfileDelegateNotPermitted
+        );
+        invariant!(
+            Clock::get()?.unix_timestamp < delegate.expiry_unix_timestamp,
+            SundialError::ProfileDelegateExpired
+        );
+        Ok(true)
+    }
+}
+
+/// Lets `delegate` act on a [SundialProfile] within `permissions` until `expiry_unix_timestamp`,
+/// it is a PDA of `[sundial_profile, "delegate", delegate]`. Counted in the `delegate_count` of the
+/// profile, so it can't outlive the profile and apply again to one created at the same address.
+#[account]
+#[derive(Debug, PartialEq, Default)]
+pub struct ProfileDelegate {
+    pub sundial_profile: Pubkey,
+    /// The user of the profile that granted it.
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: ProfileDelegatePermissions,
+    pub expiry_unix_timestamp: i64,
+    pub _padding: [u64; 4],
+}
+
+/// Profile instructions a [ProfileDelegate] can sign instead of the user.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct ProfileDelegatePermissions {
+    /// Deposit collaterals.
+    pub deposit: bool,
+    /// Repay loans with liquidity or principal tokens.
+    pub repay: bool,
+    /// Withdraw collaterals into wallets of the user.
+    pub withdraw: bool,
+    /// Mint principal tokens against the collaterals into wallets of the user.
+    pub mint: bool,
+}
+
+/// A slot of a [SundialProfile], slots holding the default key are unused.
+pub trait ProfileSlot: Pod + Default {
+    fn key(&self) -> Pubkey;
+}
+
+impl ProfileSlot for SundialProfileCollateral {
+    fn key(&self) -> Pubkey {
+        self.sundial_collateral
+    }
+}
+
+impl ProfileSlot for SundialProfileLoan {
+    fn key(&self) -> Pubkey {
+        self.sundial
+    }
+}
+
+/// Fixed-capacity slots of a [SundialProfile]. The used slots come first and dereference to a
+/// slice, the remaining ones are zeroed.
+pub struct ProfileSlots<'a, T>(RefMut<'a, [T]>);
+
+impl<'a, T: ProfileSlot> ProfileSlots<'a, T> {
+    fn used(&self) -> usize {
+        self.0
+            .iter()
+            .position(|slot| slot.key() == Pubkey::default())
+            .unwrap_or(self.0.len())
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn push(&mut self, slot: T) -> ProgramResult {
+        let used = self.used();
+        invariant!(
+            used < self.capacity(),
+            SundialError::ProfileCapacityExceeded,
+            &format!(
+                "All {:?} slots are used, resize the Sundial Profile first",
+                used
+            )
+        );
+        self.0[used] = slot;
+        Ok(())
+    }
+
+    /// Free the slot at `pos`, shifting the later slots to keep the used ones in front.
+    pub fn remove(&mut self, pos: usize) {
+        let used = self.used();
+        self.0[pos..used].rotate_left(1);
+        self.0[used - 1] = T::default();
+    }
+}
+
+impl<'a, T: ProfileSlot> Deref for ProfileSlots<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0[..self.used()]
+    }
+}
+
+impl<'a, T: ProfileSlot> DerefMut for ProfileSlots<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        let used = self.used();
+        &mut self.0[..used]
+    }
+}
+
+/// A mutably borrowed [SundialProfile] account with its collateral and loan slots.
+pub struct SundialProfileMut<'a> {
+    pub header: RefMut<'a, SundialProfile>,
+    pub collaterals: ProfileSlots<'a, SundialProfileCollateral>,
+    pub loans: ProfileSlots<'a, SundialProfileLoan>,
+}
+
+impl<'a> SundialProfileMut<'a> {
+    /// Borrow the data of a [SundialProfile] account at the current version.
+    pub fn load(profile_info: &'a AccountInfo) -> Result<Self, ProgramError> {
+        let data_len = profile_info.data_len();
+        let data = profile_info.try_borrow_mut_data()?;
+        if data_len < 8 + size_of::<SundialProfile>()
+            || data[..8] != SundialProfile::discriminator()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (header, slots) = RefMut::map_split(data, |data| {
+            data[8..].split_at_mut(size_of::<SundialProfile>())
+        });
+        let header: RefMut<SundialProfile> = RefMut::map(header, bytemuck::from_bytes_mut);
+        header.check_version(data_len)?;
+
+        let collaterals_len =
+            header.collateral_capacity as usize * size_of::<SundialProfileCollateral>();
+        let loans_len = header.loan_capacity as usize * size_of::<SundialProfileLoan>();
+        let (collaterals, loans) = RefMut::map_split(slots, |slots| {
+            let (collaterals, loans) = slots.split_at_mut(collaterals_len);
+            (collaterals, &mut loans[..loans_len])
+        });
+
+        Ok(SundialProfileMut {
+            header,
+            collaterals: ProfileSlots(RefMut::map(collaterals, bytemuck::cast_slice_mut)),
+            loans: ProfileSlots(RefMut::map(loans, bytemuck::cast_slice_mut)),
+        })
+    }
+
     #[inline(always)]
     pub fn get_borrowing_power(&self) -> Result<Decimal, ProgramError> {
         self.collaterals
             .iter()
             .try_fold(Decimal::zero(), |acc_bp, c| {
-                c.config
-                    .ltv
-                    .get_bp(Decimal(U192(c.asset.total_value)))
+                c.asset
+                    .get_value_at_price(Decimal(U192(c.borrowing_price)), c.asset.amount)
+                    .and_then(|value| c.config.ltv.get_bp(value))
                     .and_then(|bp| acc_bp.try_add(bp))
             })
     }
@@ -275,7 +1356,7 @@ impl SundialProfile {
     #[inline(always)]
     pub fn get_borrowed_value(&self) -> Result<Decimal, ProgramError> {
         self.loans.iter().try_fold(Decimal::zero(), |acc_bv, l| {
-            acc_bv.try_add(Decimal(U192(l.asset.total_value)))
+            acc_bv.try_add(l.asset.total_value()?)
         })
     }
 
@@ -286,7 +1367,7 @@ impl SundialProfile {
             .try_fold(Decimal::zero(), |acc_lm, c| {
                 c.config
                     .liquidation_config
-                    .get_liquidation_margin(Decimal(U192(c.asset.total_value)))
+                    .get_liquidation_margin(c.asset.total_value()?)
                     .and_then(|lm| acc_lm.try_add(lm))
             })
     }
@@ -318,28 +1399,6 @@ impl SundialProfile {
         let borrowed_value = log_then_prop_err!(self.get_borrowed_value());
         calculate_risk_factor(borrowed_value, liquidation_margin)
     }
-
-    #[inline(always)]
-    pub fn get_mut_collaterals_and_loans(
-        &mut self,
-    ) -> (
-        &mut Vec<SundialProfileCollateral>,
-        &mut Vec<SundialProfileLoan>,
-    ) {
-        (&mut self.collaterals, &mut self.loans)
-    }
-}
-impl Default for SundialProfile {
-    fn default() -> Self {
-        SundialProfile {
-            user: Default::default(),
-            last_update: 0.into(),
-            collaterals: vec![SundialProfileCollateral::default(); 1],
-            loans: vec![SundialProfileLoan::default(); 9],
-            sundial_market: Default::default(),
-            _padding: [0; 32],
-        }
-    }
 }
 
 pub fn calculate_risk_factor(
@@ -355,47 +1414,73 @@ pub fn calculate_risk_factor(
     }
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(Debug, PartialEq, Default)]
 pub struct AssetInfo {
     /// asset amount in lamports
     pub amount: u64,
-    /// asset dollar value
-    pub total_value: [u64; 3],
+    /// asset dollar price per whole token
+    pub price: [u64; 3], // Decimal
+    /// decimals of the asset token
+    pub decimals: u8,
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Default)]
 pub struct SundialProfileCollateralConfig {
     pub ltv: LTV,
     pub liquidation_config: LiquidationConfig,
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(Debug, PartialEq, Default)]
 pub struct SundialProfileCollateral {
     pub asset: AssetInfo,
     pub sundial_collateral: Pubkey,
     pub config: SundialProfileCollateralConfig,
+    /// Price per whole token used for borrowing power, see [SundialCollateral::borrowing_price].
+    pub borrowing_price: [u64; 3], // Decimal
 }
 
 impl AssetInfo {
+    pub fn new(amount: u64, price: Decimal, decimals: u8) -> Self {
+        AssetInfo {
+            amount,
+            price: get_raw_from_uint!(price),
+            decimals,
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_price(&mut self, price: Decimal) {
+        self.price = get_raw_from_uint!(price);
+    }
+
+    /// Dollar value of the whole asset, recomputed from the price so it never drifts.
+    #[inline(always)]
+    pub fn total_value(&self) -> Result<Decimal, ProgramError> {
+        self.get_value(self.amount)
+    }
+
     #[inline(always)]
     pub fn update_amount(&mut self, new_amount: u64) -> ProgramResult {
-        self.total_value = get_raw_from_uint!(Decimal(U192(self.total_value))
-            .try_mul(new_amount)?
-            .try_div(self.amount)?);
         self.amount = new_amount;
         Ok(())
     }
 
     #[inline(always)]
     pub fn get_value(&self, amount: u64) -> Result<Decimal, ProgramError> {
-        let market_value = Decimal(U192(self.total_value));
-        market_value.try_div(self.amount)?.try_mul(amount)
+        self.get_value_at_price(Decimal(U192(self.price)), amount)
+    }
+
+    #[inline(always)]
+    pub fn get_value_at_price(&self, price: Decimal, amount: u64) -> Result<Decimal, ProgramError> {
+        token_value(price, amount, self.decimals)
     }
 
     #[inline(always)]
     pub fn get_amount(&self, value: Decimal) -> Result<Decimal, ProgramError> {
-        let market_value = Decimal(U192(self.total_value));
-        value.try_div(market_value.try_div(self.amount)?)
+        token_amount(Decimal(U192(self.price)), value, self.decimals)
     }
 
     #[inline(always)]
@@ -413,8 +1498,7 @@ impl AssetInfo {
 
     #[inline(always)]
     pub fn reduce_value(&mut self, decr_value: Decimal) -> ProgramResult {
-        let market_value = Decimal(U192(self.total_value));
-        let new_value = market_value.try_sub(decr_value)?;
+        let new_value = self.total_value()?.try_sub(decr_value)?;
         let new_amount = self.get_amount(new_value)?;
         self.update_amount(new_amount.try_floor_u64()?)
     }
@@ -425,6 +1509,7 @@ impl SundialProfileCollateral {
         &mut self,
         sundial_collateral_info: &AccountInfo,
         clock: &Clock,
+        stale_tolerances: &StaleTolerances,
     ) -> ProgramResult {
         vipers::assert_keys_eq!(
             sundial_collateral_info.key,
@@ -439,15 +1524,18 @@ impl SundialProfileCollateral {
 
         sundial_collateral.last_updated_slot.check_stale(
             clock,
-            SUNDIAL_COLLATERAL_STALE_TOL,
+            stale_tolerances.collateral(sundial_collateral.collateral_stale_tol),
             "Sundial Collateral Is Stale",
         )?;
+        sundial_collateral.check_version()?;
 
-        self.asset.total_value = get_raw_from_uint!(log_then_prop_err!(Decimal(U192(
-            sundial_collateral.collateral_price
-        ))
-        .try_mul(self.asset.amount)));
-        self.config = sundial_collateral.sundial_collateral_config.into();
+        self.asset
+            .set_price(Decimal(U192(sundial_collateral.collateral_price)));
+        self.asset.decimals = sundial_collateral
+            .sundial_collateral_config
+            .collateral_decimals;
+        self.borrowing_price = get_raw_from_uint!(sundial_collateral.borrowing_price());
+        self.config = sundial_collateral.effective_config(clock.unix_timestamp)?;
 
         Ok(())
     }
@@ -455,17 +1543,21 @@ impl SundialProfileCollateral {
     pub fn init_collateral(
         amount: u64,
         sundial_collateral: &Account<SundialCollateral>,
+        clock: &Clock,
     ) -> Result<Self, ProgramError> {
-        let collateral_price = Decimal(U192(sundial_collateral.collateral_price));
-        let total_value = get_raw_from_uint!(log_then_prop_err!(collateral_price.try_mul(amount)));
+        sundial_collateral.check_version()?;
 
         Ok(SundialProfileCollateral {
-            asset: AssetInfo {
+            asset: AssetInfo::new(
                 amount,
-                total_value,
-            },
+                Decimal(U192(sundial_collateral.collateral_price)),
+                sundial_collateral
+                    .sundial_collateral_config
+                    .collateral_decimals,
+            ),
             sundial_collateral: sundial_collateral.key(),
-            config: sundial_collateral.sundial_collateral_config.clone().into(),
+            config: sundial_collateral.effective_config(clock.unix_timestamp)?,
+            borrowing_price: get_raw_from_uint!(sundial_collateral.borrowing_price()),
         })
     }
 }
@@ -478,7 +1570,8 @@ impl From<SundialCollateralConfig> for SundialProfileCollateralConfig {
         }
     }
 }
-#[derive(AnchorDeserialize, AnchorSerialize, Debug, PartialEq, Clone, Default)]
+#[zero_copy]
+#[derive(Debug, PartialEq, Default)]
 pub struct SundialProfileLoan {
     pub asset: AssetInfo,
     pub oracle: Pubkey,
@@ -488,18 +1581,28 @@ pub struct SundialProfileLoan {
 }
 
 impl SundialProfileLoan {
-    pub fn refresh_price(&mut self, oracle: &AccountInfo, clock: &Clock) -> ProgramResult {
+    pub fn refresh_price(
+        &mut self,
+        oracle: &AccountInfo,
+        fallback_oracle: Option<&AccountInfo>,
+        clock: &Clock,
+        stale_tol: u64,
+        oracle_config: &SundialOracleConfig,
+    ) -> ProgramResult {
         vipers::assert_keys_eq!(
             oracle.key,
             self.oracle,
             "Invalid oracle given for refreshing"
         );
-        let market_price = log_then_prop_err!(get_pyth_oracle_price(oracle, clock));
-        let market_price_per_lamport =
-            log_then_prop_err!(price_per_lamport(market_price, self.liquidity_decimals));
-        self.asset.total_value = get_raw_from_uint!(log_then_prop_err!(
-            market_price_per_lamport.try_mul(self.asset.amount)
+        let market_price = log_then_prop_err!(get_oracle_price(
+            oracle,
+            fallback_oracle,
+            clock,
+            stale_tol,
+            oracle_config
         ));
+        self.asset.set_price(market_price);
+        self.asset.decimals = self.liquidity_decimals;
 
         Ok(())
     }
@@ -513,20 +1616,24 @@ impl SundialProfileLoan {
     pub fn init_loan(
         amount: u64,
         oracle: &AccountInfo,
+        fallback_oracle: Option<&AccountInfo>,
         sundial: Pubkey,
         clock: &Clock,
         end_timestamp: i64,
         liquidity_decimals: u8,
+        stale_tol: u64,
+        oracle_config: &SundialOracleConfig,
     ) -> Result<Self, ProgramError> {
-        let market_price = log_then_prop_err!(get_pyth_oracle_price(oracle, clock));
-        let market_price_per_lamport =
-            log_then_prop_err!(price_per_lamport(market_price, liquidity_decimals));
+        let market_price = log_then_prop_err!(get_oracle_price(
+            oracle,
+            fallback_oracle,
+            clock,
+            stale_tol,
+            oracle_config
+        ));
 
         Ok(SundialProfileLoan {
-            asset: AssetInfo {
-                amount,
-                total_value: get_raw_from_uint!(market_price_per_lamport.try_mul(amount)?),
-            },
+            asset: AssetInfo::new(amount, market_price, liquidity_decimals),
             oracle: oracle.key(),
             sundial,
             maturity_unix_timestamp: end_timestamp,
@@ -539,3 +1646,165 @@ impl SundialProfileLoan {
         self.maturity_unix_timestamp <= current_ts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_value(price: Decimal, amount: u64, decimals: u8) -> Decimal {
+        price
+            .try_mul(amount)
+            .unwrap()
+            .try_div(10u64.pow(decimals as u32))
+            .unwrap()
+    }
+
+    #[test]
+    fn value_does_not_drift_across_partial_repays() {
+        // An awkward price so every division would round.
+        let price = Decimal::from(1_234_567u64).try_div(1_000_003u64).unwrap();
+        let mut asset = AssetInfo::new(1_000_000_000_000, price, 9);
+        for i in 0..5000u64 {
+            asset.reduce_amount(1 + i * 37 % 199_999).unwrap();
+            assert_eq!(
+                asset.total_value().unwrap(),
+                expected_value(price, asset.amount, 9)
+            );
+        }
+        asset.add_amount(123_456_789).unwrap();
+        assert_eq!(
+            asset.total_value().unwrap(),
+            expected_value(price, asset.amount, 9)
+        );
+        assert_eq!(Decimal(U192(asset.price)), price);
+    }
+
+    #[test]
+    fn value_does_not_drift_across_partial_withdrawals() {
+        let price = Decimal::from(7u64).try_div(3u64).unwrap();
+        let mut asset = AssetInfo::new(50_000_000_000, price, 6);
+        let step = asset.get_value(9_999).unwrap();
+        for _ in 0..3000 {
+            let before = asset.amount;
+            asset.reduce_value(step).unwrap();
+            // Rounding down the amount only ever costs the withdrawer one lamport.
+            assert!(before - asset.amount >= 9_999 && before - asset.amount <= 10_000);
+            assert_eq!(
+                asset.total_value().unwrap(),
+                expected_value(price, asset.amount, 6)
+            );
+        }
+    }
+
+    #[test]
+    fn ema_price_decays_by_half_lives() {
+        let collateral = |ema_price: u64| SundialCollateral {
+            collateral_price: get_raw_from_uint!(Decimal::from(100u64)),
+            ema_price: get_raw_from_uint!(Decimal::from(ema_price)),
+            ..Default::default()
+        };
+        let ema = |c: &SundialCollateral| Decimal(U192(c.ema_price));
+
+        let mut once = collateral(20);
+        once.update_ema_price(COLLATERAL_EMA_HALF_LIFE).unwrap();
+        assert_eq!(ema(&once), Decimal::from(60u64));
+
+        let mut falling = SundialCollateral {
+            collateral_price: get_raw_from_uint!(Decimal::from(20u64)),
+            ..collateral(100)
+        };
+        falling
+            .update_ema_price(2 * COLLATERAL_EMA_HALF_LIFE)
+            .unwrap();
+        assert_eq!(ema(&falling), Decimal::from(40u64));
+
+        // Refreshing every second gets nowhere closer than a single refresh after the same time.
+        let mut often = collateral(20);
+        for t in 1..=COLLATERAL_EMA_HALF_LIFE {
+            often.update_ema_price(t).unwrap();
+        }
+        let (high, low) = if ema(&once) > ema(&often) {
+            (ema(&once), ema(&often))
+        } else {
+            (ema(&often), ema(&once))
+        };
+        let tolerance = Decimal::one().try_div(1_000_000_000u64).unwrap();
+        assert!(high.try_sub(low).unwrap() < tolerance);
+    }
+
+    #[test]
+    fn yield_position_withdraw_pays_back_its_claimed_share() {
+        let mut position = YieldPosition {
+            yield_token_amount: 3_000,
+            claimed_liquidity_amount: 100,
+            ..Default::default()
+        };
+        // 1000 / 3000 of the 100 claimed, rounded up.
+        assert_eq!(position.withdraw(1_000).unwrap(), 34);
+        assert_eq!(position.yield_token_amount, 2_000);
+        assert_eq!(position.claimed_liquidity_amount, 66);
+        assert_eq!(position.withdraw(2_000).unwrap(), 66);
+        assert_eq!(position.claimed_liquidity_amount, 0);
+        assert_eq!(
+            position.withdraw(1),
+            Err(SundialError::InvalidYieldPositionAmount.into())
+        );
+    }
+
+    #[test]
+    fn recombine_rounds_lp_down() {
+        let sundial = Sundial {
+            start_exchange_rate: Rate::from_percent(60).0 .0,
+            ..Default::default()
+        };
+        // 3 * 0.6 = 1.8 lp, rounding to nearest would give out more than was deposited.
+        assert_eq!(sundial.get_recombine_lp_amount(3).unwrap(), 1);
+        assert_eq!(sundial.get_recombine_lp_amount(5).unwrap(), 3);
+        assert_eq!(sundial.get_recombine_lp_amount(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn profile_slots_stay_dense() {
+        let mut data = vec![0u8; SundialProfile::space(2, 1)];
+        data[..8].copy_from_slice(&SundialProfile::discriminator());
+        let header = SundialProfile {
+            version: SUNDIAL_PROFILE_VERSION,
+            collateral_capacity: 2,
+            loan_capacity: 1,
+            ..Default::default()
+        };
+        data[8..8 + size_of::<SundialProfile>()].copy_from_slice(bytemuck::bytes_of(&header));
+        let (key, owner, mut lamports) = (Pubkey::new_unique(), crate::ID, 0);
+        let info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let mut profile = SundialProfileMut::load(&info).unwrap();
+
+        let collateral = |amount| SundialProfileCollateral {
+            asset: AssetInfo::new(amount, Decimal::one(), 0),
+            sundial_collateral: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        profile.collaterals.push(collateral(1)).unwrap();
+        profile.collaterals.push(collateral(2)).unwrap();
+        assert_eq!(
+            profile.collaterals.push(collateral(3)),
+            Err(SundialError::ProfileCapacityExceeded.into())
+        );
+        assert!(profile.loans.is_empty());
+
+        profile.collaterals.remove(0);
+        assert_eq!(profile.collaterals.len(), 1);
+        assert_eq!({ profile.collaterals[0].asset.amount }, 2);
+        profile.collaterals.push(collateral(3)).unwrap();
+        assert_eq!({ profile.collaterals[1].asset.amount }, 3);
+        assert_eq!(profile.get_liquidation_margin().unwrap(), Decimal::zero());
+    }
+}
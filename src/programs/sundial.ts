@@ -7,6 +7,11 @@ export type SundialTypes = AnchorTypes<
     sundial: SundialData;
     sundialCollateral: SundialCollateralData;
     sundialProfile: SundialProfileData;
+    sundialAmm: SundialAmmData;
+    yieldPosition: YieldPositionData;
+    sundialMarketRegistry: SundialMarketRegistryData;
+    sundialMarketRegistryEntry: SundialMarketRegistryEntryData;
+    profileDelegate: ProfileDelegateData;
   },
   {
     AssetInfo: Defined['AssetInfo'];
@@ -17,21 +22,41 @@ export type SundialTypes = AnchorTypes<
     LTV: Defined['LTV'];
     SundialProfileCollateralConfig: Defined['SundialProfileCollateralConfig'];
     LiquidationConfig: Defined['LiquidationConfig'];
+    SundialMarketRoles: Defined['SundialMarketRoles'];
+    StaleTolerances: Defined['StaleTolerances'];
+    SundialOracleConfigParams: Defined['SundialOracleConfigParams'];
+    SundialCollateralPriceCheckParams: Defined['SundialCollateralPriceCheckParams'];
+    ProfileDelegatePermissions: Defined['ProfileDelegatePermissions'];
   }
 >;
 
 type Accounts = SundialTypes['Accounts'];
 type Defined = SundialTypes['Defined'];
+export type AssetInfo = Defined['AssetInfo'];
 export type SundialProfileLoan = Defined['SundialProfileLoan'];
 export type SundialProfileCollateral = Defined['SundialProfileCollateral'];
 export type SundialCollateralConfig = Defined['SundialCollateralConfig'];
+export type SundialMarketRoles = Defined['SundialMarketRoles'];
+export type StaleTolerances = Defined['StaleTolerances'];
+export type SundialOracleConfigParams =
+  Defined['SundialOracleConfigParams'];
+export type SundialCollateralPriceCheckParams =
+  Defined['SundialCollateralPriceCheckParams'];
+export type ProfileDelegatePermissions = Defined['ProfileDelegatePermissions'];
 export type SundialData = Accounts['Sundial'];
 export type SundialCollateralData = Accounts['SundialCollateral'];
 export type SundialProfileData = Accounts['SundialProfile'];
+export type SundialAmmData = Accounts['SundialAmm'];
+export type YieldPositionData = Accounts['YieldPosition'];
+export type SundialMarketRegistryData = Accounts['SundialMarketRegistry'];
+export type SundialMarketRegistryEntryData =
+  Accounts['SundialMarketRegistryEntry'];
+export type ProfileDelegateData = Accounts['ProfileDelegate'];
 
 export type SundialProgram = SundialTypes['Program'];
 
 export type SundialAccountData =
   | { type: 'sundial'; data?: SundialData }
   | { type: 'sundialCollateral'; data?: SundialCollateralData }
-  | { type: 'sundialProfile'; data?: SundialProfileData };
+  | { type: 'sundialProfile'; data?: SundialProfileData }
+  | { type: 'sundialAmm'; data?: SundialAmmData };
@@ -12,6 +12,8 @@ import {
   PublicKey,
   Signer,
   SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
 } from '@solana/web3.js';
 import mapValues from 'lodash.mapvalues';
 import {
@@ -23,8 +25,15 @@ import {
 } from '@project-serum/anchor';
 
 import { expectTX } from '@saberhq/chai-solana';
+import BN from 'bn.js';
+import {
+  StaleTolerances,
+  SundialMarketRegistryEntryData,
+  SundialMarketRoles,
+} from './programs';
 import { SundialProfileWrapper, SundialWrapper } from './wrappers';
-import { SundialCollateralWrapper } from './wrappers';
+import { SundialAmmWrapper, SundialCollateralWrapper } from './wrappers';
+import type { MarketStatus } from './wrappers';
 
 const AUTHORITY = 'authority';
 const FEE_RECEIVER_KEY = 'fee_receiver';
@@ -32,6 +41,9 @@ const PRINCIPLE_MINT_KEY = 'principle_mint';
 const YIELD_MINT_KEY = 'yield_mint';
 const LIQUIDITY_KEY = 'liquidity';
 const LP_KEY = 'lp';
+const REGISTRY_KEY = 'registry';
+const REGISTRY_ENTRY_KEY = 'entry';
+const PENDING_CONFIG_KEY = 'pending_config';
 
 export class SundialSDK {
   constructor(
@@ -72,6 +84,10 @@ export class SundialSDK {
     return new SundialProfileWrapper(this);
   }
 
+  get sundialAmmWrapper() {
+    return new SundialAmmWrapper(this);
+  }
+
   /**
    * Loads the SDK.
    * @returns SundialSDK
@@ -163,6 +179,254 @@ export class SundialSDK {
     );
   }
 
+  public async getSundialMarketRegistryAndBump(
+    sundialMarket: PublicKey,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [sundialMarket.toBuffer(), utils.bytes.utf8.encode(REGISTRY_KEY)],
+      this.programs.Sundial.programId,
+    );
+  }
+
+  public async getRegistryEntryAndBump(
+    registry: PublicKey,
+    index: BN,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [
+        registry.toBuffer(),
+        utils.bytes.utf8.encode(REGISTRY_ENTRY_KEY),
+        index.toArrayLike(Buffer, 'le', 8),
+      ],
+      this.programs.Sundial.programId,
+    );
+  }
+
+  /**
+   * Accounts the next [Sundial] or [SundialCollateral] of the market gets registered with.
+   */
+  public async getNextRegistryEntryAccounts(sundialMarket: PublicKey) {
+    const [sundialMarketRegistry] =
+      await this.getSundialMarketRegistryAndBump(sundialMarket);
+    const registry =
+      await this.programs.Sundial.account.sundialMarketRegistry.fetch(
+        sundialMarketRegistry,
+      );
+    const [registryEntry, registryEntryBump] =
+      await this.getRegistryEntryAndBump(
+        sundialMarketRegistry,
+        registry.entryCount,
+      );
+    return { sundialMarketRegistry, registryEntry, registryEntryBump };
+  }
+
+  /**
+   * Fetch every Sundial and SundialCollateral listed in the market.
+   */
+  public async fetchSundialMarketRegistry(sundialMarket: PublicKey) {
+    const [sundialMarketRegistry] =
+      await this.getSundialMarketRegistryAndBump(sundialMarket);
+    const registry =
+      await this.programs.Sundial.account.sundialMarketRegistry.fetch(
+        sundialMarketRegistry,
+      );
+    const entryKeys = await Promise.all(
+      [...Array(registry.entryCount.toNumber()).keys()].map(
+        async i =>
+          (
+            await this.getRegistryEntryAndBump(sundialMarketRegistry, new BN(i))
+          )[0],
+      ),
+    );
+    const accounts = this.programs.Sundial.account;
+    const entries = await accounts.sundialMarketRegistryEntry.fetchMultiple(
+      entryKeys,
+    );
+    return entries.filter((e): e is SundialMarketRegistryEntryData => !!e);
+  }
+
+  public async getRegistryEntryKey(
+    sundialMarket: PublicKey,
+    account: PublicKey,
+  ): Promise<PublicKey> {
+    const [sundialMarketRegistry] =
+      await this.getSundialMarketRegistryAndBump(sundialMarket);
+    const entry = (await this.fetchSundialMarketRegistry(sundialMarket)).find(
+      e => e.account.equals(account),
+    );
+    if (!entry) {
+      throw new Error(`${account.toBase58()} is not in the registry`);
+    }
+    return (
+      await this.getRegistryEntryAndBump(sundialMarketRegistry, entry.index)
+    )[0];
+  }
+
+  public changeSundialMarketStatus(
+    sundialMarket: PublicKey,
+    status: MarketStatus,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.changeSundialMarketStatus(status, {
+        accounts: {
+          sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public proposeMarketOwner(
+    sundialMarket: PublicKey,
+    newOwner: PublicKey,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.proposeMarketOwner(newOwner, {
+        accounts: {
+          sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public acceptMarketOwner(
+    sundialMarket: PublicKey,
+    pendingOwner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.acceptMarketOwner({
+        accounts: {
+          sundialMarket,
+          pendingOwner,
+        },
+      }),
+    ]);
+  }
+
+  public changeSundialMarketRoles(
+    sundialMarket: PublicKey,
+    roles: SundialMarketRoles,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.changeSundialMarketRoles(roles, {
+        accounts: {
+          sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public changeStaleTolerances(
+    sundialMarket: PublicKey,
+    staleTolerances: StaleTolerances,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.changeStaleTolerances(
+        staleTolerances,
+        {
+          accounts: {
+            sundialMarket,
+            owner,
+          },
+        },
+      ),
+    ]);
+  }
+
+  public migrateAccount(
+    account: PublicKey,
+    payer: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.migrateAccount({
+        accounts: {
+          account,
+          payer,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async getPendingConfigChangeAndBump(
+    target: PublicKey,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [target.toBuffer(), utils.bytes.utf8.encode(PENDING_CONFIG_KEY)],
+      this.programs.Sundial.programId,
+    );
+  }
+
+  public async executeConfigChange(
+    target: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    const [pendingConfigChange] = await this.getPendingConfigChangeAndBump(
+      target,
+    );
+    const pending =
+      await this.programs.Sundial.account.pendingConfigChange.fetch(
+        pendingConfigChange,
+      );
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.executeConfigChange({
+        accounts: {
+          pendingConfigChange,
+          target,
+          payer: pending.payer,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async cancelConfigChange(
+    target: PublicKey,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): Promise<TransactionEnvelope> {
+    const [pendingConfigChange] = await this.getPendingConfigChangeAndBump(
+      target,
+    );
+    const pending =
+      await this.programs.Sundial.account.pendingConfigChange.fetch(
+        pendingConfigChange,
+      );
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.cancelConfigChange({
+        accounts: {
+          pendingConfigChange,
+          sundialMarket: pending.sundialMarket,
+          payer: pending.payer,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public changeConfigChangeDelay(
+    sundialMarket: PublicKey,
+    configChangeDelay: BN,
+    owner: PublicKey = this.provider.wallet.publicKey,
+  ): TransactionEnvelope {
+    return new TransactionEnvelope(this.provider, [
+      this.programs.Sundial.instruction.changeConfigChangeDelay(
+        configChangeDelay,
+        {
+          accounts: {
+            sundialMarket,
+            owner,
+          },
+        },
+      ),
+    ]);
+  }
+
   public async getCreateSundialMarketTx({
     sundialMarketBase,
     owner,
@@ -172,6 +436,8 @@ export class SundialSDK {
     owner: PublicKey;
     payer: PublicKey;
   }): Promise<TransactionEnvelope> {
+    const [sundialMarketRegistry, registryBump] =
+      await this.getSundialMarketRegistryAndBump(sundialMarketBase.publicKey);
     const tx = new TransactionEnvelope(this.provider, [
       this.programs.Sundial.instruction.initializeSundialMarket(owner, {
         accounts: {
@@ -180,6 +446,17 @@ export class SundialSDK {
           systemProgram: SystemProgram.programId,
         },
       }),
+      this.programs.Sundial.instruction.initializeSundialMarketRegistry(
+        registryBump,
+        {
+          accounts: {
+            sundialMarketRegistry,
+            sundialMarket: sundialMarketBase.publicKey,
+            payer,
+            systemProgram: SystemProgram.programId,
+          },
+        },
+      ),
     ]);
     return tx.addSigners(sundialMarketBase);
   }
@@ -1,9 +1,12 @@
 import { BN } from '@project-serum/anchor';
+import { PublicKey } from '@solana/web3.js';
+import type { AssetInfo, SundialOracleConfigParams } from '../../programs';
 
 export * from './sundialWrapper';
 export * from './sundialAccountWrapper';
 export * from './sundialCollateralWrapper';
 export * from './sundialProfileWrapper';
+export * from './sundialAmmWrapper';
 
 export const Buffer2BN = (BNArray: BN[]) => {
   const buffer = Buffer.alloc(BNArray.length * 8);
@@ -14,3 +17,47 @@ export const Buffer2BN = (BNArray: BN[]) => {
 };
 
 export const WAD = new BN('1000000000000000000');
+
+// Dollar value of `amount` lamports priced at `price` per whole token.
+export const tokenValue = (price: BN, amount: BN, decimals: number) =>
+  price.mul(amount).div(new BN(10).pow(new BN(decimals)));
+
+export const assetValue = (asset: AssetInfo) =>
+  tokenValue(Buffer2BN(asset.price), asset.amount, asset.decimals);
+
+export type MarketStatus =
+  | { active: Record<string, never> }
+  | { reduceOnly: Record<string, never> }
+  | { paused: Record<string, never> };
+
+export const MARKET_STATUS_ACTIVE: MarketStatus = { active: {} };
+export const MARKET_STATUS_REDUCE_ONLY: MarketStatus = { reduceOnly: {} };
+export const MARKET_STATUS_PAUSED: MarketStatus = { paused: {} };
+
+export type OracleSource =
+  | { pyth: Record<string, never> }
+  | { switchboard: Record<string, never> }
+  | { portReserve: Record<string, never> };
+
+export const ORACLE_SOURCE_PYTH: OracleSource = { pyth: {} };
+export const ORACLE_SOURCE_SWITCHBOARD: OracleSource = { switchboard: {} };
+export const ORACLE_SOURCE_PORT_RESERVE: OracleSource = { portReserve: {} };
+
+export type OraclePolicy =
+  | { primaryWithFallback: Record<string, never> }
+  | { median: Record<string, never> };
+
+export const ORACLE_POLICY_PRIMARY_WITH_FALLBACK: OraclePolicy = {
+  primaryWithFallback: {},
+};
+export const ORACLE_POLICY_MEDIAN: OraclePolicy = { median: {} };
+
+export const DEFAULT_ORACLE_CONFIG: SundialOracleConfigParams = {
+  maxConfidenceBips: 0,
+  useUpperBoundPrice: false,
+  primarySource: ORACLE_SOURCE_PYTH,
+  fallbackSource: ORACLE_SOURCE_PYTH,
+  fallbackOracle: PublicKey.default,
+  policy: ORACLE_POLICY_PRIMARY_WITH_FALLBACK,
+  maxDeviationBips: 0,
+};
@@ -6,11 +6,11 @@ import invariant from 'tiny-invariant';
 export class SundialAccountWrapper {
   public readonly program: SundialProgram;
   private key?: PublicKey;
-  private data: SundialAccountData;
+  protected data: SundialAccountData;
 
   constructor(
     public readonly sdk: SundialSDK,
-    type: 'sundial' | 'sundialCollateral' | 'sundialProfile',
+    type: 'sundial' | 'sundialCollateral' | 'sundialProfile' | 'sundialAmm',
   ) {
     this.program = sdk.programs.Sundial;
     this.data = {
@@ -61,6 +61,12 @@ export class SundialAccountWrapper {
     return this.data.data;
   }
 
+  get sundialAmmData() {
+    this.checkStateValid();
+    invariant(this.data.type === 'sundialAmm', 'Not SundialAmmData');
+    return this.data.data;
+  }
+
   public async getAuthorityAndBump(): Promise<[PublicKey, number]> {
     invariant(this.key, 'key not set');
     return this.sdk.getAuthorityAndBump(this.key);
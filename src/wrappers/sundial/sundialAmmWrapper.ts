@@ -0,0 +1,248 @@
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { utils } from '@project-serum/anchor';
+import BN from 'bn.js';
+import { TransactionEnvelope } from '@saberhq/solana-contrib';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { getATAAddress, getOrCreateATA } from '@saberhq/token-utils';
+import { SundialSDK } from '../../sdk';
+import { SundialAccountWrapper } from './sundialAccountWrapper';
+import { SUNDIAL_ADDRESSES } from '../../constants';
+import { Buffer2BN, WAD } from './index';
+
+const AMM = 'amm';
+const LP_MINT = 'lp_mint';
+const PRINCIPLE = 'principle';
+
+export type SwapDirection =
+  | { principleToLiquidity: Record<string, never> }
+  | { liquidityToPrinciple: Record<string, never> };
+
+export const SWAP_PRINCIPLE_TO_LIQUIDITY: SwapDirection = {
+  principleToLiquidity: {},
+};
+export const SWAP_LIQUIDITY_TO_PRINCIPLE: SwapDirection = {
+  liquidityToPrinciple: {},
+};
+
+export class SundialAmmWrapper extends SundialAccountWrapper {
+  constructor(sdk: SundialSDK) {
+    super(sdk, 'sundialAmm');
+  }
+
+  static async getSundialAmmKeyAndBump(
+    sundial: PublicKey,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [sundial.toBuffer(), utils.bytes.utf8.encode(AMM)],
+      SUNDIAL_ADDRESSES.Sundial,
+    );
+  }
+
+  public async getLpMintAndBump(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [this.publicKey.toBuffer(), utils.bytes.utf8.encode(LP_MINT)],
+      SUNDIAL_ADDRESSES.Sundial,
+    );
+  }
+
+  public async getPrincipleWalletAndBump(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [this.publicKey.toBuffer(), utils.bytes.utf8.encode(PRINCIPLE)],
+      SUNDIAL_ADDRESSES.Sundial,
+    );
+  }
+
+  /**
+   * The implied apy of the pool, scaled by WAD.
+   */
+  public getImpliedApy(): BN {
+    return Buffer2BN(this.sundialAmmData.impliedApy);
+  }
+
+  public getImpliedApyInPercent(): number {
+    return this.getImpliedApy().muln(10000).div(WAD).toNumber() / 100;
+  }
+
+  public async createSundialAmm({
+    sundial,
+    sundialMarket,
+    liquidityMint,
+    principleTokenMint,
+    sundialPortLiquidityWallet,
+    timeScaleInSeconds,
+    swapFeeInBips = 0,
+    owner,
+  }: {
+    sundial: PublicKey;
+    sundialMarket: PublicKey;
+    liquidityMint: PublicKey;
+    principleTokenMint: PublicKey;
+    sundialPortLiquidityWallet: PublicKey;
+    timeScaleInSeconds: BN;
+    swapFeeInBips?: number;
+    owner?: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    const [sundialAmm, pdaBump] =
+      await SundialAmmWrapper.getSundialAmmKeyAndBump(sundial);
+    this.publicKey = sundialAmm;
+    const [sundialAmmAuthority, authorityBump] =
+      await this.getAuthorityAndBump();
+    const [ammLpMint, lpMintBump] = await this.getLpMintAndBump();
+    const [ammLiquidityWallet, liquidityBump] =
+      await this.getLiquidityTokenSupplyAndBump();
+    const [ammPrincipleWallet, principleBump] =
+      await this.getPrincipleWalletAndBump();
+
+    const ix = this.program.instruction.initializeSundialAmm(
+      {
+        authorityBump,
+        lpMintBump,
+        liquidityBump,
+        principleBump,
+      },
+      {
+        swapFee: swapFeeInBips,
+        timeScaleInSeconds,
+      },
+      pdaBump,
+      {
+        accounts: {
+          sundialAmm,
+          sundialAmmAuthority,
+          ammLpMint,
+          ammLiquidityWallet,
+          ammPrincipleWallet,
+          sundial,
+          sundialMarket,
+          sundialPortLiquidityWallet,
+          liquidityMint,
+          principleTokenMint,
+          owner: owner ?? this.sdk.provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async getPoolAccounts() {
+    this.checkStateValid();
+    return {
+      sundialAmm: this.publicKey,
+      sundialAmmAuthority: (await this.getAuthorityAndBump())[0],
+      ammLiquidityWallet: (await this.getLiquidityTokenSupplyAndBump())[0],
+      ammPrincipleWallet: (await this.getPrincipleWalletAndBump())[0],
+    };
+  }
+
+  public async addLiquidity({
+    liquidityAmount,
+    maxPrincipleAmount,
+    userLiquidityWallet,
+    userPrincipleTokenWallet,
+  }: {
+    liquidityAmount: BN;
+    maxPrincipleAmount: BN;
+    userLiquidityWallet: PublicKey;
+    userPrincipleTokenWallet: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    const [ammLpMint] = await this.getLpMintAndBump();
+    const { address: userAmmLpWallet, instruction } = await getOrCreateATA({
+      provider: this.sdk.provider,
+      mint: ammLpMint,
+    });
+    const ix = this.program.instruction.addAmmLiquidity(
+      liquidityAmount,
+      maxPrincipleAmount,
+      {
+        accounts: {
+          ...(await this.getPoolAccounts()),
+          sundial: this.sundialAmmData.sundial,
+          ammLpMint,
+          userLiquidityWallet,
+          userPrincipleTokenWallet,
+          userAmmLpWallet,
+          userAuthority: this.sdk.provider.wallet.publicKey,
+          sundialMarket: this.sundialAmmData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(
+      this.sdk.provider,
+      instruction ? [instruction, ix] : [ix],
+    );
+  }
+
+  public async removeLiquidity({
+    lpAmount,
+    userLiquidityWallet,
+    userPrincipleTokenWallet,
+  }: {
+    lpAmount: BN;
+    userLiquidityWallet: PublicKey;
+    userPrincipleTokenWallet: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    const [ammLpMint] = await this.getLpMintAndBump();
+    const userAmmLpWallet = await getATAAddress({
+      mint: ammLpMint,
+      owner: this.sdk.provider.wallet.publicKey,
+    });
+    const ix = this.program.instruction.removeAmmLiquidity(lpAmount, {
+      accounts: {
+        ...(await this.getPoolAccounts()),
+        sundial: this.sundialAmmData.sundial,
+        ammLpMint,
+        userLiquidityWallet,
+        userPrincipleTokenWallet,
+        userAmmLpWallet,
+        userAuthority: this.sdk.provider.wallet.publicKey,
+        sundialMarket: this.sundialAmmData.sundialMarket,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async swap({
+    amountIn,
+    minAmountOut = new BN(0),
+    direction,
+    userLiquidityWallet,
+    userPrincipleTokenWallet,
+  }: {
+    amountIn: BN;
+    minAmountOut?: BN;
+    direction: SwapDirection;
+    userLiquidityWallet: PublicKey;
+    userPrincipleTokenWallet: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    const ix = this.program.instruction.swapAmm(
+      amountIn,
+      minAmountOut,
+      direction,
+      {
+        accounts: {
+          ...(await this.getPoolAccounts()),
+          sundial: this.sundialAmmData.sundial,
+          userLiquidityWallet,
+          userPrincipleTokenWallet,
+          userAuthority: this.sdk.provider.wallet.publicKey,
+          sundialMarket: this.sundialAmmData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+}
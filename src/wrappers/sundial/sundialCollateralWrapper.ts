@@ -13,10 +13,12 @@ import {
 } from '@port.finance/port-sdk';
 import { SundialSDK } from '../../sdk';
 import { SundialAccountWrapper } from './index';
+import type { MarketStatus } from './index';
 import { TransactionEnvelope } from '@saberhq/solana-contrib';
 import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { SUNDIAL_ADDRESSES } from '../../constants';
 import invariant from 'tiny-invariant';
+import type { SundialCollateralPriceCheckParams } from '../../programs';
 
 const COLLATERAL = 'collateral';
 export class SundialCollateralWrapper extends SundialAccountWrapper {
@@ -60,12 +62,15 @@ export class SundialCollateralWrapper extends SundialAccountWrapper {
       portLpBump,
       authorityBump,
     };
+    const { sundialMarketRegistry, registryEntry, registryEntryBump } =
+      await this.sdk.getNextRegistryEntryAccounts(sundialMarket);
 
     const ix = this.program.instruction.initializeSundialCollateral(
       bumps,
       config,
       name,
       pdaBump,
+      registryEntryBump,
       {
         accounts: {
           sundialCollateral,
@@ -78,6 +83,8 @@ export class SundialCollateralWrapper extends SundialAccountWrapper {
           rent: SYSVAR_RENT_PUBKEY,
           owner: this.sdk.provider.wallet.publicKey,
           sundialMarket,
+          sundialMarketRegistry,
+          registryEntry,
         },
       },
     );
@@ -98,9 +105,18 @@ export class SundialCollateralWrapper extends SundialAccountWrapper {
       accounts: {
         sundialCollateral: this.publicKey,
         portCollateralReserve: reserve.pubkey,
+        sundialMarket: this.sundialCollateralData.sundialMarket,
         clock: SYSVAR_CLOCK_PUBKEY,
       },
     });
+    const priceCheckOracle = this.sundialCollateralData.priceCheck.oracle;
+    if (!priceCheckOracle.equals(PublicKey.default)) {
+      ix.keys.push({
+        isSigner: false,
+        isWritable: false,
+        pubkey: priceCheckOracle,
+      });
+    }
 
     const tx = refreshReserve
       ? [
@@ -119,20 +135,111 @@ export class SundialCollateralWrapper extends SundialAccountWrapper {
   public async changeConfig(
     config: SundialCollateralConfigs,
     sundialOwner?: PublicKey,
+    rampDurationInSeconds: BN = new BN(0),
   ): Promise<TransactionEnvelope> {
     const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const [pendingConfigChange, bump] =
+      await this.sdk.getPendingConfigChangeAndBump(this.publicKey);
     const changeIx = this.program.instruction.changeSundialCollateralConfig(
       config,
+      bump,
+      rampDurationInSeconds,
       {
         accounts: {
           sundialCollateral: this.publicKey,
           owner,
           sundialMarket: this.sundialCollateralData.sundialMarket,
+          pendingConfigChange,
+          systemProgram: SystemProgram.programId,
+          clock: SYSVAR_CLOCK_PUBKEY,
         },
       },
     );
     return new TransactionEnvelope(this.sdk.provider, [changeIx]);
   }
+
+  public async changeStatus(
+    status: MarketStatus,
+    sundialOwner?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const sundialMarket = this.sundialCollateralData.sundialMarket;
+    const ix = this.program.instruction.changeSundialCollateralStatus(status, {
+      accounts: {
+        sundialCollateral: this.publicKey,
+        registryEntry: await this.sdk.getRegistryEntryKey(
+          sundialMarket,
+          this.publicKey,
+        ),
+        sundialMarket,
+        owner,
+      },
+    });
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  /**
+   * Add a SundialCollateral created before the market registry to it.
+   */
+  public async registerExisting(
+    sundialOwner?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const sundialMarket = this.sundialCollateralData.sundialMarket;
+    const { sundialMarketRegistry, registryEntry, registryEntryBump } =
+      await this.sdk.getNextRegistryEntryAccounts(sundialMarket);
+    const ix = this.program.instruction.registerExistingSundialCollateral(
+      registryEntryBump,
+      {
+        accounts: {
+          sundialCollateral: this.publicKey,
+          sundialMarket,
+          sundialMarketRegistry,
+          registryEntry,
+          owner,
+          systemProgram: SystemProgram.programId,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public changePriceCheck(
+    config: SundialCollateralPriceCheckParams,
+    sundialOwner?: PublicKey,
+  ): TransactionEnvelope {
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const ix = this.program.instruction.changeSundialCollateralPriceCheck(
+      config,
+      {
+        accounts: {
+          sundialCollateral: this.publicKey,
+          sundialMarket: this.sundialCollateralData.sundialMarket,
+          owner,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public changeStaleTol(
+    collateralStaleTol: BN,
+    sundialOwner?: PublicKey,
+  ): TransactionEnvelope {
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const ix = this.program.instruction.changeSundialCollateralStaleTol(
+      collateralStaleTol,
+      {
+        accounts: {
+          sundialCollateral: this.publicKey,
+          sundialMarket: this.sundialCollateralData.sundialMarket,
+          owner,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
 }
 
 export interface SundialCollateralConfigs {
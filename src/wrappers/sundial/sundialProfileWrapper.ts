@@ -6,6 +6,7 @@ import {
   SystemProgram,
   SYSVAR_CLOCK_PUBKEY,
   SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
 } from '@solana/web3.js';
 import { BN, utils } from '@project-serum/anchor';
 import { SUNDIAL_ADDRESSES } from '../../constants';
@@ -14,47 +15,175 @@ import { SundialCollateralWrapper } from './sundialCollateralWrapper';
 import { getATAAddress, getOrCreateATA } from '@saberhq/token-utils';
 import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { SundialWrapper } from './sundialWrapper';
-import { Buffer2BN } from './index';
+import { SundialAmmWrapper } from './sundialAmmWrapper';
+import { assetValue, Buffer2BN, tokenValue } from './index';
+import type {
+  ProfileDelegatePermissions,
+  SundialData,
+  SundialProfileCollateral,
+  SundialProfileData,
+  SundialProfileLoan,
+  SundialProgram,
+} from '../../programs';
+import invariant from 'tiny-invariant';
 
 const PROFILE = 'profile';
+const DELEGATE = 'delegate';
+
+// Layout of a zero-copy Sundial Profile: discriminator and header, then the
+// collateral slots, then the loan slots.
+const PROFILE_HEADER_SIZE = 8 + 208;
+const PROFILE_COLLATERAL_SIZE = 92;
+const PROFILE_LOAN_SIZE = 106;
+
+// Used slots come first, the first one with the default key ends them.
+const decodeSlots = <T>(
+  program: SundialProgram,
+  typeName: 'SundialProfileCollateral' | 'SundialProfileLoan',
+  data: Buffer,
+  offset: number,
+  size: number,
+  capacity: number,
+  key: (slot: T) => PublicKey,
+): T[] => {
+  const slots: T[] = [];
+  for (let i = 0; i < capacity; i++) {
+    const start = offset + i * size;
+    const slot = program.coder.types.decode<T>(
+      typeName,
+      data.slice(start, start + size),
+    );
+    if (key(slot).equals(PublicKey.default)) {
+      break;
+    }
+    slots.push(slot);
+  }
+  return slots;
+};
 
 export class SundialProfileWrapper extends SundialAccountWrapper {
+  public collaterals: SundialProfileCollateral[] = [];
+  public loans: SundialProfileLoan[] = [];
+
   constructor(sdk: SundialSDK) {
     super(sdk, 'sundialProfile');
   }
 
+  public async reloadData(): Promise<void> {
+    const accountInfo = await this.sdk.provider.connection.getAccountInfo(
+      this.publicKey,
+    );
+    invariant(accountInfo, 'Sundial Profile not found');
+    const profile: SundialProfileData = this.program.coder.accounts.decode(
+      'SundialProfile',
+      accountInfo.data,
+    );
+    this.collaterals = decodeSlots<SundialProfileCollateral>(
+      this.program,
+      'SundialProfileCollateral',
+      accountInfo.data,
+      PROFILE_HEADER_SIZE,
+      PROFILE_COLLATERAL_SIZE,
+      profile.collateralCapacity,
+      c => c.sundialCollateral,
+    );
+    this.loans = decodeSlots<SundialProfileLoan>(
+      this.program,
+      'SundialProfileLoan',
+      accountInfo.data,
+      PROFILE_HEADER_SIZE +
+        profile.collateralCapacity * PROFILE_COLLATERAL_SIZE,
+      PROFILE_LOAN_SIZE,
+      profile.loanCapacity,
+      l => l.sundial,
+    );
+    this.data = { type: 'sundialProfile', data: profile };
+  }
+
   static async getSundialProfileKey(
     user: PublicKey,
     sundialMarket: PublicKey,
+    subAccountId = 0,
   ): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
       [
         sundialMarket.toBytes(),
         user.toBytes(),
         utils.bytes.utf8.encode(PROFILE),
+        new BN(subAccountId).toArrayLike(Buffer, 'le', 2),
       ],
       SUNDIAL_ADDRESSES.Sundial,
     );
   }
 
+  static async getProfileDelegateKey(
+    sundialProfile: PublicKey,
+    delegate: PublicKey,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [
+        sundialProfile.toBytes(),
+        utils.bytes.utf8.encode(DELEGATE),
+        delegate.toBytes(),
+      ],
+      SUNDIAL_ADDRESSES.Sundial,
+    );
+  }
+
+  // The ProfileDelegate record goes last in the remaining accounts when `user`
+  // is a delegate of the profile.
+  private async pushDelegateMeta(ix: TransactionInstruction, user: PublicKey) {
+    if (user.equals(this.sundialProfileData.user)) {
+      return;
+    }
+    const [profileDelegate] = await SundialProfileWrapper.getProfileDelegateKey(
+      this.publicKey,
+      user,
+    );
+    ix.keys.push({
+      pubkey: profileDelegate,
+      isSigner: false,
+      isWritable: false,
+    });
+  }
+
+  // All the profiles of a user in a market, ordered by sub account id.
+  public async getSundialProfiles(
+    sundialMarket: PublicKey,
+    userPubkey?: PublicKey,
+  ) {
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const profiles = await this.program.account.sundialProfile.all([
+      { memcmp: { offset: 8, bytes: user.toBase58() } },
+      { memcmp: { offset: 40, bytes: sundialMarket.toBase58() } },
+    ]);
+    return profiles.sort(
+      (a, b) => a.account.subAccountId - b.account.subAccountId,
+    );
+  }
+
   public getTotalCollateralValue() {
     this.checkStateValid();
-    return this.sundialProfileData.collaterals.reduce((acc, c) => {
-      return Buffer2BN(c.asset.totalValue).add(acc);
+    return this.collaterals.reduce((acc, c) => {
+      return assetValue(c.asset).add(acc);
     }, new BN(0));
   }
 
   public getTotalLoanValue() {
     this.checkStateValid();
-    return this.sundialProfileData.loans.reduce((acc, l) => {
-      return Buffer2BN(l.asset.totalValue).add(acc);
+    return this.loans.reduce((acc, l) => {
+      return assetValue(l.asset).add(acc);
     }, new BN(0));
   }
 
   public getBorrowingPower() {
     this.checkStateValid();
-    return this.sundialProfileData.collaterals.reduce((acc, c) => {
-      const value = Buffer2BN(c.asset.totalValue)
+    return this.collaterals.reduce((acc, c) => {
+      const value = tokenValue(
+        Buffer2BN(c.borrowingPrice),
+        c.asset.amount,
+        c.asset.decimals,
+      )
         .muln(c.config.ltv.ltv)
         .divn(100);
       return value.add(acc);
@@ -63,8 +192,8 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
 
   public getLiquidationThreshold() {
     this.checkStateValid();
-    return this.sundialProfileData.collaterals.reduce((acc, c) => {
-      const value = Buffer2BN(c.asset.totalValue)
+    return this.collaterals.reduce((acc, c) => {
+      const value = assetValue(c.asset)
         .muln(c.config.liquidationConfig.liquidationThreshold)
         .divn(100);
       return value.add(acc);
@@ -73,7 +202,7 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
 
   public getCollateral(sundialCollateral: PublicKey) {
     this.checkStateValid();
-    return this.sundialProfileData.collaterals.filter(k =>
+    return this.collaterals.filter(k =>
       k.sundialCollateral.equals(sundialCollateral),
     )[0];
   }
@@ -83,15 +212,13 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
   }
 
   public getCollateralValue(sundialCollateral: PublicKey) {
-    const totalValue = this.getCollateral(sundialCollateral)?.asset.totalValue;
-    return totalValue ? Buffer2BN(totalValue) : new BN(0);
+    const asset = this.getCollateral(sundialCollateral)?.asset;
+    return asset ? assetValue(asset) : new BN(0);
   }
 
   public getLoan(sundial: PublicKey) {
     this.checkStateValid();
-    return this.sundialProfileData.loans.filter(l =>
-      l.sundial.equals(sundial),
-    )[0];
+    return this.loans.filter(l => l.sundial.equals(sundial))[0];
   }
 
   public getLoanAmount(sundial: PublicKey) {
@@ -99,21 +226,27 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
   }
 
   public getLoanValue(sundial: PublicKey) {
-    const totalValue = this.getLoan(sundial)?.asset.totalValue;
-    return totalValue ? Buffer2BN(totalValue) : new BN(0);
+    const asset = this.getLoan(sundial)?.asset;
+    return asset ? assetValue(asset) : new BN(0);
   }
 
   public async createSundialProfile(
     sundialMarket: PublicKey,
     userPubkey?: PublicKey,
+    subAccountId = 0,
   ): Promise<TransactionEnvelope> {
     const user = userPubkey ?? this.program.provider.wallet.publicKey;
     const [sundialProfile, bump] =
-      await SundialProfileWrapper.getSundialProfileKey(user, sundialMarket);
+      await SundialProfileWrapper.getSundialProfileKey(
+        user,
+        sundialMarket,
+        subAccountId,
+      );
     this.publicKey = sundialProfile;
     const ix = this.program.instruction.initializeSundialProfile(
       sundialMarket,
       bump,
+      subAccountId,
       {
         accounts: {
           sundialProfile,
@@ -126,29 +259,190 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
     return new TransactionEnvelope(this.sdk.provider, [ix]);
   }
 
+  public async resizeSundialProfile(
+    collateralCapacity: number,
+    loanCapacity: number,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const ix = this.program.instruction.resizeSundialProfile(
+      collateralCapacity,
+      loanCapacity,
+      {
+        accounts: {
+          sundialProfile: this.publicKey,
+          user,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async closeSundialProfile(
+    receiver?: PublicKey,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const ix = this.program.instruction.closeSundialProfile({
+      accounts: {
+        sundialProfile: this.publicKey,
+        user,
+        receiver: receiver ?? user,
+      },
+    });
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  // Moves the profile to `newUser`, who has to sign the transaction too. The
+  // wrapper follows the new profile.
+  public async transferSundialProfile(
+    newUser: PublicKey,
+    subAccountId = 0,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const sundialMarket = this.sundialProfileData.sundialMarket;
+    const [newSundialProfile, bump] =
+      await SundialProfileWrapper.getSundialProfileKey(
+        newUser,
+        sundialMarket,
+        subAccountId,
+      );
+    const ix = this.program.instruction.transferSundialProfile(
+      subAccountId,
+      bump,
+      {
+        accounts: {
+          sundialProfile: this.publicKey,
+          sundialMarket,
+          user,
+          newUser,
+          newSundialProfile,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+    this.publicKey = newSundialProfile;
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async initializeProfileDelegate(
+    delegate: PublicKey,
+    permissions: ProfileDelegatePermissions,
+    expiryUnixTimestamp: BN,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const [profileDelegate, bump] =
+      await SundialProfileWrapper.getProfileDelegateKey(
+        this.publicKey,
+        delegate,
+      );
+    const ix = this.program.instruction.initializeProfileDelegate(
+      delegate,
+      permissions,
+      expiryUnixTimestamp,
+      bump,
+      {
+        accounts: {
+          sundialProfile: this.publicKey,
+          profileDelegate,
+          user,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async changeProfileDelegate(
+    delegate: PublicKey,
+    permissions: ProfileDelegatePermissions,
+    expiryUnixTimestamp: BN,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const [profileDelegate] = await SundialProfileWrapper.getProfileDelegateKey(
+      this.publicKey,
+      delegate,
+    );
+    const ix = this.program.instruction.changeProfileDelegate(
+      permissions,
+      expiryUnixTimestamp,
+      {
+        accounts: {
+          sundialProfile: this.publicKey,
+          profileDelegate,
+          user,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
+  public async closeProfileDelegate(
+    delegate: PublicKey,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const [profileDelegate] = await SundialProfileWrapper.getProfileDelegateKey(
+      this.publicKey,
+      delegate,
+    );
+    const ix = this.program.instruction.closeProfileDelegate({
+      accounts: {
+        profileDelegate,
+        sundialProfile: this.publicKey,
+        user,
+      },
+    });
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
   public async refreshSundialProfile(): Promise<TransactionEnvelope> {
     this.checkStateValid();
+    const sundialProfile = this.sundialProfileData;
     const ix = this.program.instruction.refreshSundialProfile({
       accounts: {
         profile: this.publicKey,
+        sundialMarket: sundialProfile.sundialMarket,
         clock: SYSVAR_CLOCK_PUBKEY,
       },
     });
 
-    const sundialProfile = this.sundialProfileData;
-    const collaterals = sundialProfile.collaterals.map(
-      c => c.sundialCollateral,
-    );
-    const oracles = sundialProfile.loans.map(l => l.oracle);
-    const collateralAndOraclesMetas = collaterals.concat(oracles).map(k => {
-      return {
-        pubkey: k,
-        isSigner: false,
-        isWritable: false,
-      };
-    });
+    const collaterals = this.collaterals.map(c => c.sundialCollateral);
+    const oracles = this.loans.map(l => l.oracle);
+    const sundials = this.loans.map(l => l.sundial);
+    const fallbackOracles = (
+      await this.program.account.sundial.fetchMultiple(sundials)
+    )
+      .map(sundial => (sundial as SundialData).fallbackOracle)
+      .filter(oracle => !oracle.equals(PublicKey.default));
+    const remainingMetas = collaterals
+      .concat(oracles)
+      .concat(sundials)
+      .concat(fallbackOracles)
+      .map(k => {
+        return {
+          pubkey: k,
+          isSigner: false,
+          isWritable: false,
+        };
+      });
 
-    ix.keys.push(...collateralAndOraclesMetas);
+    ix.keys.push(...remainingMetas);
     return new TransactionEnvelope(this.sdk.provider, [ix]);
   }
 
@@ -183,11 +477,14 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         sundialCollateral,
         sundialCollateralPortLpWallet,
         userPortLpWallet,
+        sundialMarket: this.sundialProfileData.sundialMarket,
         tokenProgram: TOKEN_PROGRAM_ID,
         user,
         transferAuthority,
+        clock: SYSVAR_CLOCK_PUBKEY,
       },
     });
+    await this.pushDelegateMeta(ix, user);
     const tx = new TransactionEnvelope(this.sdk.provider, [ix]);
     if (transferAuthorityKP) {
       tx.addSigners(transferAuthorityKP);
@@ -219,7 +516,7 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         : await getOrCreateATA({
             provider: this.sdk.provider,
             mint: sundialPrincipleMint,
-            owner: user,
+            owner: this.sundialProfileData.user,
           });
 
     const ix2 = this.program.instruction.mintSundialLiquidityWithCollateral(
@@ -232,6 +529,7 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
           sundialPrincipleMint,
           feeReceiverWallet,
           userPrincipleWallet,
+          sundialMarket: this.sundialProfileData.sundialMarket,
           tokenProgram: TOKEN_PROGRAM_ID,
           clock: SYSVAR_CLOCK_PUBKEY,
           user,
@@ -244,7 +542,17 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         isWritable: false,
         pubkey: sundialWrapper.sundialData.oracle,
       });
+      if (
+        !sundialWrapper.sundialData.fallbackOracle.equals(PublicKey.default)
+      ) {
+        ix2.keys.push({
+          isSigner: false,
+          isWritable: false,
+          pubkey: sundialWrapper.sundialData.fallbackOracle,
+        });
+      }
     }
+    await this.pushDelegateMeta(ix2, user);
     return new TransactionEnvelope(
       this.sdk.provider,
       [ix1, ix2].filter(ix => !!ix),
@@ -278,7 +586,7 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         : await getOrCreateATA({
             provider: this.sdk.provider,
             mint: sundialCollateralWrapper.sundialCollateralData.collateralMint,
-            owner: user,
+            owner: this.sundialProfileData.user,
           });
 
     const ix2 = this.program.instruction.withdrawSundialCollateral(amount, {
@@ -288,11 +596,13 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         sundialCollateralAuthority,
         sundialCollateralPortLpWallet,
         userPortLpWallet,
+        sundialMarket: this.sundialProfileData.sundialMarket,
         tokenProgram: TOKEN_PROGRAM_ID,
         clock: SYSVAR_CLOCK_PUBKEY,
         user,
       },
     });
+    await this.pushDelegateMeta(ix2, user);
 
     return new TransactionEnvelope(
       this.sdk.provider,
@@ -300,6 +610,27 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
     );
   }
 
+  public async transferSundialCollateral(
+    amount: BN,
+    sundialCollateralWrapper: SundialCollateralWrapper,
+    destinationSundialProfile: PublicKey,
+    userPubkey?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const ix = this.program.instruction.transferSundialCollateral(amount, {
+      accounts: {
+        sundialProfile: this.publicKey,
+        destinationSundialProfile,
+        sundialCollateral: sundialCollateralWrapper.publicKey,
+        sundialMarket: this.sundialProfileData.sundialMarket,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        user,
+      },
+    });
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
   public async repaySundialLiquidity(
     amount: BN,
     sundialWrapper: SundialWrapper,
@@ -324,9 +655,11 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         userLiquidityWallet,
         user,
         transferAuthority,
+        sundialMarket: this.sundialProfileData.sundialMarket,
         tokenProgram: TOKEN_PROGRAM_ID,
       },
     });
+    await this.pushDelegateMeta(ix, user);
 
     const tx = new TransactionEnvelope(this.sdk.provider, [ix]);
     if (transferAuthorityKP) {
@@ -336,6 +669,99 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
     return tx;
   }
 
+  public async repaySundialPrinciple(
+    amount: BN,
+    sundialWrapper: SundialWrapper,
+    userPrincipleWalletPubkey?: PublicKey,
+    userPubkey?: PublicKey,
+    transferAuthorityKP?: Keypair,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+    const transferAuthority = transferAuthorityKP
+      ? transferAuthorityKP.publicKey
+      : user;
+
+    const userPrincipleWallet =
+      userPrincipleWalletPubkey ??
+      (await sundialWrapper.getUserPrincipleWallet(user));
+    const ix = this.program.instruction.repaySundialPrinciple(amount, {
+      accounts: {
+        sundialProfile: this.publicKey,
+        sundial: sundialWrapper.publicKey,
+        sundialPrincipleMint: (
+          await sundialWrapper.getPrincipleMintAndBump()
+        )[0],
+        userPrincipleWallet,
+        user,
+        transferAuthority,
+        sundialMarket: this.sundialProfileData.sundialMarket,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+    await this.pushDelegateMeta(ix, user);
+
+    const tx = new TransactionEnvelope(this.sdk.provider, [ix]);
+    if (transferAuthorityKP) {
+      tx.addSigners(transferAuthorityKP);
+    }
+
+    return tx;
+  }
+
+  public async rolloverLoan({
+    mintAmount,
+    minLiquidityOut = new BN(0),
+    sundialWrapper,
+    newSundialWrapper,
+    sundialAmmWrapper,
+    userLiquidityWallet,
+    userPubkey,
+  }: {
+    mintAmount: BN;
+    minLiquidityOut?: BN;
+    sundialWrapper: SundialWrapper;
+    newSundialWrapper: SundialWrapper;
+    sundialAmmWrapper: SundialAmmWrapper;
+    userLiquidityWallet: PublicKey;
+    userPubkey?: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const user = userPubkey ?? this.program.provider.wallet.publicKey;
+
+    const ix = this.program.instruction.rolloverLoan(
+      mintAmount,
+      minLiquidityOut,
+      {
+        accounts: {
+          sundialProfile: this.publicKey,
+          sundial: sundialWrapper.publicKey,
+          sundialLiquidityWallet: (
+            await sundialWrapper.getLiquidityTokenSupplyAndBump()
+          )[0],
+          newSundial: newSundialWrapper.publicKey,
+          newSundialAuthority: (
+            await newSundialWrapper.getAuthorityAndBump()
+          )[0],
+          newSundialPrincipleMint: (
+            await newSundialWrapper.getPrincipleMintAndBump()
+          )[0],
+          newSundialFeeReceiverWallet: (
+            await newSundialWrapper.getFeeReceiverAndBump()
+          )[0],
+          ...(await sundialAmmWrapper.getPoolAccounts()),
+          userLiquidityWallet,
+          user,
+          sundialMarket: this.sundialProfileData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      },
+    );
+
+    return new TransactionEnvelope(this.sdk.provider, [ix]);
+  }
+
   public async liquidateSundialProfile(
     sundialCollateralWrapper: SundialCollateralWrapper,
     sundialWrapper: SundialWrapper,
@@ -382,6 +808,7 @@ export class SundialProfileWrapper extends SundialAccountWrapper {
         sundialLiquidityWallet,
         sundialCollateralWallet,
         transferAuthority,
+        sundialMarket: this.sundialProfileData.sundialMarket,
         tokenProgram: TOKEN_PROGRAM_ID,
         clock: SYSVAR_CLOCK_PUBKEY,
       },
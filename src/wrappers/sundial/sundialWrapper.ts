@@ -17,7 +17,9 @@ import {
 } from '@port.finance/port-sdk';
 import { getATAAddress, getOrCreateATA, MAX_U64 } from '@saberhq/token-utils';
 import { SundialAccountWrapper } from './sundialAccountWrapper';
+import type { MarketStatus } from './index';
 import { SUNDIAL_ADDRESSES } from '../../constants';
+import type { SundialOracleConfigParams } from '../../programs';
 import { utils } from '@project-serum/anchor';
 
 const PORT_LENDING = new PublicKey(
@@ -25,6 +27,7 @@ const PORT_LENDING = new PublicKey(
 );
 
 const SUNDIAL_STR = 'sundial';
+const YIELD_POSITION = 'yield_position';
 function divCeiln(dividend: BN, divisor: number): BN {
   return dividend.addn(divisor - 1).divn(divisor);
 }
@@ -100,6 +103,8 @@ export class SundialWrapper extends SundialAccountWrapper {
     const [feeReceiverWallet, feeReceiverBump] =
       await this.getFeeReceiverAndBump();
     const [sundialAuthority, authorityBump] = await this.getAuthorityAndBump();
+    const { sundialMarketRegistry, registryEntry, registryEntryBump } =
+      await this.sdk.getNextRegistryEntryAccounts(sundialMarket);
 
     return new TransactionEnvelope(this.sdk.provider, [
       refreshReserveInstruction(
@@ -127,6 +132,7 @@ export class SundialWrapper extends SundialAccountWrapper {
         oracle,
         sundialName,
         pdaBump,
+        registryEntryBump,
         {
           accounts: {
             sundial,
@@ -144,6 +150,8 @@ export class SundialWrapper extends SundialAccountWrapper {
             rent: SYSVAR_RENT_PUBKEY,
             owner,
             sundialMarket,
+            sundialMarketRegistry,
+            registryEntry,
             clock: SYSVAR_CLOCK_PUBKEY,
           },
         },
@@ -214,6 +222,7 @@ export class SundialWrapper extends SundialAccountWrapper {
             reserveLiquidityWallet: reserve.data.liquidity.supplyPubkey,
             portLendingProgram: PORT_LENDING,
           },
+          sundialMarket: this.sundialData.sundialMarket,
           tokenProgram: TOKEN_PROGRAM_ID,
           clock: SYSVAR_CLOCK_PUBKEY,
         },
@@ -264,6 +273,7 @@ export class SundialWrapper extends SundialAccountWrapper {
           userLiquidityWallet,
           userPrincipleTokenWallet: principleAssocTokenAccount,
           userAuthority,
+          sundialMarket: this.sundialData.sundialMarket,
           tokenProgram: TOKEN_PROGRAM_ID,
           clock: SYSVAR_CLOCK_PUBKEY,
         },
@@ -314,6 +324,7 @@ export class SundialWrapper extends SundialAccountWrapper {
           userLiquidityWallet,
           userYieldTokenWallet,
           userAuthority,
+          sundialMarket: this.sundialData.sundialMarket,
           tokenProgram: TOKEN_PROGRAM_ID,
           clock: SYSVAR_CLOCK_PUBKEY,
         },
@@ -326,12 +337,504 @@ export class SundialWrapper extends SundialAccountWrapper {
     return tx;
   }
 
+  public async recombineTokens({
+    amount,
+    reserve,
+    userLiquidityWallet,
+    ownerPubkey,
+    userAuthorityKP,
+  }: {
+    amount: BN;
+    reserve: ParsedAccount<ReserveData>;
+    userLiquidityWallet: PublicKey;
+    ownerPubkey?: PublicKey;
+    userAuthorityKP?: Keypair;
+  }) {
+    this.checkStateValid();
+
+    const owner = ownerPubkey ?? this.sdk.provider.wallet.publicKey;
+    const userAuthority = userAuthorityKP ? userAuthorityKP.publicKey : owner;
+    const [principleTokenMint] = await this.getPrincipleMintAndBump();
+    const [yieldTokenMint] = await this.getYieldMintAndBump();
+    const userPrincipleTokenWallet = await getATAAddress({
+      mint: principleTokenMint,
+      owner,
+    });
+    const userYieldTokenWallet = await getATAAddress({
+      mint: yieldTokenMint,
+      owner,
+    });
+    const [lendingMarketAuthority] = await PublicKey.findProgramAddress(
+      [reserve.data.lendingMarket.toBuffer()],
+      PORT_LENDING,
+    );
+
+    const tx = new TransactionEnvelope(this.sdk.provider, [
+      refreshReserveInstruction(
+        reserve.pubkey,
+        reserve.data.liquidity.oracleOption == 1
+          ? reserve.data.liquidity.oraclePubkey
+          : null,
+      ),
+      this.program.instruction.recombineTokens(amount, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialPortLpWallet: (await this.getLPTokenSupplyAndBump())[0],
+          principleTokenMint,
+          yieldTokenMint,
+          portAccounts: {
+            lendingMarket: reserve.data.lendingMarket,
+            lendingMarketAuthority,
+            reserve: reserve.pubkey,
+            reserveCollateralMint: reserve.data.collateral.mintPubkey,
+            reserveLiquidityWallet: reserve.data.liquidity.supplyPubkey,
+            portLendingProgram: PORT_LENDING,
+          },
+          userLiquidityWallet,
+          userPrincipleTokenWallet,
+          userYieldTokenWallet,
+          userAuthority,
+          sundialMarket: this.sundialData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+
+    if (userAuthorityKP) {
+      tx.addSigners(userAuthorityKP);
+    }
+    return tx;
+  }
+
+  public async withdrawSundialFees({
+    amount = new BN(MAX_U64.toString()),
+    treasuryWallet,
+    sundialOwner,
+  }: {
+    amount?: BN;
+    treasuryWallet: PublicKey;
+    sundialOwner?: PublicKey;
+  }) {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.withdrawSundialFees(amount, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialMarket: this.sundialData.sundialMarket,
+          owner,
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialFeeReceiverWallet: (await this.getFeeReceiverAndBump())[0],
+          sundialPortLiquidityWallet: (
+            await this.getLiquidityTokenSupplyAndBump()
+          )[0],
+          sundialPortLpWallet: (await this.getLPTokenSupplyAndBump())[0],
+          principleTokenMint: (await this.getPrincipleMintAndBump())[0],
+          treasuryWallet,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async closeSundial({
+    treasuryLiquidityWallet,
+    treasuryLpWallet,
+    receiver,
+    sundialOwner,
+  }: {
+    treasuryLiquidityWallet: PublicKey;
+    treasuryLpWallet: PublicKey;
+    receiver?: PublicKey;
+    sundialOwner?: PublicKey;
+  }) {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const sundialMarket = this.sundialData.sundialMarket;
+
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.closeSundial({
+        accounts: {
+          sundial: this.publicKey,
+          registryEntry: await this.sdk.getRegistryEntryKey(
+            sundialMarket,
+            this.publicKey,
+          ),
+          sundialMarket,
+          owner,
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialPortLiquidityWallet: (
+            await this.getLiquidityTokenSupplyAndBump()
+          )[0],
+          sundialPortLpWallet: (await this.getLPTokenSupplyAndBump())[0],
+          sundialFeeReceiverWallet: (await this.getFeeReceiverAndBump())[0],
+          principleTokenMint: (await this.getPrincipleMintAndBump())[0],
+          yieldTokenMint: (await this.getYieldMintAndBump())[0],
+          treasuryLiquidityWallet,
+          treasuryLpWallet,
+          receiver: receiver ?? owner,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async changeConfig({
+    lendingFeeInBips,
+    borrowingFeeInBips,
+    liquidityCap,
+    sundialOwner,
+  }: {
+    lendingFeeInBips: number;
+    borrowingFeeInBips: number;
+    liquidityCap: BN;
+    sundialOwner?: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const [pendingConfigChange, bump] =
+      await this.sdk.getPendingConfigChangeAndBump(this.publicKey);
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.changeSundialConfig(
+        {
+          lendingFee: lendingFeeInBips,
+          borrowFee: borrowingFeeInBips,
+          liquidityCap,
+        },
+        bump,
+        {
+          accounts: {
+            sundial: this.publicKey,
+            sundialMarket: this.sundialData.sundialMarket,
+            pendingConfigChange,
+            owner,
+            systemProgram: SystemProgram.programId,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+        },
+      ),
+    ]);
+  }
+
+  public async changeStatus(
+    status: MarketStatus,
+    sundialOwner?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const sundialMarket = this.sundialData.sundialMarket;
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.changeSundialStatus(status, {
+        accounts: {
+          sundial: this.publicKey,
+          registryEntry: await this.sdk.getRegistryEntryKey(
+            sundialMarket,
+            this.publicKey,
+          ),
+          sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  /**
+   * Add a Sundial created before the market registry to it.
+   */
+  public async registerExisting(
+    sundialOwner?: PublicKey,
+  ): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    const sundialMarket = this.sundialData.sundialMarket;
+    const { sundialMarketRegistry, registryEntry, registryEntryBump } =
+      await this.sdk.getNextRegistryEntryAccounts(sundialMarket);
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.registerExistingSundial(registryEntryBump, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialMarket,
+          sundialMarketRegistry,
+          registryEntry,
+          owner,
+          systemProgram: SystemProgram.programId,
+        },
+      }),
+    ]);
+  }
+
+  public changeOracleConfig(
+    config: SundialOracleConfigParams,
+    sundialOwner?: PublicKey,
+  ): TransactionEnvelope {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.changeSundialOracleConfig(config, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialMarket: this.sundialData.sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public changeOracleStaleTol(
+    oracleStaleTol: BN,
+    sundialOwner?: PublicKey,
+  ): TransactionEnvelope {
+    this.checkStateValid();
+    const owner = sundialOwner ?? this.sdk.provider.wallet.publicKey;
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.changeSundialOracleStaleTol(oracleStaleTol, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialMarket: this.sundialData.sundialMarket,
+          owner,
+        },
+      }),
+    ]);
+  }
+
+  public async rolloverPrincipal({
+    amount,
+    newSundial,
+    reserve,
+    userAuthorityKP,
+  }: {
+    amount: BN;
+    newSundial: SundialWrapper;
+    reserve: ParsedAccount<ReserveData>;
+    userAuthorityKP?: Keypair;
+  }) {
+    this.checkStateValid();
+    newSundial.checkStateValid();
+    const userAuthority = userAuthorityKP
+      ? userAuthorityKP.publicKey
+      : this.sdk.provider.wallet.publicKey;
+    const [newPrincipleTokenMint] = await newSundial.getPrincipleMintAndBump();
+    const [newYieldTokenMint] = await newSundial.getYieldMintAndBump();
+    const { address: userNewPrincipleTokenWallet, instruction: ix1 } =
+      await getOrCreateATA({
+        provider: this.sdk.provider,
+        mint: newPrincipleTokenMint,
+        owner: userAuthority,
+      });
+    const { address: userNewYieldTokenWallet, instruction: ix2 } =
+      await getOrCreateATA({
+        provider: this.sdk.provider,
+        mint: newYieldTokenMint,
+        owner: userAuthority,
+      });
+
+    const ixs = [ix1, ix2].filter(ix => !!ix);
+
+    const [lendingMarketAuthority] = await PublicKey.findProgramAddress(
+      [reserve.data.lendingMarket.toBuffer()],
+      PORT_LENDING,
+    );
+
+    ixs.push(
+      refreshReserveInstruction(
+        reserve.pubkey,
+        reserve.data.liquidity.oracleOption == 1
+          ? reserve.data.liquidity.oraclePubkey
+          : null,
+      ),
+      this.program.instruction.rolloverPrincipal(amount, {
+        accounts: {
+          sundial: this.publicKey,
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialPortLiquidityWallet: (
+            await this.getLiquidityTokenSupplyAndBump()
+          )[0],
+          sundialPortLpWallet: (await this.getLPTokenSupplyAndBump())[0],
+          principleTokenMint: (await this.getPrincipleMintAndBump())[0],
+          newSundial: newSundial.publicKey,
+          newSundialAuthority: (await newSundial.getAuthorityAndBump())[0],
+          newSundialPortLpWallet: (
+            await newSundial.getLPTokenSupplyAndBump()
+          )[0],
+          newSundialFeeReceiverWallet: (
+            await newSundial.getFeeReceiverAndBump()
+          )[0],
+          newPrincipleTokenMint,
+          newYieldTokenMint,
+          portAccounts: {
+            lendingMarket: reserve.data.lendingMarket,
+            lendingMarketAuthority,
+            reserve: reserve.pubkey,
+            reserveCollateralMint: reserve.data.collateral.mintPubkey,
+            reserveLiquidityWallet: reserve.data.liquidity.supplyPubkey,
+            portLendingProgram: PORT_LENDING,
+          },
+          userPrincipleTokenWallet: await this.getUserPrincipleWallet(
+            userAuthority,
+          ),
+          userNewPrincipleTokenWallet,
+          userNewYieldTokenWallet,
+          userAuthority,
+          sundialMarket: this.sundialData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    );
+
+    const tx = new TransactionEnvelope(this.sdk.provider, ixs);
+    if (userAuthorityKP) {
+      tx.addSigners(userAuthorityKP);
+    }
+    return tx;
+  }
+
+  public async getYieldPositionKeyAndBump(
+    owner?: PublicKey,
+  ): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [
+        this.publicKey.toBuffer(),
+        (owner ?? this.sdk.provider.wallet.publicKey).toBuffer(),
+        utils.bytes.utf8.encode(YIELD_POSITION),
+      ],
+      SUNDIAL_ADDRESSES.Sundial,
+    );
+  }
+
+  public async createYieldPosition(): Promise<TransactionEnvelope> {
+    const [yieldPosition, bump] = await this.getYieldPositionKeyAndBump();
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.initializeYieldPosition(bump, {
+        accounts: {
+          yieldPosition,
+          sundial: this.publicKey,
+          sundialMarket: this.sundialData.sundialMarket,
+          owner: this.sdk.provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async depositYieldPosition(amount: BN): Promise<TransactionEnvelope> {
+    const owner = this.sdk.provider.wallet.publicKey;
+    const [yieldTokenMint] = await this.getYieldMintAndBump();
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.depositYieldPosition(amount, {
+        accounts: {
+          sundial: this.publicKey,
+          yieldPosition: (await this.getYieldPositionKeyAndBump())[0],
+          yieldTokenMint,
+          userYieldTokenWallet: await getATAAddress({
+            mint: yieldTokenMint,
+            owner,
+          }),
+          owner,
+          sundialMarket: this.sundialData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async withdrawYieldPosition({
+    amount,
+    userLiquidityWallet,
+  }: {
+    amount: BN;
+    userLiquidityWallet: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const owner = this.sdk.provider.wallet.publicKey;
+    const [yieldTokenMint] = await this.getYieldMintAndBump();
+    return new TransactionEnvelope(this.sdk.provider, [
+      this.program.instruction.withdrawYieldPosition(amount, {
+        accounts: {
+          sundial: this.publicKey,
+          yieldPosition: (await this.getYieldPositionKeyAndBump())[0],
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialPortLiquidityWallet: (
+            await this.getLiquidityTokenSupplyAndBump()
+          )[0],
+          yieldTokenMint,
+          userYieldTokenWallet: await getATAAddress({
+            mint: yieldTokenMint,
+            owner,
+          }),
+          userLiquidityWallet,
+          owner,
+          sundialMarket: this.sundialData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  public async claimYieldPosition({
+    reserve,
+    userLiquidityWallet,
+  }: {
+    reserve: ParsedAccount<ReserveData>;
+    userLiquidityWallet: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    this.checkStateValid();
+    const [lendingMarketAuthority] = await PublicKey.findProgramAddress(
+      [reserve.data.lendingMarket.toBuffer()],
+      PORT_LENDING,
+    );
+    return new TransactionEnvelope(this.sdk.provider, [
+      refreshReserveInstruction(
+        reserve.pubkey,
+        reserve.data.liquidity.oracleOption == 1
+          ? reserve.data.liquidity.oraclePubkey
+          : null,
+      ),
+      this.program.instruction.claimYieldPosition({
+        accounts: {
+          sundial: this.publicKey,
+          yieldPosition: (await this.getYieldPositionKeyAndBump())[0],
+          sundialAuthority: (await this.getAuthorityAndBump())[0],
+          sundialPortLpWallet: (await this.getLPTokenSupplyAndBump())[0],
+          sundialPortLiquidityWallet: (
+            await this.getLiquidityTokenSupplyAndBump()
+          )[0],
+          principleTokenMint: (await this.getPrincipleMintAndBump())[0],
+          yieldTokenMint: (await this.getYieldMintAndBump())[0],
+          portAccounts: {
+            lendingMarket: reserve.data.lendingMarket,
+            lendingMarketAuthority,
+            reserve: reserve.pubkey,
+            reserveCollateralMint: reserve.data.collateral.mintPubkey,
+            reserveLiquidityWallet: reserve.data.liquidity.supplyPubkey,
+            portLendingProgram: PORT_LENDING,
+          },
+          userLiquidityWallet,
+          owner: this.sdk.provider.wallet.publicKey,
+          sundialMarket: this.sundialData.sundialMarket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
   public async redeemPortLp({
     lendingMarket,
     reserve,
+    amount = new BN(MAX_U64.toString()),
   }: {
     lendingMarket: PublicKey;
     reserve: ParsedAccount<ReserveData>;
+    amount?: BN;
   }) {
     this.checkStateValid();
 
@@ -342,7 +845,7 @@ export class SundialWrapper extends SundialAccountWrapper {
     );
 
     ixs.push(
-      this.program.instruction.redeemLp({
+      this.program.instruction.redeemLp(amount, {
         accounts: {
           sundial: this.publicKey,
           sundialAuthority: (await this.getAuthorityAndBump())[0],
@@ -358,6 +861,7 @@ export class SundialWrapper extends SundialAccountWrapper {
             reserveCollateralMint: reserve.data.collateral.mintPubkey,
             portLendingProgram: PORT_LENDING,
           },
+          sundialMarket: this.sundialData.sundialMarket,
           tokenProgram: TOKEN_PROGRAM_ID,
           clock: SYSVAR_CLOCK_PUBKEY,
         },
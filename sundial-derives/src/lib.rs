@@ -61,9 +61,16 @@ pub fn check_sundial_profile_stale(input: TokenStream) -> TokenStream {
     (quote! {
         impl<'a> crate::helpers::CheckSundialProfileStale for #name<'a> {
              fn check_sundial_profile_stale(&self) -> ProgramResult {
-                self.sundial_profile.last_update.check_stale(
+                let sundial_profile = self.sundial_profile.load()?;
+                vipers::assert_keys_eq!(
+                    sundial_profile.sundial_market,
+                    self.sundial_market.key(),
+                    crate::error::SundialError::SundialMarketNotMatch,
+                    "Sundial Profile's market not matches the one passed in"
+                );
+                sundial_profile.last_update().check_stale(
                     &self.clock,
-                    crate::helpers::SUNDIAL_PROFILE_STALE_TOL,
+                    self.sundial_market.stale_tolerances.profile(),
                     "Sundial Profile Is Stale"
                 )
              }
@@ -72,19 +79,15 @@ pub fn check_sundial_profile_stale(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(CheckSundialMarketOwner)]
+#[proc_macro_derive(CheckSundialMarketOwner, attributes(sundial_role))]
 pub fn check_sundial_market_owner(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
+    let check_authority = authority_check(&ast);
     (quote! {
         impl<'a> crate::helpers::CheckSundialMarketOwner for #name<'a> {
              fn check_sundial_market_owner(&self) -> ProgramResult {
-                vipers::assert_keys_eq!(
-                    self.sundial_market.owner,
-                    *self.owner.key,
-                    SundialError::InvalidOwner,
-                    "Invalid Sundial Market Owner"
-                );
+                #check_authority
                 Ok(())
             }
         }
@@ -105,7 +108,7 @@ pub fn check_sundial_profile_market(input: TokenStream) -> TokenStream {
         quote! {
             vipers::assert_keys_eq!(
                 self.sundial.sundial_market,
-                self.sundial_profile.sundial_market,
+                sundial_profile.sundial_market,
                 SundialError::SundialMarketNotMatch,
                 "Sundial's market not matches the one on sundial profile"
             );
@@ -118,7 +121,7 @@ pub fn check_sundial_profile_market(input: TokenStream) -> TokenStream {
         quote! {
             vipers::assert_keys_eq!(
                 self.sundial_collateral.sundial_market,
-                self.sundial_profile.sundial_market,
+                sundial_profile.sundial_market,
                 SundialError::SundialMarketNotMatch,
                 "Sundial Collateral's market not matches the one on sundial profile"
             );
@@ -130,6 +133,7 @@ pub fn check_sundial_profile_market(input: TokenStream) -> TokenStream {
     (quote! {
         impl<'a> crate::helpers::CheckSundialProfileMarket for #name<'a> {
              fn check_sundial_profile_market(&self) -> ProgramResult {
+                let sundial_profile = self.sundial_profile.load()?;
                 #sundial_check;
                 #sundial_collateral_check;
                 Ok(())
@@ -139,6 +143,37 @@ pub fn check_sundial_profile_market(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// `#[sundial_role(risk_admin)]` lets the holder of that role on the `SundialMarket` sign in
+/// place of the owner, without it only the owner can.
+fn authority_check(ast: &DeriveInput) -> proc_macro2::TokenStream {
+    let role = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("sundial_role"))
+        .map(|attr| attr.parse_args::<Ident>().expect("Invalid sundial role"));
+    match role {
+        Some(role) => {
+            let message = format!("Neither Sundial Market Owner nor {}", role);
+            quote! {
+                vipers::invariant!(
+                    *self.owner.key == self.sundial_market.owner
+                        || *self.owner.key == self.sundial_market.roles.#role,
+                    SundialError::InvalidOwner,
+                    #message
+                );
+            }
+        }
+        None => quote! {
+            vipers::assert_keys_eq!(
+                self.sundial_market.owner,
+                *self.owner.key,
+                SundialError::InvalidOwner,
+                "Invalid Sundial Market Owner"
+            );
+        },
+    }
+}
+
 fn has_field(struct_input: &Data, field_name: &str) -> bool {
     if let Struct(data) = &struct_input {
         if let Named(ref fields) = data.fields {
@@ -154,7 +189,86 @@ fn has_field(struct_input: &Data, field_name: &str) -> bool {
     false
 }
 
-#[proc_macro_derive(CheckSundialOwner)]
+fn status_check(
+    ast: &DeriveInput,
+    trait_name: Ident,
+    fn_name: Ident,
+    check: proc_macro2::TokenStream,
+) -> TokenStream {
+    let name = &ast.ident;
+    let has_sundial = has_field(&ast.data, "sundial");
+    let has_sundial_collateral = has_field(&ast.data, "sundial_collateral");
+    if !has_field(&ast.data, "sundial_market") {
+        panic!("Has no sundial market")
+    }
+    if !has_sundial && !has_sundial_collateral {
+        panic!("Has neither sundial or sundial collateral")
+    }
+
+    let sundial_check = if has_sundial {
+        quote! {
+            vipers::assert_keys_eq!(
+                self.sundial.sundial_market,
+                self.sundial_market.key(),
+                crate::error::SundialError::SundialMarketNotMatch,
+                "Sundial's market not matches the one passed in"
+            );
+            self.sundial.status.#check("Sundial")?;
+        }
+    } else {
+        quote! {}
+    };
+
+    let sundial_collateral_check = if has_sundial_collateral {
+        quote! {
+            vipers::assert_keys_eq!(
+                self.sundial_collateral.sundial_market,
+                self.sundial_market.key(),
+                crate::error::SundialError::SundialMarketNotMatch,
+                "Sundial Collateral's market not matches the one passed in"
+            );
+            self.sundial_collateral.status.#check("Sundial Collateral")?;
+        }
+    } else {
+        quote! {}
+    };
+
+    (quote! {
+        impl<'a> crate::helpers::#trait_name for #name<'a> {
+             fn #fn_name(&self) -> ProgramResult {
+                self.sundial_market.status.#check("Sundial Market")?;
+                #sundial_check
+                #sundial_collateral_check
+                Ok(())
+             }
+        }
+    })
+    .into()
+}
+
+#[proc_macro_derive(CheckSundialActive)]
+pub fn check_sundial_active(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    status_check(
+        &ast,
+        format_ident!("CheckSundialActive"),
+        format_ident!("check_sundial_active"),
+        quote! { check_active },
+    )
+}
+
+#[proc_macro_derive(CheckSundialNotPaused)]
+pub fn check_sundial_not_paused(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    status_check(
+        &ast,
+        format_ident!("CheckSundialNotPaused"),
+        format_ident!("check_sundial_not_paused"),
+        quote! { check_not_paused },
+    )
+}
+
+#[proc_macro_derive(CheckSundialOwner, attributes(sundial_role))]
 pub fn check_sundial_owner(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
@@ -191,9 +305,9 @@ pub fn check_sundial_owner(input: TokenStream) -> TokenStream {
         quote! {}
     };
 
+    let check_authority = authority_check(&ast);
     let check_owner = quote! {
-        vipers::assert_keys_eq!(self.sundial_market.owner, *self.owner.key,
-            SundialError::InvalidOwner, "Invalid Sundial Market Owner");
+        #check_authority
         if !self.owner.is_signer {
             msg!("Owner didn't sign");
             return Err(SundialError::OwnerNotSigned.into());
@@ -29,12 +29,19 @@ import {
 } from '@port.finance/port-sdk';
 import { expectTX } from '@saberhq/chai-solana';
 import { TransactionEnvelope } from '@saberhq/solana-contrib';
+import { getATAAddress, getOrCreateATA } from '@saberhq/token-utils';
+import {
+  DEFAULT_ORACLE_CONFIG,
+  MARKET_STATUS_ACTIVE,
+  MARKET_STATUS_PAUSED,
+} from '../src';
 describe('sundial', () => {
   setProvider(Provider.local());
   const provider = Provider.local();
 
   const sdk = makeSDK();
   const sundialWrapper = sdk.sundialWrapper;
+  const laterSundialWrapper = sdk.sundialWrapper;
   let lendingMarketKP: Keypair;
   let reserveState: ReserveState;
   let liquidityMint: PublicKey;
@@ -70,7 +77,7 @@ describe('sundial', () => {
   const FEE_IN_BIPS = 10;
   const sundialName = 'USDC';
   it('Initialize Sundial', async () => {
-    const duration = new BN(3); // 3 seconds from now
+    const duration = new BN(5); // 5 seconds from now
     const createTx = await sundialWrapper.createSundial({
       sundialName,
       owner: provider.wallet.publicKey,
@@ -100,6 +107,180 @@ describe('sundial', () => {
     );
   });
 
+  it('Initialize a later Sundial on the same reserve', async () => {
+    const createTx = await laterSundialWrapper.createSundial({
+      sundialName: `${sundialName}-later`,
+      owner: provider.wallet.publicKey,
+      durationInSeconds: new BN(60 * 60 * 24),
+      liquidityMint: liquidityMint,
+      reserve: parsedReserve,
+      sundialMarket: sundialMarketBase.publicKey,
+      oracle: PublicKey.default,
+      lendingFeeInBips: FEE_IN_BIPS,
+    });
+    await expectTX(createTx, 'Create later sundial').to.be.fulfilled;
+    await laterSundialWrapper.reloadData();
+  });
+
+  it('Lists both Sundials in the market registry', async () => {
+    const entries = await sdk.fetchSundialMarketRegistry(
+      sundialMarketBase.publicKey,
+    );
+    expect(entries.length).equal(2);
+    expect(entries[0].account).eqAddress(sundialWrapper.publicKey);
+    expect(entries[1].account).eqAddress(laterSundialWrapper.publicKey);
+    expect(entries[1].reserve).eqAddress(parsedReserve.pubkey);
+    expect(entries[1].maturityUnixTimestamp.toString()).equal(
+      laterSundialWrapper.sundialData.endUnixTimeStamp.toString(),
+    );
+  });
+
+  it('Unable to register a Sundial twice', async () => {
+    expect(sundialWrapper.sundialData.registered).to.be.true;
+    await expectTX(
+      await sundialWrapper.registerExisting(),
+      'register an already listed sundial',
+    ).to.be.rejected;
+    const entries = await sdk.fetchSundialMarketRegistry(
+      sundialMarketBase.publicKey,
+    );
+    expect(entries.length).equal(2);
+  });
+
+  it('Unable to mint from a paused Sundial', async () => {
+    const pauseTx = await laterSundialWrapper.changeStatus(
+      MARKET_STATUS_PAUSED,
+    );
+    await expectTX(pauseTx, 'pause later sundial').to.be.fulfilled;
+    const entries = await sdk.fetchSundialMarketRegistry(
+      sundialMarketBase.publicKey,
+    );
+    expect(entries[1].status).to.deep.equal(MARKET_STATUS_PAUSED);
+
+    const depositTx = await laterSundialWrapper.mintPrincipleAndYieldTokens({
+      amount: new BN(1_000),
+      userLiquidityWallet: liquidityVault,
+      reserve: parsedReserve,
+    });
+    await expectTX(depositTx, 'mint from paused sundial').to.be.rejected;
+
+    const activateTx = await laterSundialWrapper.changeStatus(
+      MARKET_STATUS_ACTIVE,
+    );
+    await expectTX(activateTx, 'reactivate later sundial').to.be.fulfilled;
+    await laterSundialWrapper.reloadData();
+  });
+
+  it('Transfer the market ownership in two steps', async () => {
+    const newOwner = Keypair.generate();
+    const market = sundialMarketBase.publicKey;
+    await expectTX(
+      sdk.proposeMarketOwner(market, newOwner.publicKey),
+      'propose new owner',
+    ).to.be.fulfilled;
+    await expectTX(sdk.acceptMarketOwner(market), 'accept by non proposed').to
+      .be.rejected;
+    await expectTX(
+      sdk.acceptMarketOwner(market, newOwner.publicKey).addSigners(newOwner),
+      'accept by proposed owner',
+    ).to.be.fulfilled;
+    const marketData =
+      await sdk.programs.Sundial.account.sundialMarket.fetch(market);
+    expect(marketData.owner).eqAddress(newOwner.publicKey);
+    expect(marketData.pendingOwner).eqAddress(PublicKey.default);
+
+    await expectTX(
+      sdk
+        .proposeMarketOwner(
+          market,
+          provider.wallet.publicKey,
+          newOwner.publicKey,
+        )
+        .addSigners(newOwner),
+      'propose the original owner',
+    ).to.be.fulfilled;
+    await expectTX(sdk.acceptMarketOwner(market), 'accept by original owner')
+      .to.be.fulfilled;
+  });
+
+  it('Pause guardian can change the Sundial status', async () => {
+    const guardian = Keypair.generate();
+    const pauseTx = await laterSundialWrapper.changeStatus(
+      MARKET_STATUS_PAUSED,
+      guardian.publicKey,
+    );
+    await expectTX(pauseTx.addSigners(guardian), 'pause before role assigned')
+      .to.be.rejected;
+
+    await expectTX(
+      sdk.changeSundialMarketRoles(sundialMarketBase.publicKey, {
+        riskAdmin: PublicKey.default,
+        feeAdmin: PublicKey.default,
+        pauseGuardian: guardian.publicKey,
+        listingAdmin: PublicKey.default,
+      }),
+      'assign pause guardian',
+    ).to.be.fulfilled;
+    await expectTX(pauseTx, 'pause by guardian').to.be.fulfilled;
+
+    const activateTx = await laterSundialWrapper.changeStatus(
+      MARKET_STATUS_ACTIVE,
+      guardian.publicKey,
+    );
+    await expectTX(activateTx.addSigners(guardian), 'reactivate by guardian')
+      .to.be.fulfilled;
+  });
+
+  it('Change the oracle config of a Sundial', async () => {
+    await expectTX(
+      laterSundialWrapper.changeOracleConfig({
+        ...DEFAULT_ORACLE_CONFIG,
+        maxConfidenceBips: 10_001,
+      }),
+      'confidence over the price',
+    ).to.be.rejected;
+    await expectTX(
+      laterSundialWrapper.changeOracleConfig({
+        ...DEFAULT_ORACLE_CONFIG,
+        maxConfidenceBips: 200,
+        useUpperBoundPrice: true,
+      }),
+      'change oracle config',
+    ).to.be.fulfilled;
+    await laterSundialWrapper.reloadData();
+    const oracleConfig = laterSundialWrapper.sundialData.oracleConfig;
+    expect(oracleConfig.maxConfidenceBips).to.equal(200);
+    expect(oracleConfig.useUpperBoundPrice).to.be.true;
+  });
+
+  it('Migrating accounts at the latest version is a no-op', async () => {
+    const before = await provider.connection.getAccountInfo(
+      laterSundialWrapper.publicKey,
+    );
+    await expectTX(
+      sdk.migrateAccount(laterSundialWrapper.publicKey),
+      'migrate account',
+    ).to.be.fulfilled;
+    const after = await provider.connection.getAccountInfo(
+      laterSundialWrapper.publicKey,
+    );
+    expect(after.data.equals(before.data)).to.be.true;
+    await laterSundialWrapper.reloadData();
+    expect(laterSundialWrapper.sundialData.version).to.equal(1);
+
+    const marketBefore = await provider.connection.getAccountInfo(
+      sundialMarketBase.publicKey,
+    );
+    await expectTX(
+      sdk.migrateAccount(sundialMarketBase.publicKey),
+      'migrate a Sundial Market',
+    ).to.be.fulfilled;
+    const marketAfter = await provider.connection.getAccountInfo(
+      sundialMarketBase.publicKey,
+    );
+    expect(marketAfter.data.equals(marketBefore.data)).to.be.true;
+  });
+
   const amount = INITIAL_MINT_AMOUNT.sub(RESERVE_INIT_LIQUIDITY);
   const fee = amount.muln(FEE_IN_BIPS).divn(10_000).addn(1); //Since fee calculation is rounding up, so add one here
   it('Mints principle and yield tokens', async () => {
@@ -152,6 +333,28 @@ describe('sundial', () => {
     expect(liquidityWallet.amount.toString()).equal('1');
     expect(sundialLendingFeeAmount.toString()).equal(fee.toString());
   });
+  const RECOMBINE_AMOUNT = new BN(1_000);
+  it('Recombine principle and yield tokens before end date', async () => {
+    const recombineTx = await sundialWrapper.recombineTokens({
+      amount: RECOMBINE_AMOUNT,
+      reserve: parsedReserve,
+      userLiquidityWallet: liquidityVault,
+    });
+    await expectTX(recombineTx, 'recombine principle and yield').to.be
+      .fulfilled;
+
+    const sundialLpWallet = (await sundialWrapper.getLPTokenSupplyAndBump())[0];
+    const sundialLpAmount = (await getTokenAccount(provider, sundialLpWallet))
+      .amount;
+    const liquidityWallet = await getTokenAccount(provider, liquidityVault);
+    expect(sundialLpAmount.toString()).equal(
+      amount.sub(RECOMBINE_AMOUNT).toString(),
+    );
+    // The reserve has no borrows, so one Port LP is still worth one liquidity.
+    expect(liquidityWallet.amount.toString()).equal(
+      RECOMBINE_AMOUNT.addn(1).toString(),
+    );
+  });
   it('Unable to redeem Port Lp before end date', async () => {
     const redeemTx = await sundialWrapper.redeemPortLp({
       lendingMarket: lendingMarketKP.publicKey,
@@ -175,7 +378,7 @@ describe('sundial', () => {
   });
 
   it('sleep and transfer some fund to liquidity wallet', async () => {
-    await sleep(4000);
+    await sleep(6000);
     const sundialLiquidityWalletPubkey = (
       await sundialWrapper.getLiquidityTokenSupplyAndBump()
     )[0];
@@ -207,7 +410,57 @@ describe('sundial', () => {
     await expectTX(tx, 'redeem yield failed').to.be.rejected;
   });
 
-  it('Redeem Port Lp', async () => {
+  const HALF_LP_AMOUNT = amount.sub(RECOMBINE_AMOUNT).divn(2);
+  it('Redeem half of the Port Lp', async () => {
+    const redeemTx = await sundialWrapper.redeemPortLp({
+      lendingMarket: lendingMarketKP.publicKey,
+      reserve: parsedReserve,
+      amount: HALF_LP_AMOUNT,
+    });
+    await expectTX(redeemTx, 'redeem half from Port successful').to.be
+      .fulfilled;
+    await sundialWrapper.reloadData();
+    expect(sundialWrapper.sundialData.redeemedLpAmount.toString()).equal(
+      HALF_LP_AMOUNT.toString(),
+    );
+  });
+
+  const PARTIAL_REDEEM_AMOUNT = new BN(1_000_000);
+  it('Unable to redeem more principal tokens than the redeemed share', async () => {
+    const tx = await sundialWrapper.redeemPrincipleTokens({
+      amount: amount.sub(fee).sub(RECOMBINE_AMOUNT),
+      userLiquidityWallet: liquidityVault,
+    });
+    await expectTX(tx, 'redeem principle failed').to.be.rejected;
+  });
+
+  it('Redeem principal tokens from the redeemed share', async () => {
+    const tx = await sundialWrapper.redeemPrincipleTokens({
+      amount: PARTIAL_REDEEM_AMOUNT,
+      userLiquidityWallet: liquidityVault,
+    });
+    await expectTX(tx, 'redeem part of principal tokens').to.be.fulfilled;
+  });
+
+  const ROLLOVER_AMOUNT = new BN(1_000_000);
+  it('Roll principal tokens over into the later Sundial', async () => {
+    const tx = await sundialWrapper.rolloverPrincipal({
+      amount: ROLLOVER_AMOUNT,
+      newSundial: laterSundialWrapper,
+      reserve: parsedReserve,
+    });
+    await expectTX(tx, 'rollover principal tokens').to.be.fulfilled;
+    const newPrincipleWallet = await getTokenAccount(
+      provider,
+      await laterSundialWrapper.getUserPrincipleWallet(),
+    );
+    const rolloverFee = ROLLOVER_AMOUNT.muln(FEE_IN_BIPS).divn(10_000);
+    expect(newPrincipleWallet.amount.toString()).equal(
+      ROLLOVER_AMOUNT.sub(rolloverFee).toString(),
+    );
+  });
+
+  it('Redeem the rest of the Port Lp', async () => {
     const sundialLiquidityWalletPubkey = (
       await sundialWrapper.getLiquidityTokenSupplyAndBump()
     )[0];
@@ -224,7 +477,11 @@ describe('sundial', () => {
   });
 
   it('Redeem principal tokens', async () => {
-    const redeemAmount = amount.sub(fee);
+    const redeemAmount = amount
+      .sub(fee)
+      .sub(RECOMBINE_AMOUNT)
+      .sub(PARTIAL_REDEEM_AMOUNT)
+      .sub(ROLLOVER_AMOUNT);
     const tx = await sundialWrapper.redeemPrincipleTokens({
       amount: redeemAmount,
       userLiquidityWallet: liquidityVault,
@@ -232,18 +489,106 @@ describe('sundial', () => {
     await expectTX(tx, 'redeem principal tokens successfully').to.be.fulfilled;
     const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
     expect(userLiquidityWallet.amount.toString()).equal(
-      amount.sub(fee).toString(),
+      amount.sub(fee).sub(ROLLOVER_AMOUNT).toString(),
     );
   });
   it('Redeem yield token', async () => {
     const redeemTx = await sundialWrapper.redeemYieldTokens({
-      amount,
+      amount: amount.sub(RECOMBINE_AMOUNT),
       userLiquidityWallet: liquidityVault,
     });
     await expectTX(redeemTx, 'redeem yield token').to.be.fulfilled;
     const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
     expect(userLiquidityWallet.amount.toString()).equal(
-      amount.sub(fee).toString(),
+      amount.sub(fee).sub(ROLLOVER_AMOUNT).toString(),
+    );
+  });
+
+  it('Unable to close the Sundial with fees left', async () => {
+    const { address: treasuryLpWallet, instruction: createATAIx } =
+      await getOrCreateATA({
+        provider: sdk.provider,
+        mint: parsedReserve.data.collateral.mintPubkey,
+      });
+    if (createATAIx) {
+      await expectTX(
+        new TransactionEnvelope(sdk.provider, [createATAIx]),
+        'create treasury lp wallet',
+      ).to.be.fulfilled;
+    }
+    const tx = await sundialWrapper.closeSundial({
+      treasuryLiquidityWallet: liquidityVault,
+      treasuryLpWallet,
+    });
+    await expectTX(tx, 'close sundial with fees left').to.be.rejected;
+  });
+
+  it('Withdraw fees as liquidity after end date', async () => {
+    const sundialLendingFeeWallet = (
+      await sundialWrapper.getFeeReceiverAndBump()
+    )[0];
+    const tx = await sundialWrapper.withdrawSundialFees({
+      treasuryWallet: liquidityVault,
+    });
+    await expectTX(tx, 'withdraw sundial fees').to.be.fulfilled;
+    const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
+    const sundialLendingFeeAmount = (
+      await getTokenAccount(provider, sundialLendingFeeWallet)
+    ).amount;
+    expect(userLiquidityWallet.amount.toString()).equal(
+      amount.sub(ROLLOVER_AMOUNT).toString(),
+    );
+    expect(sundialLendingFeeAmount.toString()).equal('0');
+  });
+
+  it('Close the fully redeemed Sundial', async () => {
+    const sundialFeeWallet = (await sundialWrapper.getFeeReceiverAndBump())[0];
+    const sundialLiquidityWalletPubkey = (
+      await sundialWrapper.getLiquidityTokenSupplyAndBump()
+    )[0];
+    const registryEntry = await sdk.getRegistryEntryKey(
+      sundialWrapper.sundialData.sundialMarket,
+      sundialWrapper.publicKey,
     );
+    const DUST_AMOUNT = 3;
+    const dustTx = new TransactionEnvelope(sdk.provider, [
+      Token.createTransferInstruction(
+        TOKEN_PROGRAM_ID,
+        liquidityVault,
+        sundialLiquidityWalletPubkey,
+        provider.wallet.publicKey,
+        [],
+        DUST_AMOUNT,
+      ),
+    ]);
+    await expectTX(dustTx, 'leave dust in the liquidity wallet').to.be
+      .fulfilled;
+    const treasuryLiquidityBefore = (
+      await getTokenAccount(provider, liquidityVault)
+    ).amount;
+
+    const tx = await sundialWrapper.closeSundial({
+      treasuryLiquidityWallet: liquidityVault,
+      treasuryLpWallet: await getATAAddress({
+        mint: parsedReserve.data.collateral.mintPubkey,
+        owner: provider.wallet.publicKey,
+      }),
+    });
+    await expectTX(tx, 'close sundial').to.be.fulfilled;
+    const treasuryLiquidityAfter = (
+      await getTokenAccount(provider, liquidityVault)
+    ).amount;
+    expect(
+      treasuryLiquidityAfter.sub(treasuryLiquidityBefore).toString(),
+    ).equal(DUST_AMOUNT.toString());
+    expect(
+      await provider.connection.getAccountInfo(sundialWrapper.publicKey),
+    ).to.be.null;
+    expect(await provider.connection.getAccountInfo(sundialFeeWallet)).to.be
+      .null;
+    expect(
+      await provider.connection.getAccountInfo(sundialLiquidityWalletPubkey),
+    ).to.be.null;
+    expect(await provider.connection.getAccountInfo(registryEntry)).to.be.null;
   });
 });
@@ -0,0 +1,207 @@
+import { Provider, setProvider, BN } from '@project-serum/anchor';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { DEFAULT_RESERVE_CONFIG } from './constants';
+import { createMintAndVault, getTokenAccount } from '@project-serum/common';
+import { expect } from 'chai';
+import { createDefaultReserve, createLendingMarket } from './utils';
+import {
+  INITIAL_MINT_AMOUNT,
+  RESERVE_INIT_LIQUIDITY,
+  makeSDK,
+} from './workspace';
+import {
+  ReserveParser,
+  ParsedAccount,
+  ReserveData,
+} from '@port.finance/port-sdk';
+import { expectTX } from '@saberhq/chai-solana';
+import {
+  SWAP_LIQUIDITY_TO_PRINCIPLE,
+  SWAP_PRINCIPLE_TO_LIQUIDITY,
+} from '../src';
+
+describe('sundial amm', () => {
+  setProvider(Provider.local());
+  const provider = Provider.local();
+
+  const sdk = makeSDK();
+  const sundialWrapper = sdk.sundialWrapper;
+  const sundialAmmWrapper = sdk.sundialAmmWrapper;
+  let liquidityMint: PublicKey;
+  let liquidityVault: PublicKey;
+  let userPrincipleWallet: PublicKey;
+  let parsedReserve: ParsedAccount<ReserveData>;
+  let sundialMarketBase: Keypair;
+
+  const MINT_AMOUNT = new BN(500_000_000);
+  before('Initialize Sundial and mint principal tokens', async () => {
+    const lendingMarketKP = await createLendingMarket(provider);
+    const [mintPubkey, vaultPubkey] = await createMintAndVault(
+      provider,
+      INITIAL_MINT_AMOUNT,
+    );
+    liquidityMint = mintPubkey;
+    liquidityVault = vaultPubkey;
+    const reserveState = await createDefaultReserve(
+      provider,
+      RESERVE_INIT_LIQUIDITY,
+      vaultPubkey,
+      lendingMarketKP.publicKey,
+      DEFAULT_RESERVE_CONFIG,
+    );
+    parsedReserve = ReserveParser({
+      pubkey: reserveState.address,
+      account: await provider.connection.getAccountInfo(reserveState.address),
+    });
+    sundialMarketBase = await sdk.createSundialMarket();
+
+    const createTx = await sundialWrapper.createSundial({
+      sundialName: 'USDC',
+      owner: provider.wallet.publicKey,
+      durationInSeconds: new BN(1_000),
+      liquidityMint,
+      reserve: parsedReserve,
+      sundialMarket: sundialMarketBase.publicKey,
+      oracle: PublicKey.default,
+    });
+    await expectTX(createTx, 'Create sundial').to.be.fulfilled;
+    await sundialWrapper.reloadData();
+
+    const mintTx = await sundialWrapper.mintPrincipleAndYieldTokens({
+      amount: MINT_AMOUNT,
+      userLiquidityWallet: liquidityVault,
+      reserve: parsedReserve,
+    });
+    await expectTX(mintTx, 'mint principle and yield').to.be.fulfilled;
+    userPrincipleWallet = await sundialWrapper.getUserPrincipleWallet();
+  });
+
+  // One year in the curve's time units makes the implied apy (x/y)^0.1 - 1.
+  const TIME_SCALE = new BN(315_360_000);
+  it('Initialize Sundial Amm', async () => {
+    const createTx = await sundialAmmWrapper.createSundialAmm({
+      sundial: sundialWrapper.publicKey,
+      sundialMarket: sundialMarketBase.publicKey,
+      liquidityMint,
+      principleTokenMint: (await sundialWrapper.getPrincipleMintAndBump())[0],
+      sundialPortLiquidityWallet: (
+        await sundialWrapper.getLiquidityTokenSupplyAndBump()
+      )[0],
+      timeScaleInSeconds: TIME_SCALE,
+    });
+    await expectTX(createTx, 'Create sundial amm').to.be.fulfilled;
+    await sundialAmmWrapper.reloadData();
+    expect(sundialAmmWrapper.sundialAmmData.sundial).eqAddress(
+      sundialWrapper.publicKey,
+    );
+    expect(
+      sundialAmmWrapper.sundialAmmData.config.timeScaleInSeconds.toString(),
+    ).equal(TIME_SCALE.toString());
+  });
+
+  const LIQUIDITY_AMOUNT = new BN(100_000_000);
+  const PRINCIPLE_AMOUNT = new BN(110_000_000);
+  it('Add initial liquidity', async () => {
+    const addTx = await sundialAmmWrapper.addLiquidity({
+      liquidityAmount: LIQUIDITY_AMOUNT,
+      maxPrincipleAmount: PRINCIPLE_AMOUNT,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(addTx, 'add amm liquidity').to.be.fulfilled;
+    await sundialAmmWrapper.reloadData();
+
+    const ammPrincipleWallet = await getTokenAccount(
+      provider,
+      (
+        await sundialAmmWrapper.getPrincipleWalletAndBump()
+      )[0],
+    );
+    expect(ammPrincipleWallet.amount.toString()).equal(
+      PRINCIPLE_AMOUNT.toString(),
+    );
+    // 1.1^0.1 - 1
+    expect(sundialAmmWrapper.getImpliedApyInPercent()).equal(0.95);
+  });
+
+  const SWAP_AMOUNT = new BN(1_000_000);
+  it('Buy principal tokens below par', async () => {
+    const principleBefore = (
+      await getTokenAccount(provider, userPrincipleWallet)
+    ).amount;
+    const swapTx = await sundialAmmWrapper.swap({
+      amountIn: SWAP_AMOUNT,
+      minAmountOut: SWAP_AMOUNT,
+      direction: SWAP_LIQUIDITY_TO_PRINCIPLE,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(swapTx, 'buy principle').to.be.fulfilled;
+    const principleAfter = (await getTokenAccount(provider, userPrincipleWallet))
+      .amount;
+    expect(principleAfter.sub(principleBefore).gt(SWAP_AMOUNT)).to.be.true;
+
+    const apyBefore = sundialAmmWrapper.getImpliedApy();
+    await sundialAmmWrapper.reloadData();
+    expect(sundialAmmWrapper.getImpliedApy().lt(apyBefore)).to.be.true;
+  });
+
+  it('Sell principal tokens below par', async () => {
+    const liquidityBefore = (await getTokenAccount(provider, liquidityVault))
+      .amount;
+    const swapTx = await sundialAmmWrapper.swap({
+      amountIn: SWAP_AMOUNT,
+      direction: SWAP_PRINCIPLE_TO_LIQUIDITY,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(swapTx, 'sell principle').to.be.fulfilled;
+    const liquidityAfter = (await getTokenAccount(provider, liquidityVault))
+      .amount;
+    expect(liquidityAfter.sub(liquidityBefore).lt(SWAP_AMOUNT)).to.be.true;
+  });
+
+  it('Unable to swap with too little output', async () => {
+    const swapTx = await sundialAmmWrapper.swap({
+      amountIn: SWAP_AMOUNT,
+      minAmountOut: SWAP_AMOUNT,
+      direction: SWAP_PRINCIPLE_TO_LIQUIDITY,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(swapTx, 'slippage exceeded').to.be.rejected;
+  });
+
+  it('Unable to buy principal tokens above par', async () => {
+    const swapTx = await sundialAmmWrapper.swap({
+      amountIn: new BN(10_000_000),
+      direction: SWAP_LIQUIDITY_TO_PRINCIPLE,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(swapTx, 'negative rate').to.be.rejected;
+  });
+
+  it('Remove all liquidity', async () => {
+    const [ammLpMint] = await sundialAmmWrapper.getLpMintAndBump();
+    const removeTx = await sundialAmmWrapper.removeLiquidity({
+      lpAmount: LIQUIDITY_AMOUNT,
+      userLiquidityWallet: liquidityVault,
+      userPrincipleTokenWallet: userPrincipleWallet,
+    });
+    await expectTX(removeTx, 'remove amm liquidity').to.be.fulfilled;
+    await sundialAmmWrapper.reloadData();
+
+    const lpSupply = (await provider.connection.getTokenSupply(ammLpMint))
+      .value.amount;
+    const ammLiquidityWallet = await getTokenAccount(
+      provider,
+      (
+        await sundialAmmWrapper.getLiquidityTokenSupplyAndBump()
+      )[0],
+    );
+    expect(lpSupply).equal('0');
+    expect(ammLiquidityWallet.amount.toString()).equal('0');
+    expect(sundialAmmWrapper.getImpliedApy().toString()).equal('0');
+  });
+});
@@ -1,6 +1,12 @@
 import { describe } from 'mocha';
 import { MockOraclesWrapper } from '@port.finance/mock-oracles';
-import { Keypair, PublicKey, Transaction } from '@solana/web3.js';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from '@solana/web3.js';
 import { BN, Provider, setProvider } from '@project-serum/anchor';
 import {
   INITIAL_MINT_AMOUNT,
@@ -35,6 +41,7 @@ import {
   createAccountRentExempt,
   createMintAndVault,
   createTokenAccount,
+  getTokenAccount,
   sleep,
 } from '@project-serum/common';
 import {
@@ -54,12 +61,23 @@ import { getATAAddress, getOrCreateATA, MAX_U64 } from '@saberhq/token-utils';
 import { TransactionEnvelope } from '@saberhq/solana-contrib';
 import { Big } from 'big.js';
 import {
+  assetValue,
   Buffer2BN,
+  DEFAULT_ORACLE_CONFIG,
+  MARKET_STATUS_ACTIVE,
+  MARKET_STATUS_REDUCE_ONLY,
+  MarketStatus,
+  ORACLE_POLICY_MEDIAN,
+  ORACLE_POLICY_PRIMARY_WITH_FALLBACK,
+  ORACLE_SOURCE_PYTH,
+  OraclePolicy,
   SundialCollateralConfig,
   SundialCollateralWrapper,
   SundialProfileCollateral,
   SundialProfileLoan,
+  SundialProfileWrapper,
   SundialWrapper,
+  tokenValue,
   WAD,
 } from '../src';
 import invariant from 'tiny-invariant';
@@ -561,6 +579,38 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Resize Sundial Profile', async () => {
+    const { collateralCapacity, loanCapacity } =
+      sundialProfileWrapper.sundialProfileData;
+    await expectTX(
+      await sundialProfileWrapper.resizeSundialProfile(
+        collateralCapacity - 1,
+        loanCapacity,
+      ),
+      'Shrink sundial profile',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.resizeSundialProfile(
+        collateralCapacity + 1,
+        loanCapacity + 2,
+      ),
+      'Resize sundial profile',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    const profileData = sundialProfileWrapper.sundialProfileData;
+    expect(profileData.collateralCapacity).eq(collateralCapacity + 1);
+    expect(profileData.loanCapacity).eq(loanCapacity + 2);
+    const accountInfo = await provider.connection.getAccountInfo(
+      sundialProfileWrapper.publicKey,
+    );
+    expect(accountInfo?.data.length).eq(
+      8 + 208 + (collateralCapacity + 1) * 92 + (loanCapacity + 2) * 106,
+    );
+    expect(sundialProfileWrapper.collaterals).deep.equal([]);
+    expect(sundialProfileWrapper.loans).deep.equal([]);
+  });
+
   it('Refresh Sundial Collateral Fail if reserve is not fresh', async () => {
     const refreshSundialCollateralTx =
       await sundialSerumCollateralWrapper.refreshSundialCollateral(
@@ -633,9 +683,23 @@ describe('SundialCollateral', () => {
       ]) => {
         invariant(collateral, 'Sundial Profile Collateral Not Exist');
         expect(
-          Buffer2BN(collateral.asset.totalValue),
+          Buffer2BN(collateral.asset.price),
+          `Check collateral price in profile collateral, ${msg}`,
+        ).to.bignumber.eq(price);
+        expect(
+          assetValue(collateral.asset),
           `Check collateral value in profile collateral, ${msg}`,
-        ).to.bignumber.eq(price.mul(collateral.asset.amount));
+        ).to.bignumber.eq(
+          tokenValue(
+            price,
+            collateral.asset.amount,
+            config.collateralDecimals,
+          ),
+        );
+        expect(
+          Buffer2BN(collateral.borrowingPrice),
+          `Check borrowing price in profile collateral, ${msg}`,
+        ).to.bignumber.lte(price);
         expect(
           collateral.sundialCollateral,
           `Check collateral pubkey in profile collateral, ${msg}`,
@@ -656,8 +720,7 @@ describe('SundialCollateral', () => {
     );
   const checkSundialProfileNumOfCollateralDiff = (diff: number) =>
     checkBNDiff(
-      async () =>
-        new BN(sundialProfileWrapper.sundialProfileData.collaterals.length),
+      async () => new BN(sundialProfileWrapper.collaterals.length),
       new BN(diff),
     );
 
@@ -693,7 +756,7 @@ describe('SundialCollateral', () => {
       ),
       checkSundialProfileNumOfCollateralDiff(1),
       checkBefore(
-        async () => sundialProfileWrapper.sundialProfileData.collaterals,
+        async () => sundialProfileWrapper.collaterals,
         async collaterals => {
           expect(collaterals).deep.equal([]);
         },
@@ -755,8 +818,12 @@ describe('SundialCollateral', () => {
         const reserveInfo = await port.getReserve(reserveState.address);
         const price = reserveInfo.getMarkPrice().getRaw();
         const exchangeRate = reserveInfo.getExchangeRatio();
+        const decimals =
+          sundialCollateral.sundialCollateralData.sundialCollateralConfig
+            .collateralDecimals;
         const expectedCollateralPrice = price
           .mul(WAD.toString())
+          .mul(new Big(10).pow(decimals))
           .div(exchangeRate.getUnchecked());
         return [collateralPrice, expectedCollateralPrice];
       },
@@ -795,6 +862,100 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Refresh SundialCollateral checked against an independent oracle', async () => {
+    const checkOracleKP = await mockOraclesWrapper.createAccount(
+      mockOraclesWrapper.PYTH_PRICE_ACCOUNT_SIZE,
+    );
+    const refresh = async (checkPrice: BN) => {
+      await mockOraclesWrapper.writePythPrice(checkOracleKP, {
+        price: checkPrice,
+        slot: new BN(await provider.connection.getSlot()),
+      });
+      await mockOraclesWrapper.writePythPrice(serumOracleKP, {
+        slot: new BN(await provider.connection.getSlot()),
+      });
+      await expectTX(
+        await sundialSerumCollateralWrapper.refreshSundialCollateral(
+          parsedSerumReserve,
+        ),
+        'RefreshSundialCollateral',
+      ).to.be.fulfilled;
+      await sundialSerumCollateralWrapper.reloadData();
+      return sundialSerumCollateralWrapper.sundialCollateralData;
+    };
+
+    await expectTX(
+      sundialSerumCollateralWrapper.changePriceCheck({
+        oracle: checkOracleKP.publicKey,
+        source: ORACLE_SOURCE_PYTH,
+        maxDeviationBips: 0,
+      }),
+      'price check without a band',
+    ).to.be.rejected;
+    await expectTX(
+      sundialSerumCollateralWrapper.changePriceCheck({
+        oracle: checkOracleKP.publicKey,
+        source: ORACLE_SOURCE_PYTH,
+        maxDeviationBips: 500,
+      }),
+      'turn the price check on',
+    ).to.be.fulfilled;
+    await sundialSerumCollateralWrapper.reloadData();
+
+    const agreed = await refresh(SERUM_PRICE);
+    expect(agreed.priceCheck.lastDeviationBips.toNumber()).to.equal(0);
+    expect(agreed.lastUpdatedSlot.slot).to.bignumber.eq(
+      agreed.priceCheck.lastCheckedSlot,
+    );
+
+    const deviated = await refresh(SERUM_PRICE.muln(2));
+    expect(deviated.priceCheck.lastDeviationBips.toNumber()).to.equal(10000);
+    expect(deviated.lastUpdatedSlot.slot).to.bignumber.eq(
+      agreed.lastUpdatedSlot.slot,
+    );
+
+    await expectTX(
+      sundialSerumCollateralWrapper.changePriceCheck({
+        oracle: PublicKey.default,
+        source: ORACLE_SOURCE_PYTH,
+        maxDeviationBips: 0,
+      }),
+      'turn the price check off',
+    ).to.be.fulfilled;
+    await sundialSerumCollateralWrapper.reloadData();
+  });
+
+  it('Ema price lags behind a collateral price spike', async () => {
+    const refresh = async (price: BN) => {
+      await mockOraclesWrapper.writePythPrice(serumOracleKP, {
+        price,
+        slot: new BN(await provider.connection.getSlot()),
+      });
+      await expectTX(
+        await sundialSerumCollateralWrapper.refreshSundialCollateral(
+          parsedSerumReserve,
+        ),
+        'RefreshSundialCollateral',
+      ).to.be.fulfilled;
+      await sundialSerumCollateralWrapper.reloadData();
+      return sundialSerumCollateralWrapper.sundialCollateralData;
+    };
+
+    const before = await refresh(SERUM_PRICE);
+    const spiked = await refresh(SERUM_PRICE.muln(10));
+    expect(Buffer2BN(spiked.collateralPrice)).to.bignumber.gt(
+      Buffer2BN(before.collateralPrice).muln(9),
+    );
+    expect(Buffer2BN(spiked.emaPrice)).to.bignumber.lt(
+      Buffer2BN(spiked.collateralPrice).divn(2),
+    );
+
+    const recovered = await refresh(SERUM_PRICE);
+    expect(Buffer2BN(recovered.emaPrice)).to.bignumber.gte(
+      Buffer2BN(recovered.collateralPrice),
+    );
+  });
+
   it('Mint No Refresh', async () => {
     await expectTX(
       await sundialProfileWrapper.mintSundialLiquidityWithCollateral(
@@ -867,10 +1028,14 @@ describe('SundialCollateral', () => {
         const price = await getPythPrice(provider, oracle);
 
         expect(
-          Buffer2BN(loan.asset.totalValue),
+          assetValue(loan.asset),
           `Check asset value in profile loan ${msg}`,
         ).to.bignumber.eq(
-          new BN(price.mul(WAD.toString()).toString()).mul(loan.asset.amount),
+          tokenValue(
+            new BN(price.mul(WAD.toString()).toString()),
+            loan.asset.amount,
+            loan.liquidityDecimals,
+          ),
         );
         expect(loan.sundial, `Check sundial in profile loan ${msg}`).eqAddress(
           sundialUSDCWrapper.publicKey,
@@ -887,7 +1052,7 @@ describe('SundialCollateral', () => {
 
   const checkSundialProfileNumOfLoanDiff = (diff: number) =>
     checkBNDiff(
-      async () => new BN(sundialProfileWrapper.sundialProfileData.loans.length),
+      async () => new BN(sundialProfileWrapper.loans.length),
       new BN(diff),
     );
 
@@ -911,7 +1076,7 @@ describe('SundialCollateral', () => {
         await sundialProfileWrapper.reloadData();
       },
       checkBefore(
-        async () => sundialProfileWrapper.sundialProfileData.loans,
+        async () => sundialProfileWrapper.loans,
         async beforeLoanList => {
           expect(beforeLoanList).deep.equal([]);
         },
@@ -952,6 +1117,85 @@ describe('SundialCollateral', () => {
       'RefreshSundialProfile',
     ).to.be.fulfilled;
     await sundialProfileWrapper.reloadData();
+
+    const shortTx = await sundialProfileWrapper.refreshSundialProfile();
+    shortTx.instructions[0].keys.pop();
+    await expectTX(shortTx, 'refresh without the Sundial of the loan').to.be
+      .rejected;
+  });
+
+  it('Refresh SundialProfile with a fallback oracle', async () => {
+    const fallbackOracleKP = await mockOraclesWrapper.createAccount(
+      mockOraclesWrapper.PYTH_PRICE_ACCOUNT_SIZE,
+    );
+    const writeFallbackPrice = async (price: BN) =>
+      await mockOraclesWrapper.writePythPrice(fallbackOracleKP, {
+        price,
+        slot: new BN(await provider.connection.getSlot()),
+      });
+    const changeOracleConfig = async (
+      policy: OraclePolicy,
+      fallbackOracle = fallbackOracleKP.publicKey,
+    ) => {
+      await expectTX(
+        sundialUSDCWrapper.changeOracleConfig({
+          ...DEFAULT_ORACLE_CONFIG,
+          fallbackOracle,
+          policy,
+          maxDeviationBips: 1000,
+        }),
+        'change oracle config',
+      ).to.be.fulfilled;
+      await sundialUSDCWrapper.reloadData();
+    };
+    const refreshTx = async () => {
+      await updateOraclesSlot();
+      await expectTX(
+        await sundialSerumCollateralWrapper.refreshSundialCollateral(
+          parsedSerumReserve,
+        ),
+        'Refresh Sundial Collateral',
+      ).to.be.fulfilled;
+      return await sundialProfileWrapper.refreshSundialProfile();
+    };
+    const loanValue = async () => {
+      await sundialProfileWrapper.reloadData();
+      return assetValue(
+        sundialProfileWrapper.getLoan(sundialUSDCWrapper.publicKey).asset,
+      );
+    };
+    const valueAtUSDCPrice = await loanValue();
+
+    await writeFallbackPrice(USDC_PRICE.muln(2));
+    await changeOracleConfig(ORACLE_POLICY_PRIMARY_WITH_FALLBACK);
+    await expectTX(await refreshTx(), 'prices deviate too much').to.be
+      .rejected;
+
+    let tx = await refreshTx();
+    await mockOraclesWrapper.writePythPrice(usdcOracleKP, {
+      slot: new BN(0),
+    });
+    await expectTX(tx, 'fall back on a stale primary').to.be.fulfilled;
+    expect(await loanValue()).to.bignumber.eq(valueAtUSDCPrice.muln(2));
+
+    await changeOracleConfig(ORACLE_POLICY_MEDIAN);
+    tx = await refreshTx();
+    await mockOraclesWrapper.writePythPrice(usdcOracleKP, {
+      slot: new BN(0),
+    });
+    await expectTX(tx, 'median needs both prices').to.be.rejected;
+
+    await writeFallbackPrice(USDC_PRICE);
+    await expectTX(await refreshTx(), 'median of both prices').to.be.fulfilled;
+    expect(await loanValue()).to.bignumber.eq(valueAtUSDCPrice);
+
+    await changeOracleConfig(
+      ORACLE_POLICY_PRIMARY_WITH_FALLBACK,
+      PublicKey.default,
+    );
+    await expectTX(await refreshTx(), 'refresh without fallback').to.be
+      .fulfilled;
+    await sundialProfileWrapper.reloadData();
   });
 
   it('Mint Sundial pUSDC (Existing loan)', async () => {
@@ -1055,6 +1299,129 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Repay with principal tokens at par', async () => {
+    const repayAmount = sundialProfileWrapper
+      .getLoanAmount(sundialUSDCWrapper.publicKey)
+      .divn(2);
+    await addCheckers(
+      async () => {
+        const repayTx = await sundialProfileWrapper.repaySundialPrinciple(
+          repayAmount,
+          sundialUSDCWrapper,
+        );
+        await expectTX(repayTx).to.be.fulfilled;
+        await sundialProfileWrapper.reloadData();
+      },
+      checkSundialProfileNumOfLoanDiff(0),
+      checkMintAmountDiff(
+        sdk.provider,
+        async () => (await sundialUSDCWrapper.getPrincipleMintAndBump())[0],
+        repayAmount.neg(),
+        'Check burned principal tokens',
+      ),
+      checkTokenBalanceDiff(
+        sdk.provider,
+        () => sundialUSDCWrapper.getUserPrincipleWallet(),
+        repayAmount.neg(),
+        'Check user principal token diff',
+      ),
+      ...sundialProfileLoanStateChecks(repayAmount.neg(), sundialUSDCWrapper),
+    );
+  });
+
+  it('Reduce only Sundial Market only lets positions shrink', async () => {
+    const changeMarketStatus = async (status: MarketStatus) =>
+      await expectTX(
+        sdk.changeSundialMarketStatus(sundialMarketBase.publicKey, status),
+        'change the market status',
+      ).to.be.fulfilled;
+    await changeMarketStatus(MARKET_STATUS_REDUCE_ONLY);
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    await expectTX(
+      await sundialProfileWrapper.depositSundialCollateral(
+        new BN(100),
+        sundialSerumCollateralWrapper,
+      ),
+      'deposit collateral into a reduce only market',
+    ).to.be.rejected;
+    await expectTX(
+      await sundialProfileWrapper.mintSundialLiquidityWithCollateral(
+        new BN(1),
+        sundialUSDCWrapper,
+      ),
+      'borrow from a reduce only market',
+    ).to.be.rejected;
+    await expectTX(
+      await sundialUSDCWrapper.mintPrincipleAndYieldTokens({
+        amount: new BN(100),
+        userLiquidityWallet: usdcVault,
+        reserve: parsedUSDCReserve,
+      }),
+      'lend to a reduce only market',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.repaySundialLiquidity(
+        new BN(1),
+        sundialUSDCWrapper,
+        usdcVault,
+      ),
+      'repay in a reduce only market',
+    ).to.be.fulfilled;
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    await expectTX(
+      await sundialProfileWrapper.withdrawSundialCollateral(
+        new BN(1),
+        sundialSerumCollateralWrapper,
+      ),
+      'withdraw from a reduce only market',
+    ).to.be.fulfilled;
+
+    await changeMarketStatus(MARKET_STATUS_ACTIVE);
+    await sundialProfileWrapper.reloadData();
+    await sundialSerumCollateralWrapper.reloadData();
+  });
+
+  it('Reduce only Sundial Collateral only lets deposits shrink', async () => {
+    await expectTX(
+      await sundialSerumCollateralWrapper.changeStatus(
+        MARKET_STATUS_REDUCE_ONLY,
+      ),
+      'set the collateral reduce only',
+    ).to.be.fulfilled;
+    const entries = await sdk.fetchSundialMarketRegistry(
+      sundialMarketBase.publicKey,
+    );
+    const entry = entries.find(e =>
+      e.account.equals(sundialSerumCollateralWrapper.publicKey),
+    );
+    expect(entry?.status).to.deep.equal(MARKET_STATUS_REDUCE_ONLY);
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    await expectTX(
+      await sundialProfileWrapper.depositSundialCollateral(
+        new BN(100),
+        sundialSerumCollateralWrapper,
+      ),
+      'deposit into a reduce only collateral',
+    ).to.be.rejected;
+    await expectTX(
+      await sundialProfileWrapper.withdrawSundialCollateral(
+        new BN(1),
+        sundialSerumCollateralWrapper,
+      ),
+      'withdraw from a reduce only collateral',
+    ).to.be.fulfilled;
+
+    await expectTX(
+      await sundialSerumCollateralWrapper.changeStatus(MARKET_STATUS_ACTIVE),
+      'reactivate the collateral',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    await sundialSerumCollateralWrapper.reloadData();
+  });
+
   it('Repay USDC (all loan)', async () => {
     const repayAmount = sundialProfileWrapper.getLoanAmount(
       sundialUSDCWrapper.publicKey,
@@ -1102,6 +1469,160 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Rollover USDC loan into a later Sundial', async () => {
+    const laterSundialUSDCWrapper = sdk.sundialWrapper;
+    await updateOraclesSlot();
+    await expectTX(
+      await laterSundialUSDCWrapper.createSundial({
+        sundialName: 'USDC Later',
+        owner: provider.wallet.publicKey,
+        durationInSeconds: new BN(7200),
+        liquidityMint: USDCMint,
+        reserve: parsedUSDCReserve,
+        sundialMarket: sundialMarketBase.publicKey,
+        oracle: usdcOracleKP.publicKey,
+        lendingFeeInBips: FEE_IN_BIPS,
+        borrowingFeeInBips: FEE_IN_BIPS,
+      }),
+      'Create later sundial',
+    ).to.be.fulfilled;
+    await laterSundialUSDCWrapper.reloadData();
+
+    const AMM_LIQUIDITY = new BN(1_000_000);
+    await expectTX(
+      await laterSundialUSDCWrapper.mintPrincipleAndYieldTokens({
+        amount: AMM_LIQUIDITY.muln(2),
+        userLiquidityWallet: usdcVault,
+        reserve: parsedUSDCReserve,
+      }),
+      'mint later principal tokens',
+    ).to.be.fulfilled;
+    const sundialAmmWrapper = sdk.sundialAmmWrapper;
+    await expectTX(
+      await sundialAmmWrapper.createSundialAmm({
+        sundial: laterSundialUSDCWrapper.publicKey,
+        sundialMarket: sundialMarketBase.publicKey,
+        liquidityMint: USDCMint,
+        principleTokenMint: (
+          await laterSundialUSDCWrapper.getPrincipleMintAndBump()
+        )[0],
+        sundialPortLiquidityWallet: (
+          await laterSundialUSDCWrapper.getLiquidityTokenSupplyAndBump()
+        )[0],
+        timeScaleInSeconds: new BN(315_360_000),
+      }),
+      'Create sundial amm',
+    ).to.be.fulfilled;
+    await sundialAmmWrapper.reloadData();
+    await expectTX(
+      await sundialAmmWrapper.addLiquidity({
+        liquidityAmount: AMM_LIQUIDITY,
+        maxPrincipleAmount: AMM_LIQUIDITY.muln(11).divn(10),
+        userLiquidityWallet: usdcVault,
+        userPrincipleTokenWallet:
+          await laterSundialUSDCWrapper.getUserPrincipleWallet(),
+      }),
+      'add amm liquidity',
+    ).to.be.fulfilled;
+
+    const LOAN_AMOUNT = new BN(10_000);
+    await refreshThenMint(LOAN_AMOUNT, sundialUSDCWrapper, [
+      sundialSerumCollateralWrapper,
+      parsedSerumReserve,
+    ]);
+
+    const rollover = async (
+      mintAmount: BN,
+      newSundialWrapper = laterSundialUSDCWrapper,
+      sundialWrapper = sundialUSDCWrapper,
+    ) => {
+      await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+      return await sundialProfileWrapper.rolloverLoan({
+        mintAmount,
+        sundialWrapper,
+        newSundialWrapper,
+        sundialAmmWrapper,
+        userLiquidityWallet: usdcVault,
+      });
+    };
+    await expectTX(
+      await rollover(new BN(1_000), sundialSaberWrapper),
+      'rollover into a Sundial of another reserve',
+    ).to.be.rejected;
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    const remainingMintAmount = sundialProfileWrapper
+      .getBorrowingPower()
+      .sub(sundialProfileWrapper.getTotalLoanValue())
+      .div(USDC_PRICE)
+      .div(WAD);
+    await expectTX(
+      await rollover(remainingMintAmount.add(LOAN_AMOUNT).addn(1_000)),
+      'rollover beyond the borrowing power',
+    ).to.be.rejected;
+
+    const ROLLOVER_AMOUNT = new BN(15_000);
+    const [ammLiquidityWallet] =
+      await sundialAmmWrapper.getLiquidityTokenSupplyAndBump();
+    const balance = async (wallet: PublicKey) =>
+      (await getTokenAccount(provider, wallet)).amount;
+    const ammLiquidityBefore = await balance(ammLiquidityWallet);
+    const userLiquidityBefore = await balance(usdcVault);
+    await addCheckers(
+      async () => {
+        await expectTX(await rollover(ROLLOVER_AMOUNT), 'rollover loan').to.be
+          .fulfilled;
+        await sundialProfileWrapper.reloadData();
+      },
+      checkMintAmountDiff(
+        sdk.provider,
+        async () =>
+          (await laterSundialUSDCWrapper.getPrincipleMintAndBump())[0],
+        ROLLOVER_AMOUNT,
+        'Check principal tokens minted for the new loan',
+      ),
+      checkTokenBalanceDiff(
+        sdk.provider,
+        async () =>
+          (await sundialUSDCWrapper.getLiquidityTokenSupplyAndBump())[0],
+        LOAN_AMOUNT,
+        'Check expiring loan repaid',
+      ),
+      checkSundialProfileNumOfLoanDiff(0),
+    );
+    const liquidityOut = ammLiquidityBefore.sub(
+      await balance(ammLiquidityWallet),
+    );
+    expect(
+      (await balance(usdcVault)).sub(userLiquidityBefore),
+    ).to.bignumber.eq(liquidityOut.sub(LOAN_AMOUNT));
+    expect(sundialProfileWrapper.getLoan(sundialUSDCWrapper.publicKey)).to.be
+      .undefined;
+    expect(
+      sundialProfileWrapper.getLoanAmount(laterSundialUSDCWrapper.publicKey),
+    ).to.bignumber.eq(ROLLOVER_AMOUNT);
+
+    await expectTX(
+      await rollover(
+        new BN(1_000),
+        sundialUSDCWrapper,
+        laterSundialUSDCWrapper,
+      ),
+      'rollover into an earlier Sundial',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.repaySundialLiquidity(
+        ROLLOVER_AMOUNT,
+        laterSundialUSDCWrapper,
+        usdcVault,
+      ),
+      'Repay rolled over loan',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    expect(sundialProfileWrapper.loans).deep.equal([]);
+  });
+
   it('Withdraw Collateral (half collateral)', async () => {
     const withdrawAmount = sundialProfileWrapper
       .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
@@ -1135,6 +1656,315 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Transfer Collateral between Sub Profiles', async () => {
+    const subProfileWrapper = sdk.sundialProfileWrapper;
+    await expectTX(
+      await subProfileWrapper.createSundialProfile(
+        sundialMarketBase.publicKey,
+        undefined,
+        1,
+      ),
+      'Create sundial sub profile',
+    ).to.be.fulfilled;
+    await subProfileWrapper.reloadData();
+    expect(subProfileWrapper.sundialProfileData.subAccountId).eq(1);
+    expect(subProfileWrapper.publicKey.equals(sundialProfileWrapper.publicKey))
+      .to.be.false;
+
+    const profiles = await sundialProfileWrapper.getSundialProfiles(
+      sundialMarketBase.publicKey,
+    );
+    expect(profiles.map(p => p.publicKey.toBase58())).deep.equal([
+      sundialProfileWrapper.publicKey.toBase58(),
+      subProfileWrapper.publicKey.toBase58(),
+    ]);
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    const collateralAmount = sundialProfileWrapper.getCollateralAmount(
+      sundialSerumCollateralWrapper.publicKey,
+    );
+    const transferAmount = collateralAmount.divn(10);
+
+    await expectTX(
+      await sundialProfileWrapper.transferSundialCollateral(
+        transferAmount,
+        sundialSerumCollateralWrapper,
+        sundialProfileWrapper.publicKey,
+      ),
+      'Transfer collateral to the same profile',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.transferSundialCollateral(
+        transferAmount,
+        sundialSerumCollateralWrapper,
+        subProfileWrapper.publicKey,
+      ),
+      'Transfer collateral to sub profile',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    await subProfileWrapper.reloadData();
+    expect(
+      sundialProfileWrapper
+        .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
+        .toString(),
+    ).eq(collateralAmount.sub(transferAmount).toString());
+    expect(
+      subProfileWrapper
+        .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
+        .toString(),
+    ).eq(transferAmount.toString());
+
+    await expectTX(
+      await subProfileWrapper.refreshSundialProfile(),
+      'RefreshSundialProfile',
+    ).to.be.fulfilled;
+    await expectTX(
+      await subProfileWrapper.transferSundialCollateral(
+        transferAmount.muln(2),
+        sundialSerumCollateralWrapper,
+        sundialProfileWrapper.publicKey,
+      ),
+      'Transfer collateral back from sub profile',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    await subProfileWrapper.reloadData();
+    expect(
+      sundialProfileWrapper
+        .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
+        .toString(),
+    ).eq(collateralAmount.toString());
+    expect(subProfileWrapper.collaterals).deep.equal([]);
+  });
+
+  it('Close Sundial Profile', async () => {
+    await expectTX(
+      await sundialProfileWrapper.closeSundialProfile(),
+      'Close sundial profile with collateral',
+    ).to.be.rejected;
+
+    const subProfileWrapper = sdk.sundialProfileWrapper;
+    subProfileWrapper.publicKey = (
+      await SundialProfileWrapper.getSundialProfileKey(
+        provider.wallet.publicKey,
+        sundialMarketBase.publicKey,
+        1,
+      )
+    )[0];
+    await subProfileWrapper.reloadData();
+    const receiver = Keypair.generate().publicKey;
+    await expectTX(
+      await subProfileWrapper.closeSundialProfile(receiver),
+      'Close empty sundial profile',
+    ).to.be.fulfilled;
+    expect(
+      await provider.connection.getAccountInfo(subProfileWrapper.publicKey),
+    ).to.be.null;
+    expect(await provider.connection.getBalance(receiver)).to.be.above(0);
+  });
+
+  it('Transfer Sundial Profile', async () => {
+    const subProfileWrapper = sdk.sundialProfileWrapper;
+    await expectTX(
+      await subProfileWrapper.createSundialProfile(
+        sundialMarketBase.publicKey,
+        undefined,
+        2,
+      ),
+      'Create sundial sub profile',
+    ).to.be.fulfilled;
+    await subProfileWrapper.reloadData();
+    const subProfile = subProfileWrapper.publicKey;
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    const collateralAmount = sundialProfileWrapper.getCollateralAmount(
+      sundialSerumCollateralWrapper.publicKey,
+    );
+    const transferAmount = collateralAmount.divn(10);
+    await expectTX(
+      await sundialProfileWrapper.transferSundialCollateral(
+        transferAmount,
+        sundialSerumCollateralWrapper,
+        subProfile,
+      ),
+      'Transfer collateral to sub profile',
+    ).to.be.fulfilled;
+
+    const newUserKP = Keypair.generate();
+    await expectTX(
+      new TransactionEnvelope(provider, [
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: newUserKP.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        }),
+      ]),
+      'Fund new user',
+    ).to.be.fulfilled;
+
+    const transferTx = await subProfileWrapper.transferSundialProfile(
+      newUserKP.publicKey,
+    );
+    transferTx.addSigners(newUserKP);
+    await expectTX(transferTx, 'Transfer sundial profile').to.be.fulfilled;
+    expect(await provider.connection.getAccountInfo(subProfile)).to.be.null;
+    await subProfileWrapper.reloadData();
+    expect(subProfileWrapper.sundialProfileData.user).eqAddress(
+      newUserKP.publicKey,
+    );
+    expect(subProfileWrapper.sundialProfileData.subAccountId).eq(0);
+    expect(
+      subProfileWrapper
+        .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
+        .toString(),
+    ).eq(transferAmount.toString());
+
+    const transferBackTx = await subProfileWrapper.transferSundialProfile(
+      provider.wallet.publicKey,
+      2,
+      newUserKP.publicKey,
+    );
+    transferBackTx.addSigners(newUserKP);
+    await expectTX(transferBackTx, 'Transfer sundial profile back').to.be
+      .fulfilled;
+    expect(subProfileWrapper.publicKey).eqAddress(subProfile);
+    await subProfileWrapper.reloadData();
+
+    await expectTX(
+      await subProfileWrapper.refreshSundialProfile(),
+      'RefreshSundialProfile',
+    ).to.be.fulfilled;
+    await expectTX(
+      await subProfileWrapper.transferSundialCollateral(
+        transferAmount,
+        sundialSerumCollateralWrapper,
+        sundialProfileWrapper.publicKey,
+      ),
+      'Transfer collateral back from sub profile',
+    ).to.be.fulfilled;
+    await expectTX(
+      await subProfileWrapper.closeSundialProfile(),
+      'Close sub profile',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+  });
+
+  it('Manage Sundial Profile with a delegate', async () => {
+    const delegateKP = Keypair.generate();
+    const delegate = delegateKP.publicKey;
+    const permissions = {
+      deposit: false,
+      repay: true,
+      withdraw: true,
+      mint: false,
+    };
+    const expiry = new BN(Math.floor(Date.now() / 1000) + 3600);
+    await expectTX(
+      await sundialProfileWrapper.initializeProfileDelegate(
+        delegate,
+        permissions,
+        expiry,
+      ),
+      'Initialize profile delegate',
+    ).to.be.fulfilled;
+
+    const transferredProfileWrapper = sdk.sundialProfileWrapper;
+    transferredProfileWrapper.publicKey = sundialProfileWrapper.publicKey;
+    await transferredProfileWrapper.reloadData();
+    expect(transferredProfileWrapper.sundialProfileData.delegateCount).eq(1);
+    await expectTX(
+      await transferredProfileWrapper.transferSundialProfile(
+        provider.wallet.publicKey,
+        3,
+      ),
+      'Transfer sundial profile with a delegate',
+    ).to.be.rejected;
+
+    await refreshProfile([sundialSerumCollateralWrapper, parsedSerumReserve]);
+    const collateralAmount = sundialProfileWrapper.getCollateralAmount(
+      sundialSerumCollateralWrapper.publicKey,
+    );
+    const withdrawAmount = collateralAmount.divn(10);
+    const delegateTx = async (txPromise: Promise<TransactionEnvelope>) => {
+      const tx = await txPromise;
+      tx.addSigners(delegateKP);
+      return tx;
+    };
+
+    const delegateWallet = await createTokenAccount(
+      provider,
+      sundialSerumCollateralWrapper.sundialCollateralData.collateralMint,
+      delegate,
+    );
+    await expectTX(
+      await delegateTx(
+        sundialProfileWrapper.withdrawSundialCollateral(
+          withdrawAmount,
+          sundialSerumCollateralWrapper,
+          delegateWallet,
+          delegate,
+        ),
+      ),
+      'Delegate withdraws to its own wallet',
+    ).to.be.rejected;
+
+    await expectTX(
+      await delegateTx(
+        sundialProfileWrapper.withdrawSundialCollateral(
+          withdrawAmount,
+          sundialSerumCollateralWrapper,
+          undefined,
+          delegate,
+        ),
+      ),
+      'Delegate withdraws to the user',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    expect(
+      sundialProfileWrapper
+        .getCollateralAmount(sundialSerumCollateralWrapper.publicKey)
+        .toString(),
+    ).eq(collateralAmount.sub(withdrawAmount).toString());
+
+    await expectTX(
+      await delegateTx(
+        sundialProfileWrapper.mintSundialLiquidityWithCollateral(
+          new BN(1),
+          sundialUSDCWrapper,
+          delegate,
+        ),
+      ),
+      'Delegate mints without the permission',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.changeProfileDelegate(
+        delegate,
+        permissions,
+        new BN(Math.floor(Date.now() / 1000) - 1),
+      ),
+      'Change profile delegate to an expired one',
+    ).to.be.rejected;
+
+    await expectTX(
+      await sundialProfileWrapper.closeProfileDelegate(delegate),
+      'Close profile delegate',
+    ).to.be.fulfilled;
+    await sundialProfileWrapper.reloadData();
+    expect(sundialProfileWrapper.sundialProfileData.delegateCount).eq(0);
+    await expectTX(
+      await delegateTx(
+        sundialProfileWrapper.withdrawSundialCollateral(
+          withdrawAmount,
+          sundialSerumCollateralWrapper,
+          undefined,
+          delegate,
+        ),
+      ),
+      'Revoked delegate withdraws',
+    ).to.be.rejected;
+  });
+
   it('Refresh Sundial Profile Fail, Sundial Collateral Stale', async () => {
     await sleep(5000);
     const refreshProfileTx =
@@ -1326,6 +2156,14 @@ describe('SundialCollateral', () => {
       [sundialSolCollateralWrapper, parsedSolReserve],
     );
 
+    // Liquidations stay open in a reduce only market.
+    await expectTX(
+      sdk.changeSundialMarketStatus(
+        sundialMarketBase.publicKey,
+        MARKET_STATUS_REDUCE_ONLY,
+      ),
+      'set the market reduce only',
+    ).to.be.fulfilled;
     await addCheckers(
       async () => {
         await expectTX(await updatePriceAndPrepareLiquidation(), 'liquidate').to
@@ -1350,6 +2188,13 @@ describe('SundialCollateral', () => {
         sundialSerumCollateralWrapper,
       ),
     );
+    await expectTX(
+      sdk.changeSundialMarketStatus(
+        sundialMarketBase.publicKey,
+        MARKET_STATUS_ACTIVE,
+      ),
+      'reactivate the market',
+    ).to.be.fulfilled;
 
     await mockOraclesWrapper.writePythPrice(usdcOracleKP, {
       price: USDC_PRICE,
@@ -1780,13 +2625,19 @@ describe('SundialCollateral', () => {
     const newLiquidationPenalty = 10;
     await addCheckers(
       async () => {
-        const changeTx = sundialSerumCollateralWrapper.changeConfig({
+        const changeTx = await sundialSerumCollateralWrapper.changeConfig({
           ...DEFAULT_SUNDIAL_COLLATERAL_CONFIG,
           ltv: newLTV,
           liquidationPenalty: newLiquidationPenalty,
           liquidationThreshold: newLiquidationThreshold,
         });
-        expectTX(changeTx).to.be.fulfilled;
+        await expectTX(changeTx, 'queue config change').to.be.fulfilled;
+        await expectTX(
+          await sdk.executeConfigChange(
+            sundialSerumCollateralWrapper.publicKey,
+          ),
+          'execute config change',
+        ).to.be.fulfilled;
         await refreshProfile(
           [sundialSerumCollateralWrapper, parsedSerumReserve],
           [sundialSolCollateralWrapper, parsedSolReserve],
@@ -1810,6 +2661,122 @@ describe('SundialCollateral', () => {
     );
   });
 
+  it('Ramp LTV and liquidation threshold to a new config', async () => {
+    const RAMP_DURATION = 60 * 60 * 24 * 7;
+    const prevConfig =
+      sundialSerumCollateralWrapper.sundialCollateralData
+        .sundialCollateralConfig;
+    await expectTX(
+      await sundialSerumCollateralWrapper.changeConfig(
+        DEFAULT_SUNDIAL_COLLATERAL_CONFIG,
+        undefined,
+        new BN(RAMP_DURATION),
+      ),
+      'queue ramped config change',
+    ).to.be.fulfilled;
+    await expectTX(
+      await sdk.executeConfigChange(sundialSerumCollateralWrapper.publicKey),
+      'execute ramped config change',
+    ).to.be.fulfilled;
+    await sundialSerumCollateralWrapper.reloadData();
+    const { sundialCollateralConfig, configRamp } =
+      sundialSerumCollateralWrapper.sundialCollateralData;
+    expect(sundialCollateralConfig.ltv.ltv).equal(
+      DEFAULT_SUNDIAL_COLLATERAL_CONFIG.ltv,
+    );
+    expect(configRamp.startLtv).equal(prevConfig.ltv.ltv);
+    expect(configRamp.startLiquidationThreshold).equal(
+      prevConfig.liquidationConfig.liquidationThreshold,
+    );
+    expect(
+      configRamp.endUnixTimestamp.sub(configRamp.startUnixTimestamp).toNumber(),
+    ).equal(RAMP_DURATION);
+
+    await refreshProfile(
+      [sundialSerumCollateralWrapper, parsedSerumReserve],
+      [sundialSolCollateralWrapper, parsedSolReserve],
+    );
+    const collateral = sundialProfileWrapper.getCollateral(
+      sundialSerumCollateralWrapper.publicKey,
+    );
+    invariant(collateral, 'Sundial Profile Collateral Not Exist');
+    expect(collateral.config.ltv.ltv).equal(prevConfig.ltv.ltv);
+    expect(collateral.config.liquidationConfig.liquidationThreshold).equal(
+      prevConfig.liquidationConfig.liquidationThreshold,
+    );
+  });
+
+  it('Cancel a config change held back by the delay', async () => {
+    const sundialMarket =
+      sundialSerumCollateralWrapper.sundialCollateralData.sundialMarket;
+    await expectTX(
+      sdk.changeConfigChangeDelay(sundialMarket, new BN(60 * 60)),
+      'raise config change delay',
+    ).to.be.fulfilled;
+    await expectTX(
+      sdk.changeConfigChangeDelay(sundialMarket, new BN(0)),
+      'lower config change delay',
+    ).to.be.rejected;
+    await expectTX(
+      sdk.changeConfigChangeDelay(sundialMarket, new BN(365 * 24 * 60 * 60)),
+      'config change delay over the maximum',
+    ).to.be.rejected;
+
+    const target = sundialSerumCollateralWrapper.publicKey;
+    await expectTX(
+      await sundialSerumCollateralWrapper.changeConfig(
+        DEFAULT_SUNDIAL_COLLATERAL_CONFIG,
+      ),
+      'queue config change',
+    ).to.be.fulfilled;
+    await expectTX(
+      await sdk.executeConfigChange(target),
+      'execute before the delay',
+    ).to.be.rejected;
+    await expectTX(await sdk.cancelConfigChange(target), 'cancel config change')
+      .to.be.fulfilled;
+
+    const [pendingConfigChange] = await sdk.getPendingConfigChangeAndBump(
+      target,
+    );
+    expect(await provider.connection.getAccountInfo(pendingConfigChange)).to.be
+      .null;
+  });
+
+  it('Change stale tolerances within bounds', async () => {
+    const sundialMarket =
+      sundialSerumCollateralWrapper.sundialCollateralData.sundialMarket;
+    await expectTX(
+      sdk.changeStaleTolerances(sundialMarket, {
+        collateralSlots: new BN(20),
+        profileSlots: new BN(10),
+        oracleSlots: new BN(20),
+      }),
+      'change market stale tolerances',
+    ).to.be.fulfilled;
+    await expectTX(
+      sdk.changeStaleTolerances(sundialMarket, {
+        collateralSlots: new BN(151),
+        profileSlots: new BN(10),
+        oracleSlots: new BN(20),
+      }),
+      'stale tolerance over the max',
+    ).to.be.rejected;
+
+    await expectTX(
+      sundialSerumCollateralWrapper.changeStaleTol(new BN(30)),
+      'override collateral stale tolerance',
+    ).to.be.fulfilled;
+    await sundialSerumCollateralWrapper.reloadData();
+    expect(
+      sundialSerumCollateralWrapper.sundialCollateralData.collateralStaleTol.toNumber(),
+    ).to.equal(30);
+    await expectTX(
+      sundialSerumCollateralWrapper.changeStaleTol(new BN(0)),
+      'unset collateral stale tolerance',
+    ).to.be.fulfilled;
+  });
+
   it('Should fail to deposit and mint with different lending market', async () => {
     const tempSundialMarket = await sdk.createSundialMarket();
     const createTx = await sundialProfileWrapper.createSundialProfile(
@@ -214,6 +214,69 @@ describe('Sundial Interacting with Port Reserve that has positive APY', () => {
     expect(amount.sub(yieldWallet.amount).toNumber()).gt(interestAccrue);
   });
 
+  let positionAmount: BN;
+  let positionClaimed: BN;
+  it('should claim accrued interest of a yield position before end', async () => {
+    const yieldAssocTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      (
+        await sundialWrapper.getYieldMintAndBump()
+      )[0],
+      provider.wallet.publicKey,
+    );
+    const yieldWallet = await getTokenAccount(provider, yieldAssocTokenAccount);
+    positionAmount = yieldWallet.amount.divn(2);
+
+    const createTx = await sundialWrapper.createYieldPosition();
+    await expectTX(createTx, 'create yield position').to.be.fulfilled;
+    const depositTx = await sundialWrapper.depositYieldPosition(
+      positionAmount,
+    );
+    await expectTX(depositTx, 'deposit yield position').to.be.fulfilled;
+
+    const beforeUserLiquidityWallet = await getTokenAccount(
+      provider,
+      liquidityVault,
+    );
+    const claimTx = await sundialWrapper.claimYieldPosition({
+      reserve: parsedReserve,
+      userLiquidityWallet: liquidityVault,
+    });
+    await expectTX(claimTx, 'claim yield position').to.be.fulfilled;
+    const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
+    positionClaimed = userLiquidityWallet.amount.sub(
+      beforeUserLiquidityWallet.amount,
+    );
+    expect(positionClaimed).to.bignumber.gt(new BN(0));
+
+    // Taking half out and putting it back pays back the interest claimed for that half.
+    const withdrawAmount = positionAmount.divn(2);
+    const withdrawTx = (
+      await sundialWrapper.withdrawYieldPosition({
+        amount: withdrawAmount,
+        userLiquidityWallet: liquidityVault,
+      })
+    ).combine(await sundialWrapper.depositYieldPosition(withdrawAmount));
+    await expectTX(withdrawTx, 'withdraw and deposit yield position').to.be
+      .fulfilled;
+    const paidBack = userLiquidityWallet.amount.sub(
+      (await getTokenAccount(provider, liquidityVault)).amount,
+    );
+    expect(paidBack).to.bignumber.equal(
+      positionClaimed
+        .mul(withdrawAmount)
+        .add(positionAmount.subn(1))
+        .div(positionAmount),
+    );
+    positionClaimed = positionClaimed.sub(paidBack);
+    const position = await sdk.programs.Sundial.account.yieldPosition.fetch(
+      (await sundialWrapper.getYieldPositionKeyAndBump())[0],
+    );
+    expect(position.yieldTokenAmount).to.bignumber.equal(positionAmount);
+    expect(position.claimedLiquidityAmount).to.bignumber.equal(positionClaimed);
+  });
+
   it('should fail minting principle and yield tokens', async () => {
     await sleep(8000);
     const depositTx = await sundialWrapper.mintPrincipleAndYieldTokens({
@@ -224,6 +287,8 @@ describe('Sundial Interacting with Port Reserve that has positive APY', () => {
     await expectTX(depositTx, 'fail mint principle and yield').to.be.rejected;
   });
 
+  let yieldInterest: BN;
+  let yieldRedeemed: BN;
   it('should redeem yield token with interest', async () => {
     const principleAssocTokenAccount = await Token.getAssociatedTokenAddress(
       ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -259,10 +324,13 @@ describe('Sundial Interacting with Port Reserve that has positive APY', () => {
     );
     const beforeRedeemAmount = INITIAL_MINT_AMOUNT.sub(amount)
       .sub(portDepositAmount)
-      .add(portBorrowAmount);
+      .add(portBorrowAmount)
+      .add(positionClaimed);
     expect(beforeUserLiquidityWallet.amount).to.bignumber.equal(
       beforeRedeemAmount,
     );
+    // Subtract 1 since when we calculate the principal token amount we use flooring.
+    yieldInterest = amount.sub(new BN(1)).sub(principleWallet.amount).sub(fee);
     const redeemYieldTokenTx = await sundialWrapper.redeemYieldTokens({
       amount: yieldWallet.amount,
       userLiquidityWallet: liquidityVault,
@@ -270,12 +338,30 @@ describe('Sundial Interacting with Port Reserve that has positive APY', () => {
     await expectTX(redeemYieldTokenTx, 'redeem yield token').to.be.fulfilled;
     const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
     expect(userLiquidityWallet.amount).to.bignumber.gt(beforeRedeemAmount);
-    expect(
-      userLiquidityWallet.amount.sub(beforeRedeemAmount),
-    ).to.bignumber.equal(
-      // Subtract 1 since when we calculate the principal token amount we use flooring.
-      amount.sub(new BN(1)).sub(principleWallet.amount).sub(fee),
+    yieldRedeemed = userLiquidityWallet.amount.sub(beforeRedeemAmount);
+    // Half of the yield tokens are in the position, which has not settled yet.
+    expect(yieldRedeemed.sub(yieldInterest.divn(2)).abs()).to.bignumber.lte(
+      new BN(2),
+    );
+  });
+
+  it('should settle yield position after end', async () => {
+    const beforeUserLiquidityWallet = await getTokenAccount(
+      provider,
+      liquidityVault,
+    );
+    const claimTx = await sundialWrapper.claimYieldPosition({
+      reserve: parsedReserve,
+      userLiquidityWallet: liquidityVault,
+    });
+    await expectTX(claimTx, 'settle yield position').to.be.fulfilled;
+    const userLiquidityWallet = await getTokenAccount(provider, liquidityVault);
+    const positionSettled = userLiquidityWallet.amount.sub(
+      beforeUserLiquidityWallet.amount,
     );
+    // The position ends up with the same interest as the yield tokens redeemed after end.
+    const totalYield = positionClaimed.add(positionSettled).add(yieldRedeemed);
+    expect(totalYield.sub(yieldInterest).abs()).to.bignumber.lte(new BN(2));
   });
 
   it('should redeem principal token without interest', async () => {